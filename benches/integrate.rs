@@ -0,0 +1,62 @@
+//! 积分阶段的基准测试：大量互不接触的物体，每次 step 的开销主要来自速度和位置的积分
+//!
+//! 运行 `cargo bench --bench integrate`，开启 `simd` 特性时 `batch::integrate` 使用 SSE/NEON
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use p2d::{
+    body::Body,
+    broad_phase::BroadPhaseKind,
+    math::Real,
+    shape::Circle,
+    vec2::{batch, Vec2},
+    world::World,
+};
+
+const COUNTS: [usize; 3] = [1_000, 5_000, 20_000];
+
+// 排成网格、朝不同方向匀速运动的小球，彼此之间不会接触
+fn scattered_world(count: usize) -> World {
+    let mut world = World::new(1. / 60., 10, 0.);
+    world.set_broad_phase_kind(BroadPhaseKind::SpatialHash { cell_size: 4. });
+    for i in 0..count {
+        let position = Vec2::new((i % 100) as Real * 10., (i / 100) as Real * 10.);
+        let mut body = Body::new_circle(Circle::new(0.5), position, 0.5);
+        body.set_velocity(Vec2::new((i % 7) as Real - 3., (i % 5) as Real - 2.));
+        world.add_body(body);
+    }
+    world
+}
+
+fn world_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_step");
+    for count in COUNTS {
+        let mut world = scattered_world(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| world.step())
+        });
+    }
+    group.finish();
+}
+
+fn batch_integrate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_integrate");
+    for count in COUNTS {
+        let velocities: Vec<Vec2> = (0..count).map(|i| Vec2::splat(i as Real)).collect();
+        let mut positions = vec![Vec2::ZERO; count];
+        group.bench_with_input(BenchmarkId::new("scalar", count), &count, |b, _| {
+            b.iter(|| {
+                let dt = black_box(1. / 60.);
+                for (p, v) in positions.iter_mut().zip(&velocities) {
+                    *p += *v * dt;
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, _| {
+            b.iter(|| batch::integrate(&mut positions, &velocities, black_box(1. / 60.)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, world_step, batch_integrate);
+criterion_main!(benches);
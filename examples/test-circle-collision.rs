@@ -8,10 +8,13 @@ use font_kit::family_name::FamilyName;
 use font_kit::properties::Properties;
 use font_kit::source::SystemSource;
 use p2d::body::Body;
-use p2d::shape::{Circle, AABB};
+use p2d::clock::PhysicsClock;
+use p2d::math::Real;
+use p2d::shape::{Capsule, Circle, ShapeType, AABB};
+use p2d::transform::Transform2D;
 use p2d::vec2::Vec2;
 use p2d::world::World;
-use raqote::{DrawOptions, DrawTarget, PathBuilder, Point, SolidSource, Source};
+use raqote::{DrawOptions, DrawTarget, PathBuilder, Point, SolidSource, Source, StrokeStyle};
 
 use softbuffer::{Context, Surface};
 use winit::dpi::PhysicalSize;
@@ -20,27 +23,84 @@ use winit::event_loop::{ControlFlow, EventLoop};
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::WindowBuilder;
 
-fn draw_ball(dt: &mut DrawTarget, pos: Vec2, radius: f32, solid_source: SolidSource) {
+// raqote 使用 f32，开启 f64 特性时 Real 为 f64，需要在绘制时转换
+#[allow(clippy::unnecessary_cast)]
+fn to_f32(v: Real) -> f32 {
+    v as f32
+}
+
+// winit 的坐标是 f64，不开启 f64 特性时需要转换
+#[allow(clippy::unnecessary_cast)]
+fn to_real(v: f64) -> Real {
+    v as Real
+}
+
+fn draw_ball(dt: &mut DrawTarget, pos: Vec2, radius: Real, solid_source: SolidSource) {
     let mut pb = PathBuilder::new();
-    pb.arc(pos.x, pos.y, radius, 0., 2. * PI);
+    pb.arc(to_f32(pos.x), to_f32(pos.y), to_f32(radius), 0., 2. * PI);
     let path = pb.finish();
     dt.fill(&path, &Source::Solid(solid_source), &DrawOptions::new());
 }
 
 fn draw_aabb(dt: &mut DrawTarget, min: Vec2, max: Vec2, pos: Vec2, solid_source: SolidSource) {
     let mut pb = PathBuilder::new();
-    let half_extend = (max - min) / 2.;
-    let left_top = pos - half_extend;
-    pb.rect(
-        left_top.x,
-        left_top.y,
-        half_extend.x * 2.,
-        half_extend.y * 2.,
-    );
+    let size = max - min;
+    let left_top = pos + min;
+    pb.rect(to_f32(left_top.x), to_f32(left_top.y), to_f32(size.x), to_f32(size.y));
+    let path = pb.finish();
+    dt.fill(&path, &Source::Solid(solid_source), &DrawOptions::new());
+}
+
+fn draw_polygon(dt: &mut DrawTarget, transform: Transform2D, vertices: &[Vec2], solid_source: SolidSource) {
+    let mut pb = PathBuilder::new();
+    for (i, v) in vertices.iter().enumerate() {
+        let p = transform.transform_point(*v);
+        if i == 0 {
+            pb.move_to(to_f32(p.x), to_f32(p.y));
+        } else {
+            pb.line_to(to_f32(p.x), to_f32(p.y));
+        }
+    }
+    pb.close();
+    let path = pb.finish();
+    dt.fill(&path, &Source::Solid(solid_source), &DrawOptions::new());
+}
+
+fn draw_capsule(dt: &mut DrawTarget, transform: Transform2D, capsule: &Capsule, solid_source: SolidSource) {
+    let a = transform.transform_point(capsule.a());
+    let b = transform.transform_point(capsule.b());
+    draw_ball(dt, a, capsule.radius(), solid_source);
+    draw_ball(dt, b, capsule.radius(), solid_source);
+    let side = (b - a).try_normalize().unwrap_or(Vec2::ZERO).perp() * capsule.radius();
+    let mut pb = PathBuilder::new();
+    pb.move_to(to_f32(a.x + side.x), to_f32(a.y + side.y));
+    pb.line_to(to_f32(b.x + side.x), to_f32(b.y + side.y));
+    pb.line_to(to_f32(b.x - side.x), to_f32(b.y - side.y));
+    pb.line_to(to_f32(a.x - side.x), to_f32(a.y - side.y));
+    pb.close();
     let path = pb.finish();
     dt.fill(&path, &Source::Solid(solid_source), &DrawOptions::new());
 }
 
+fn draw_polyline(dt: &mut DrawTarget, transform: Transform2D, points: &[Vec2], solid_source: SolidSource) {
+    let mut pb = PathBuilder::new();
+    for (i, p) in points.iter().enumerate() {
+        let p = transform.transform_point(*p);
+        if i == 0 {
+            pb.move_to(to_f32(p.x), to_f32(p.y));
+        } else {
+            pb.line_to(to_f32(p.x), to_f32(p.y));
+        }
+    }
+    let path = pb.finish();
+    dt.stroke(
+        &path,
+        &Source::Solid(solid_source),
+        &StrokeStyle::default(),
+        &DrawOptions::new(),
+    );
+}
+
 fn render_fps(dt: &mut DrawTarget, fps: i32) {
     let font = SystemSource::new()
         .select_best_match(&[FamilyName::SansSerif], &Properties::new())
@@ -69,20 +129,50 @@ fn render(dt: &mut DrawTarget, world: &World) {
         } else {
             SolidSource::from_unpremultiplied_argb(0xff, 0, 0xff, 0)
         };
-        match inner_body.shape() {
-            p2d::shape::ShapeType::Circle(ref circle) => {
-                draw_ball(dt, inner_body.position(), circle.radius(), solid_source);
+        draw_shape(dt, &inner_body.shape(), inner_body.transform(), solid_source);
+    }
+}
+
+fn draw_shape(dt: &mut DrawTarget, shape: &ShapeType, transform: Transform2D, solid_source: SolidSource) {
+    match shape {
+        ShapeType::Circle(circle) => {
+            draw_ball(dt, transform.translation, circle.radius(), solid_source);
+        }
+        ShapeType::AABB(aabb) => {
+            draw_aabb(
+                dt,
+                aabb.min(),
+                aabb.max(),
+                transform.translation,
+                solid_source,
+            );
+        }
+        ShapeType::Polygon(polygon) => {
+            draw_polygon(dt, transform, polygon.vertices(), solid_source);
+        }
+        ShapeType::Triangle(triangle) => {
+            draw_polygon(dt, transform, triangle.vertices(), solid_source);
+        }
+        ShapeType::Capsule(capsule) => {
+            draw_capsule(dt, transform, capsule, solid_source);
+        }
+        ShapeType::Segment(segment) => {
+            draw_polyline(dt, transform, &[segment.a(), segment.b()], solid_source);
+        }
+        ShapeType::Chain(chain) => {
+            let mut points = chain.points().to_vec();
+            if chain.is_loop() {
+                points.push(points[0]);
             }
-            p2d::shape::ShapeType::AABB(ref aabb) => {
-                draw_aabb(
-                    dt,
-                    aabb.min(),
-                    aabb.max(),
-                    inner_body.position(),
-                    solid_source,
-                );
+            draw_polyline(dt, transform, &points, solid_source);
+        }
+        ShapeType::Compound(compound) => {
+            for fixture in compound.fixtures() {
+                draw_shape(dt, fixture.shape(), transform * fixture.transform(), solid_source);
             }
         }
+        ShapeType::Custom(_) => {}
+        ShapeType::Point(_) => draw_ball(dt, transform.translation, 2., solid_source),
     }
 }
 
@@ -91,6 +181,7 @@ const WINDOW_HEIGHT: i32 = 600;
 
 fn render_loop(world: &mut World) {
     let mut frames = VecDeque::with_capacity(100);
+    let mut clock = PhysicsClock::new();
     let mut last_frame_timestamp = std::time::SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
@@ -117,14 +208,13 @@ fn render_loop(world: &mut World) {
             WindowEvent::CloseRequested => {
                 elwt.exit();
             }
-            WindowEvent::KeyboardInput { event, .. } => match event.physical_key {
-                PhysicalKey::Code(KeyCode::Escape) => {
-                    elwt.exit();
-                }
-                _ => {}
-            },
+            WindowEvent::KeyboardInput { event, .. }
+                if event.physical_key == PhysicalKey::Code(KeyCode::Escape) =>
+            {
+                elwt.exit();
+            }
             WindowEvent::CursorMoved { position, .. } => {
-                mouse_position = Some(Vec2::new(position.x as f32, position.y as f32));
+                mouse_position = Some(Vec2::new(to_real(position.x), to_real(position.y)));
             }
             WindowEvent::MouseInput { button, state, .. } => {
                 if button == MouseButton::Left && state == winit::event::ElementState::Released {
@@ -136,7 +226,7 @@ fn render_loop(world: &mut World) {
                 {
                     let pos = mouse_position.unwrap();
                     world.add_body(Body::new_aabb(
-                        AABB::new(Vec2::new(0., 0.), Vec2::new(60., 60.)),
+                        AABB::from_half_extents(Vec2::new(30., 30.)),
                         pos,
                         1.0,
                     ));
@@ -172,7 +262,7 @@ fn render_loop(world: &mut World) {
                 let mut buffer = surface.buffer_mut().unwrap();
 
                 // make some painting
-                world.step();
+                clock.advance(world, delta as Real / 1000.);
                 render(&mut dt, world);
                 render_fps(&mut dt, avg_fps as i32);
 
@@ -194,11 +284,15 @@ fn main() {
     let mut world = World::new(dt, 20, 1.0);
     // add ground
     let ground_height = 20;
-    let ground_aabb = AABB::new(
-        Vec2::new(0., (WINDOW_HEIGHT - ground_height) as f32),
-        Vec2::new(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32),
+    let ground_aabb = AABB::from_half_extents(Vec2::new(
+        WINDOW_WIDTH as Real / 2.,
+        ground_height as Real / 2.,
+    ));
+    let ground_position = Vec2::new(
+        WINDOW_WIDTH as Real / 2.,
+        (WINDOW_HEIGHT - ground_height / 2) as Real,
     );
-    let mut groud = Body::new_aabb(ground_aabb, ground_aabb.center(), 0.5);
+    let mut groud = Body::new_aabb(ground_aabb, ground_position, 0.5);
     groud.make_static();
     world.add_body(groud);
     render_loop(&mut world);
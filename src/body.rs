@@ -1,60 +1,268 @@
-use crate::{shape::{Circle, Shape, ShapeType, AABB}, vec2::Vec2};
+use std::rc::Rc;
+
+use crate::{
+    inspect::{Property, PropertyError, PropertyValue},
+    math::Real,
+    rot::Rot,
+    shape::{
+        valid_scale_factor, Capsule, Chain, Circle, Compound, CustomShape, Point, Polygon, Ray,
+        Segment, Shape, ShapeKind, ShapeType, Triangle, AABB,
+    },
+    transform::Transform2D,
+    vec2::Vec2,
+};
 
 pub struct Body {
     shape: ShapeType,
     position: Vec2,
     velocity: Vec2,
-    restitution: f32,
+    restitution: Real,
     force: Vec2,
-    mass: f32,
-    inverse_mass: f32,
+    mass: Real,
+    inverse_mass: Real,
+    rotation: Rot,
+    angular_velocity: Real,
+    inverse_inertia: Real,
 
-    pub(crate) static_fraction: f32,
-    pub(crate) dynamic_fraction: f32,
+    pub(crate) static_fraction: Real,
+    pub(crate) dynamic_fraction: Real,
+    pub(crate) read_only: bool,
+    sensor: bool,
+    ghost: bool,
+    occluder: bool,
+    report_contacts: bool,
+    report_sensor_overlaps: bool,
+    surface_id: u32,
+    update_interval: u32,
+    pub(crate) update_phase: u32,
+    velocity_callback: Option<Box<VelocityCallback>>,
+    surface_normal: Option<Rc<SurfaceNormal>>,
+    acceleration: Option<Rc<Acceleration>>,
+    // 上一次 step 开始时的变换，用于渲染插值
+    previous_transform: Transform2D,
+    interpolate: bool,
 }
 
+/// 速度回调，参数为物体当前的速度和 step 的时间间隔，返回新的速度
+pub type VelocityCallback = dyn FnMut(Vec2, Real) -> Vec2;
+
+/// 表面法线函数，参数为与之碰撞的物体的位置和碰撞检测得到的表面法线，返回实际使用的表面法线
+pub type SurfaceNormal = dyn Fn(Vec2, Vec2) -> Vec2;
+
+/// 加速度函数，参数为物体当前的速度和角速度，返回额外的加速度
+pub type Acceleration = dyn Fn(Vec2, Real) -> Vec2;
+
 impl Body {
     #[inline]
-    pub fn new_circle(shape: Circle, position: Vec2, restitution: f32) -> Body {
-        let mass = shape.mass();
-        let inverse_mass = shape.mass_recip();
-        Body {
-            shape: ShapeType::Circle(shape),
-            position,
-            restitution,
-            velocity: Vec2::ZERO,
-            force: Vec2::ZERO,
-            mass,
-            inverse_mass,
-            static_fraction: 0.1,
-            dynamic_fraction: 0.05,
+    pub fn new_circle(shape: Circle, position: Vec2, restitution: Real) -> Body {
+        Body::new(ShapeType::Circle(shape), shape.mass(), position, restitution)
+            .with_inertia(shape.inertia())
+    }
+
+    /// 点不会转动，质量不大于 0 的点是静态的
+    #[inline]
+    pub fn new_point(shape: Point, position: Vec2, restitution: Real) -> Body {
+        let mut body = Body::new(ShapeType::Point(shape), shape.mass(), position, restitution);
+        if shape.mass() <= 0. {
+            body.make_static();
+        }
+        body
+    }
+
+    /// AABB 始终与坐标轴对齐，不会转动
+    #[inline]
+    pub fn new_aabb(shape: AABB, position: Vec2, restitution: Real) -> Body {
+        Body::new(ShapeType::AABB(shape), shape.mass(), position, restitution)
+    }
+
+    #[inline]
+    pub fn new_polygon(shape: Polygon, position: Vec2, restitution: Real) -> Body {
+        Body::new(ShapeType::Polygon(shape), shape.mass(), position, restitution)
+            .with_inertia(shape.inertia())
+    }
+
+    #[inline]
+    pub fn new_triangle(shape: Triangle, position: Vec2, restitution: Real) -> Body {
+        Body::new(ShapeType::Triangle(shape), shape.mass(), position, restitution)
+            .with_inertia(shape.inertia())
+    }
+
+    #[inline]
+    pub fn new_capsule(shape: Capsule, position: Vec2, restitution: Real) -> Body {
+        Body::new(ShapeType::Capsule(shape), shape.mass(), position, restitution)
+            .with_inertia(shape.inertia())
+    }
+
+    /// 线段没有质量，物体总是静态的
+    #[inline]
+    pub fn new_segment(shape: Segment, position: Vec2, restitution: Real) -> Body {
+        let mut body = Body::new(ShapeType::Segment(shape), 0., position, restitution);
+        body.make_static();
+        body
+    }
+
+    /// 折线没有质量，物体总是静态的
+    #[inline]
+    pub fn new_chain(shape: Chain, position: Vec2, restitution: Real) -> Body {
+        let mut body = Body::new(ShapeType::Chain(shape), 0., position, restitution);
+        body.make_static();
+        body
+    }
+
+    /// 子形状的总质量为 0（例如只由线段和折线组成）时，物体是静态的
+    #[inline]
+    pub fn new_compound(shape: Compound, position: Vec2, restitution: Real) -> Body {
+        let (mass, inertia) = (shape.mass(), shape.inertia());
+        let mut body = Body::new(ShapeType::Compound(shape), mass, position, restitution);
+        if mass > 0. {
+            body.inverse_inertia = inertia.recip();
+        } else {
+            body.make_static();
         }
+        body
     }
 
+    /// 使用自定义形状的物体，质量为 0 时物体是静态的
     #[inline]
-    pub fn new_aabb(shape: AABB, position: Vec2, restitution: f32) -> Body {
-        let mass = shape.mass();
-        let inverse_mass = shape.mass_recip();
+    pub fn new_custom(shape: Rc<dyn CustomShape>, position: Vec2, restitution: Real) -> Body {
+        let (mass, inertia) = (shape.mass(), shape.inertia());
+        let mut body = Body::new(ShapeType::Custom(shape), mass, position, restitution);
+        if mass > 0. {
+            body.inverse_inertia = if inertia > 0. { inertia.recip() } else { 0. };
+        } else {
+            body.make_static();
+        }
+        body
+    }
+
+    /// 复合形状的一个子形状在碰撞检测时使用的临时物体，只有形状和变换
+    pub(crate) fn proxy(shape: ShapeType, transform: Transform2D) -> Body {
+        let mut body = Body::new(shape, 0., transform.translation, 0.);
+        body.rotation = transform.rotation;
+        body
+    }
+
+    // 质量不是正数（例如密度或者半径为 0 的形状）的物体是静态的
+    fn new(shape: ShapeType, mass: Real, position: Vec2, restitution: Real) -> Body {
+        let mass = if mass > 0. { mass } else { 0. };
         Body {
-            shape: ShapeType::AABB(shape),
+            shape,
             position,
             restitution,
             velocity: Vec2::ZERO,
             force: Vec2::ZERO,
             mass,
-            inverse_mass,
+            inverse_mass: if mass > 0. { mass.recip() } else { 0. },
+            rotation: Rot::IDENTITY,
+            angular_velocity: 0.,
+            inverse_inertia: 0.,
             static_fraction: 0.1,
             dynamic_fraction: 0.05,
+            read_only: false,
+            sensor: false,
+            ghost: false,
+            occluder: false,
+            report_contacts: false,
+            report_sensor_overlaps: false,
+            surface_id: 0,
+            update_interval: 1,
+            update_phase: 0,
+            velocity_callback: None,
+            surface_normal: None,
+            acceleration: None,
+            previous_transform: Transform2D::from_translation(position),
+            interpolate: true,
         }
     }
 
+    // 设置转动惯量，静态物体和转动惯量不是正数的物体不能转动
+    fn with_inertia(mut self, inertia: Real) -> Body {
+        if self.inverse_mass > 0. && inertia > 0. {
+            self.inverse_inertia = inertia.recip();
+        }
+        self
+    }
+
+    /// 复制物体的状态，速度回调无法复制，新的物体没有速度回调
+    pub(crate) fn fork(&self) -> Body {
+        Body {
+            shape: self.shape.clone(),
+            position: self.position,
+            velocity: self.velocity,
+            restitution: self.restitution,
+            force: self.force,
+            mass: self.mass,
+            inverse_mass: self.inverse_mass,
+            rotation: self.rotation,
+            angular_velocity: self.angular_velocity,
+            inverse_inertia: self.inverse_inertia,
+            static_fraction: self.static_fraction,
+            dynamic_fraction: self.dynamic_fraction,
+            read_only: self.read_only,
+            sensor: self.sensor,
+            ghost: self.ghost,
+            occluder: self.occluder,
+            report_contacts: self.report_contacts,
+            report_sensor_overlaps: self.report_sensor_overlaps,
+            surface_id: self.surface_id,
+            update_interval: self.update_interval,
+            update_phase: self.update_phase,
+            velocity_callback: None,
+            surface_normal: self.surface_normal.clone(),
+            acceleration: self.acceleration.clone(),
+            previous_transform: self.previous_transform,
+            interpolate: self.interpolate,
+        }
+    }
+
+    /// 两个物体除形状、位置和运动状态之外的属性是否完全相同，相同的静态物体才能合并。
+    /// 速度回调无法比较，有速度回调的物体与任何物体都不相同
+    pub(crate) fn same_properties(&self, other: &Body) -> bool {
+        self.is_static() == other.is_static()
+            && self.restitution == other.restitution
+            && self.static_fraction == other.static_fraction
+            && self.dynamic_fraction == other.dynamic_fraction
+            && self.read_only == other.read_only
+            && self.sensor == other.sensor
+            && self.ghost == other.ghost
+            && self.occluder == other.occluder
+            && self.report_contacts == other.report_contacts
+            && self.report_sensor_overlaps == other.report_sensor_overlaps
+            && self.surface_id == other.surface_id
+            && self.update_interval == other.update_interval
+            && self.interpolate == other.interpolate
+            && self.velocity_callback.is_none()
+            && other.velocity_callback.is_none()
+            && same_rc(&self.surface_normal, &other.surface_normal)
+            && same_rc(&self.acceleration, &other.acceleration)
+    }
+
+    /// 复制物体除形状和位置之外的属性，得到覆盖 `min` 到 `max` 的 AABB 物体，用于合并静态物体
+    pub(crate) fn with_aabb(&self, min: Vec2, max: Vec2) -> Body {
+        let position = (min + max) / 2.;
+        let mut body = self.fork();
+        body.shape = ShapeType::AABB(AABB::from_half_extents((max - min) / 2.));
+        body.position = position;
+        body.rotation = Rot::IDENTITY;
+        body.previous_transform = Transform2D::from_translation(position);
+        body
+    }
+
+    /// 复制物体并放到 `position`，渲染插值从新的位置开始
+    pub(crate) fn fork_at(&self, position: Vec2) -> Body {
+        let mut body = self.fork();
+        body.position = position;
+        body.previous_transform = body.transform();
+        body
+    }
+
     #[inline(always)]
-    pub fn restitution(&self) -> f32 {
+    pub fn restitution(&self) -> Real {
         self.restitution
     }
 
     #[inline(always)]
-    pub fn mass(&self) -> f32 {
+    pub fn mass(&self) -> Real {
         self.mass
     }
 
@@ -63,13 +271,16 @@ impl Body {
         self.position
     }
 
+    /// 设置物体的位置，只读的物体会忽略该操作
     #[inline(always)]
     pub fn set_position(&mut self, pos: Vec2) {
-        self.position = pos;
+        if !self.read_only {
+            self.position = pos;
+        }
     }
 
     #[inline(always)]
-    pub fn inverse_mass(&self) -> f32 {
+    pub fn inverse_mass(&self) -> Real {
         self.inverse_mass
     }
 
@@ -78,9 +289,95 @@ impl Body {
         self.velocity
     }
 
+    /// 设置物体的速度，只读的物体会忽略该操作
     #[inline(always)]
     pub fn set_velocity(&mut self, v: Vec2) {
-        self.velocity = v;
+        if !self.read_only {
+            self.velocity = v;
+        }
+    }
+
+    /// 物体的局部坐标系到世界坐标系的变换，形状都定义在局部坐标系中，
+    /// 原点为物体的质心
+    #[inline(always)]
+    pub fn transform(&self) -> Transform2D {
+        Transform2D::new(self.position, self.rotation)
+    }
+
+    #[inline(always)]
+    pub fn rotation(&self) -> Rot {
+        self.rotation
+    }
+
+    /// 设置物体的朝向，只读的物体会忽略该操作
+    #[inline(always)]
+    pub fn set_rotation(&mut self, rotation: Rot) {
+        if !self.read_only {
+            self.rotation = rotation;
+        }
+    }
+
+    /// 渲染插值得到的变换，`alpha` 通常为 `PhysicsClock::alpha`。
+    /// 在上一次 step 开始时和当前的变换之间插值，关闭了插值的物体直接返回当前的变换
+    pub fn interpolated_transform(&self, alpha: Real) -> Transform2D {
+        if !self.interpolate {
+            return self.transform();
+        }
+        let previous = self.previous_transform;
+        let delta = (previous.rotation.inverse() * self.rotation).angle();
+        Transform2D::new(
+            previous.translation.lerp(self.position, alpha),
+            previous.rotation * Rot::from_angle(delta * alpha),
+        )
+    }
+
+    /// 是否对物体做渲染插值，默认开启。
+    /// 经常被直接设置位置的物体（例如传送点、由动画驱动的物体）可以关闭插值，只读的物体会忽略该操作
+    pub fn set_interpolation(&mut self, interpolate: bool) {
+        if self.read_only {
+            return;
+        }
+        self.interpolate = interpolate;
+    }
+
+    pub fn interpolates(&self) -> bool {
+        self.interpolate
+    }
+
+    /// 标记物体在这一帧瞬移，把插值的起点设为当前的变换，
+    /// 下一次 step 之前渲染插值直接得到当前位置，不会把瞬移画成一段移动。只读的物体会忽略该操作
+    pub fn snap(&mut self) {
+        if self.read_only {
+            return;
+        }
+        self.previous_transform = self.transform();
+    }
+
+    /// 角速度，逆时针为正，单位为弧度每秒
+    #[inline(always)]
+    pub fn angular_velocity(&self) -> Real {
+        self.angular_velocity
+    }
+
+    /// 设置物体的角速度，只读的物体和不能转动的物体会忽略该操作
+    #[inline(always)]
+    pub fn set_angular_velocity(&mut self, w: Real) {
+        if !self.read_only && self.inverse_inertia > 0. {
+            self.angular_velocity = w;
+        }
+    }
+
+    // 设置由外部驱动的静态物体的角速度，只用于计算接触点的速度，只读的物体会忽略该操作
+    pub(crate) fn set_kinematic_angular_velocity(&mut self, w: Real) {
+        if !self.read_only {
+            self.angular_velocity = w;
+        }
+    }
+
+    /// 转动惯量的倒数，不能转动的物体为 0
+    #[inline(always)]
+    pub fn inverse_inertia(&self) -> Real {
+        self.inverse_inertia
     }
 
     #[inline(always)]
@@ -89,7 +386,11 @@ impl Body {
     }
 
     pub fn shape(&self) -> ShapeType {
-        self.shape
+        self.shape.clone()
+    }
+
+    pub(crate) fn shape_kind(&self) -> ShapeKind {
+        self.shape.kind()
     }
 
     #[inline(always)]
@@ -107,14 +408,474 @@ impl Body {
         self.velocity += impulse * self.inverse_mass;
     }
 
+    /// 设置物体的质量，同时按比例重新计算转动惯量。
+    /// 静态物体设置正的质量后变为非静态物体，质量不大于 0 时等同于 `make_static`。
+    /// 只读的物体和只能是静态的线段、折线物体会忽略正的质量
+    pub fn set_mass(&mut self, mass: Real) {
+        if self.read_only {
+            return;
+        }
+        if mass <= 0. || matches!(self.shape, ShapeType::Segment(_) | ShapeType::Chain(_)) {
+            self.make_static();
+            return;
+        }
+        self.mass = mass;
+        self.inverse_mass = mass.recip();
+        // AABB 不会转动
+        let inertia = match self.shape {
+            ShapeType::AABB(_) => 0.,
+            _ => self.shape.inertia_for_mass(mass),
+        };
+        self.inverse_inertia = if inertia > 0. { inertia.recip() } else { 0. };
+    }
+
+    /// 设置形状的密度，非静态物体按新的密度重新计算质量和转动惯量，参见 `set_mass`。
+    /// 静态物体仍然是静态的
+    pub fn set_density(&mut self, density: Real) {
+        if self.read_only {
+            return;
+        }
+        self.shape.set_density(density);
+        if !self.is_static() {
+            self.set_mass(self.shape.mass());
+        }
+    }
+
+    /// 替换物体的形状，例如下蹲时换成更矮的胶囊体。非静态物体按新的形状重新计算质量和转动惯量，
+    /// 换成线段或折线时物体变为静态物体。只读的物体会忽略该操作
+    pub fn set_shape(&mut self, shape: ShapeType) {
+        if self.read_only {
+            return;
+        }
+        self.shape = shape;
+        if matches!(self.shape, ShapeType::Segment(_) | ShapeType::Chain(_)) {
+            self.make_static();
+        } else if !self.is_static() {
+            self.set_mass(self.shape.mass());
+        }
+    }
+
+    /// 以物体的位置为中心把形状缩放 `factor` 倍，非静态物体按缩放后的形状重新计算质量和转动惯量。
+    /// 接触点在下一次 step 时按新的形状重新检测，只读的物体和不是正的有限数的 `factor` 会被忽略
+    pub fn scale_shape(&mut self, factor: Real) {
+        if self.read_only || !valid_scale_factor(factor) {
+            return;
+        }
+        self.shape.scale(factor);
+        if !self.is_static() {
+            self.set_mass(self.shape.mass());
+        }
+    }
+
+    /// 把物体变为静态物体，只读的物体会忽略该操作
     #[inline(always)]
     pub fn make_static(&mut self) {
+        if self.read_only {
+            return;
+        }
         self.mass = 0.;
         self.inverse_mass = 0.;
+        self.inverse_inertia = 0.;
+        self.angular_velocity = 0.;
     }
 
     #[inline(always)]
     pub fn is_static(&self) -> bool {
         self.mass.abs() < 0.00001
     }
-}
\ No newline at end of file
+
+    /// 是否是只读的物体，`World::bake_statics` 之后的静态物体是只读的。
+    /// 只读的物体会被 `World::fork` 出的多个 world 共享，修改位置、质量、形状以及
+    /// 传感器、幽灵、遮挡物、事件上报、表面材质、插值、更新间隔、速度回调和加速度等属性的函数都会被忽略
+    #[inline(always)]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// 是否是传感器，传感器只检测重叠，不参与碰撞求解
+    #[inline(always)]
+    pub fn is_sensor(&self) -> bool {
+        self.sensor
+    }
+
+    #[inline(always)]
+    pub fn set_sensor(&mut self, sensor: bool) {
+        if self.read_only {
+            return;
+        }
+        self.sensor = sensor;
+    }
+
+    /// 是否是幽灵物体，幽灵物体完全不参与碰撞求解，也不会与普通物体产生接触事件，
+    /// 但仍然可以被射线检测、区域查询和传感器检测到，适合只用来装饰的可交互物体
+    #[inline(always)]
+    pub fn is_ghost(&self) -> bool {
+        self.ghost
+    }
+
+    #[inline(always)]
+    pub fn set_ghost(&mut self, ghost: bool) {
+        if self.read_only {
+            return;
+        }
+        self.ghost = ghost;
+    }
+
+    /// 是否是遮挡物，遮挡物只阻挡射线检测和视线查询，不与任何物体（包括传感器）做碰撞检测，
+    /// 适合玻璃窗、雾墙这类挡住视线但可以穿过的物体
+    #[inline(always)]
+    pub fn is_occluder(&self) -> bool {
+        self.occluder
+    }
+
+    #[inline(always)]
+    pub fn set_occluder(&mut self, occluder: bool) {
+        if self.read_only {
+            return;
+        }
+        self.occluder = occluder;
+    }
+
+    /// 是否为该物体生成接触开始/结束事件，默认不生成
+    #[inline(always)]
+    pub fn report_contacts(&self) -> bool {
+        self.report_contacts
+    }
+
+    #[inline(always)]
+    pub fn set_report_contacts(&mut self, report: bool) {
+        if self.read_only {
+            return;
+        }
+        self.report_contacts = report;
+    }
+
+    /// 是否为该物体生成传感器进入/离开事件，默认不生成
+    #[inline(always)]
+    pub fn report_sensor_overlaps(&self) -> bool {
+        self.report_sensor_overlaps
+    }
+
+    #[inline(always)]
+    pub fn set_report_sensor_overlaps(&mut self, report: bool) {
+        if self.read_only {
+            return;
+        }
+        self.report_sensor_overlaps = report;
+    }
+
+    /// 表面材质的编号，默认为 0，会随碰撞冲击事件一起上报，用来选择碰撞音效
+    #[inline(always)]
+    pub fn surface_id(&self) -> u32 {
+        self.surface_id
+    }
+
+    #[inline(always)]
+    pub fn set_surface_id(&mut self, surface_id: u32) {
+        if self.read_only {
+            return;
+        }
+        self.surface_id = surface_id;
+    }
+
+    /// 每隔多少次 step 更新一次物体的位置和速度，默认为 1，即每次 step 都更新。
+    /// 大于 1 时物体每 `interval` 次 step 更新一次，并使用 `interval` 倍的时间间隔，
+    /// 适合远处不重要的物体。物体进入 world 的激活区域时恢复为每次 step 都更新，
+    /// 参见 `World::add_activation_region`
+    #[inline(always)]
+    pub fn update_interval(&self) -> u32 {
+        self.update_interval
+    }
+
+    #[inline(always)]
+    pub fn set_update_interval(&mut self, interval: u32) {
+        if self.read_only {
+            return;
+        }
+        self.update_interval = interval.max(1);
+    }
+
+    /// 点 `point` 是否在物体内部
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        self.shape.contains_point(&self.transform(), point)
+    }
+
+    /// 设置速度回调，每次 step 完成积分之后、下一次碰撞检测之前调用，
+    /// 可以用来限制角色的水平速度或者实现自定义的阻力，只读的物体会忽略该操作
+    pub fn set_velocity_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(Vec2, Real) -> Vec2 + 'static,
+    {
+        if self.read_only {
+            return;
+        }
+        self.velocity_callback = Some(Box::new(callback));
+    }
+
+    pub fn clear_velocity_callback(&mut self) {
+        if self.read_only {
+            return;
+        }
+        self.velocity_callback = None;
+    }
+
+    pub(crate) fn run_velocity_callback(&mut self, dt: Real) {
+        if let Some(callback) = &mut self.velocity_callback {
+            let v = callback(self.velocity, dt);
+            self.set_velocity(v);
+        }
+    }
+
+    /// 设置随速度变化的加速度，例如旋转的球受到的马格努斯力或者机翼的升力。
+    /// 每次积分时用物体当前的速度和角速度计算，与重力和 `apply_force` 施加的力叠加，只读的物体会忽略该操作
+    pub fn set_acceleration<F>(&mut self, acceleration: F)
+    where
+        F: Fn(Vec2, Real) -> Vec2 + 'static,
+    {
+        if self.read_only {
+            return;
+        }
+        self.acceleration = Some(Rc::new(acceleration));
+    }
+
+    pub fn clear_acceleration(&mut self) {
+        if self.read_only {
+            return;
+        }
+        self.acceleration = None;
+    }
+
+    pub(crate) fn acceleration_fn(&self) -> Option<Rc<Acceleration>> {
+        self.acceleration.clone()
+    }
+
+    // 加速度函数在当前状态下的值，没有设置时为 0
+    pub(crate) fn acceleration(&self) -> Vec2 {
+        match &self.acceleration {
+            Some(f) => f(self.velocity, self.angular_velocity),
+            None => Vec2::ZERO,
+        }
+    }
+
+    /// 设置表面法线函数，只对静态物体生效，只读的物体会忽略该操作。
+    /// 碰撞检测得到的法线会被替换成该函数的返回值，可以让一组方块拼成的斜坡或者山丘
+    /// 表现得像光滑的曲面，法线都是指向物体外侧的
+    pub fn set_surface_normal<F>(&mut self, normal: F)
+    where
+        F: Fn(Vec2, Vec2) -> Vec2 + 'static,
+    {
+        if self.read_only {
+            return;
+        }
+        self.surface_normal = Some(Rc::new(normal));
+    }
+
+    pub fn clear_surface_normal(&mut self) {
+        if self.read_only {
+            return;
+        }
+        self.surface_normal = None;
+    }
+
+    // 与位于 `other` 的物体碰撞时实际使用的表面法线，返回值无法归一化时使用原来的法线
+    pub(crate) fn surface_normal(&self, other: Vec2, normal: Vec2) -> Vec2 {
+        match &self.surface_normal {
+            Some(f) if self.is_static() => f(other, normal).try_normalize().unwrap_or(normal),
+            _ => normal,
+        }
+    }
+
+    /// 射线检测，射线为 `origin + dir * t`，`t` 在 `[0, max_t]` 之间
+    /// 返回射线第一次进入物体时的 `t`，起点在物体内部时返回 0
+    pub(crate) fn ray_cast(&self, origin: Vec2, dir: Vec2, max_t: Real) -> Option<Real> {
+        self.shape.raycast(&self.transform(), Ray::new(origin, dir, max_t))
+    }
+
+    /// 与 `ray_cast` 相同，同时返回被击中的子形状
+    pub(crate) fn ray_cast_sub_shape(&self, ray: Ray) -> Option<(Real, usize)> {
+        self.shape.raycast_sub_shape(&self.transform(), ray)
+    }
+
+    /// 物体在世界坐标系下的包围盒，返回 `(min, max)`
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        self.shape.bounds(&self.transform())
+    }
+
+    /// 复合形状的每个子形状在世界坐标系下的包围盒，其他形状返回 `None`
+    pub(crate) fn fixture_bounds(&self) -> Option<Vec<(Vec2, Vec2)>> {
+        let ShapeType::Compound(compound) = &self.shape else {
+            return None;
+        };
+        let transform = self.transform();
+        let bounds = compound
+            .fixtures()
+            .iter()
+            .map(|f| f.shape().bounds(&(transform * f.transform())))
+            .collect();
+        Some(bounds)
+    }
+
+    /// 物体的所有属性，供编辑器、调试面板等外部工具显示，不需要匹配 `ShapeType`。
+    /// 形状的属性在前，参见 `ShapeType::properties`，`rotation` 的单位为弧度
+    pub fn properties(&self) -> Vec<Property> {
+        let mut properties = self.shape.properties();
+        properties.extend([
+            Property::new("position", PropertyValue::Vec2(self.position), true),
+            Property::new("rotation", PropertyValue::Real(self.rotation.angle()), true),
+            Property::new("velocity", PropertyValue::Vec2(self.velocity), true),
+            Property::new("angular_velocity", PropertyValue::Real(self.angular_velocity), true),
+            Property::new("mass", PropertyValue::Real(self.mass), true),
+            Property::new("restitution", PropertyValue::Real(self.restitution), true),
+            Property::new("static_friction", PropertyValue::Real(self.static_fraction), true),
+            Property::new("dynamic_friction", PropertyValue::Real(self.dynamic_fraction), true),
+            Property::new("sensor", PropertyValue::Bool(self.sensor), true),
+        ]);
+        properties
+    }
+
+    /// 按名字修改 `properties` 中可编辑的属性，`mass` 的效果与 `set_mass` 相同
+    pub fn set_property(&mut self, name: &str, value: PropertyValue) -> Result<(), PropertyError> {
+        if !self.properties().iter().any(|p| p.name == name) {
+            return Err(PropertyError::Unknown);
+        }
+        if self.read_only {
+            return Err(PropertyError::BodyReadOnly);
+        }
+        match (name, value) {
+            ("position", PropertyValue::Vec2(v)) => self.position = v,
+            ("rotation", PropertyValue::Real(v)) => self.rotation = Rot::from_angle(v),
+            ("velocity", PropertyValue::Vec2(v)) => self.velocity = v,
+            ("angular_velocity", PropertyValue::Real(v)) => self.set_angular_velocity(v),
+            ("mass", PropertyValue::Real(v)) => self.set_mass(v),
+            ("restitution", PropertyValue::Real(v)) => self.restitution = v,
+            ("static_friction", PropertyValue::Real(v)) => self.static_fraction = v,
+            ("dynamic_friction", PropertyValue::Real(v)) => self.dynamic_fraction = v,
+            ("sensor", PropertyValue::Bool(v)) => self.sensor = v,
+            (
+                "position" | "rotation" | "velocity" | "angular_velocity" | "mass" | "restitution"
+                | "static_friction" | "dynamic_friction" | "sensor",
+                _,
+            ) => return Err(PropertyError::TypeMismatch),
+            _ => return Err(PropertyError::ReadOnly),
+        }
+        Ok(())
+    }
+}
+
+// 两个可选的共享函数是否都为空，或者指向同一个函数
+fn same_rc<T: ?Sized>(a: &Option<Rc<T>>, b: &Option<Rc<T>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * set_mass(Real)
+    #[test]
+    fn set_mass_should_keep_static_only_shapes_static() {
+        let segment = Segment::new(Vec2::new(-1., 0.), Vec2::new(1., 0.));
+        let chain = Chain::new(&[Vec2::new(-1., 0.), Vec2::new(0., 1.), Vec2::new(1., 0.)]);
+        for mut body in [
+            Body::new_segment(segment, Vec2::ZERO, 0.5),
+            Body::new_chain(chain, Vec2::ZERO, 0.5),
+        ] {
+            body.set_mass(5.);
+            assert!(body.is_static());
+            assert_eq!(body.inverse_mass(), 0.);
+            body.set_density(2.);
+            assert!(body.is_static());
+        }
+
+        let mut body = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+        body.make_static();
+        body.set_mass(5.);
+        assert!(!body.is_static());
+        assert!(body.inverse_inertia() > 0.);
+    }
+
+    /// Test cases for:
+    /// * set_shape(ShapeType)
+    #[test]
+    fn set_shape_should_recompute_mass() {
+        let mut body = Body::new_capsule(
+            Capsule::new(Vec2::new(0., -1.), Vec2::new(0., 1.), 0.5),
+            Vec2::ZERO,
+            0.5,
+        );
+        let crouched = Capsule::new(Vec2::new(0., -0.25), Vec2::new(0., 0.25), 0.5);
+        body.set_shape(ShapeType::Capsule(crouched));
+        assert!((body.mass() - crouched.mass()).abs() < 0.0001);
+        assert!((body.inverse_inertia() * crouched.inertia() - 1.).abs() < 0.0001);
+        assert!((body.bounds().1.y - 0.75).abs() < 0.0001);
+
+        body.read_only = true;
+        body.set_shape(ShapeType::Circle(Circle::new(3.)));
+        assert!(matches!(body.shape(), ShapeType::Capsule(_)));
+        body.read_only = false;
+
+        body.set_shape(ShapeType::Segment(Segment::new(Vec2::new(-1., 0.), Vec2::new(1., 0.))));
+        assert!(body.is_static());
+        let mut ground = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+        ground.make_static();
+        ground.set_shape(ShapeType::Circle(Circle::new(2.)));
+        assert!(ground.is_static());
+    }
+
+    /// Test cases for:
+    /// * scale_shape(Real)
+    #[test]
+    fn scale_shape_should_ignore_invalid_factor() {
+        let mut body = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+        let mass = body.mass();
+        for factor in [0., -2., Real::NAN, Real::INFINITY] {
+            body.scale_shape(factor);
+            assert_eq!(body.mass(), mass);
+            assert_eq!(body.bounds(), (Vec2::new(-1., -1.), Vec2::new(1., 1.)));
+        }
+        body.scale_shape(2.);
+        assert!((body.mass() - 4. * mass).abs() < 1e-3);
+    }
+
+    /// Test cases for:
+    /// * properties()
+    /// * set_property(&str, PropertyValue)
+    #[test]
+    fn properties_should_describe_and_edit_body() {
+        let mut body = Body::new_circle(Circle::new(2.), Vec2::new(1., 2.), 0.5);
+        let properties = body.properties();
+        let find = |name: &str| properties.iter().find(|p| p.name == name).unwrap().clone();
+        assert_eq!(find("kind").value, PropertyValue::Kind(ShapeKind::Circle));
+        assert_eq!(find("radius").value, PropertyValue::Real(2.));
+        assert!(!find("radius").editable);
+        assert_eq!(find("position").value, PropertyValue::Vec2(Vec2::new(1., 2.)));
+        assert!(find("position").editable);
+
+        body.set_property("velocity", PropertyValue::Vec2(Vec2::new(3., 0.))).unwrap();
+        assert_eq!(body.velocity(), Vec2::new(3., 0.));
+        body.set_property("mass", PropertyValue::Real(4.)).unwrap();
+        assert_eq!(body.inverse_mass(), 0.25);
+        assert!(body.inverse_inertia() > 0.);
+        body.set_property("sensor", PropertyValue::Bool(true)).unwrap();
+        assert!(body.is_sensor());
+
+        let error = |body: &mut Body, name: &str, value| body.set_property(name, value).unwrap_err();
+        assert_eq!(error(&mut body, "radius", PropertyValue::Real(1.)), PropertyError::ReadOnly);
+        assert_eq!(error(&mut body, "size", PropertyValue::Real(1.)), PropertyError::Unknown);
+        assert_eq!(
+            error(&mut body, "mass", PropertyValue::Bool(true)),
+            PropertyError::TypeMismatch
+        );
+        body.read_only = true;
+        assert_eq!(
+            error(&mut body, "mass", PropertyValue::Real(1.)),
+            PropertyError::BodyReadOnly
+        );
+        assert_eq!(body.inverse_mass(), 0.25);
+    }
+}
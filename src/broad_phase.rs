@@ -0,0 +1,1412 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use crate::{body::Body, bvh::DynamicBvh, math::Real, vec2::Vec2};
+
+/// 粗测阶段的数据结构，维护所有代理的包围盒并生成可能发生碰撞的代理对。
+/// `World` 中每个非只读的物体对应一个代理，代理的编号就是物体在 world 中的下标，
+/// 每次 step 和每次查询之前 `World` 会把物体包围盒的变化同步过来。
+/// 代理的包围盒向外扩大了 `World::broad_phase_margin`，物体移出扩大后的包围盒时才会调用 `update`。
+///
+/// `pairs`、`query` 和 `ray_query` 可以给出包围盒实际并不重叠的代理，`World` 会再次检查，
+/// 但不能遗漏包围盒重叠的代理
+pub trait BroadPhase: BroadPhaseClone {
+    /// 加入编号为 `proxy` 的代理，包围盒为 `[min, max]`
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2);
+
+    /// 移除编号为 `proxy` 的代理
+    fn remove(&mut self, proxy: usize);
+
+    /// 代理的包围盒变为 `[min, max]`
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2);
+
+    /// 把包围盒重叠的代理对追加到 `pairs` 中，对的顺序和重复都没有关系
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>);
+
+    /// 对包围盒与 `[min, max]` 重叠的每个代理调用 `callback`，`callback` 返回 false 时停止
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool);
+
+    /// `query` 是否只访问查询范围附近的代理，开销远小于遍历所有代理。
+    /// 为 true 时 `World` 只为移动过的代理调用 `query` 增量更新代理对，
+    /// 否则每次 step 都调用 `pairs` 重新生成所有代理对。默认为 false
+    fn has_local_query(&self) -> bool {
+        false
+    }
+
+    /// 对包围盒被射线 `origin + dir * t`（`t` 在 `[0, max_t]` 之间）穿过的每个代理调用 `callback`，
+    /// `callback` 返回 false 时停止。默认查询射线所在线段的包围盒
+    fn ray_query(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        max_t: Real,
+        callback: &mut dyn FnMut(usize) -> bool,
+    ) {
+        let end = origin + dir * max_t;
+        // `dir` 的分量为 0 而 `max_t` 为无穷大时 `end` 的分量为 NaN，`min`、`max` 会忽略 NaN
+        self.query(origin.min(end), origin.max(end), callback);
+    }
+}
+
+/// 复制装箱的 `BroadPhase`，实现了 `Clone` 的类型自动实现该 trait，`World::fork` 时使用
+pub trait BroadPhaseClone {
+    fn clone_box(&self) -> Box<dyn BroadPhase>;
+}
+
+impl<T: BroadPhase + Clone + 'static> BroadPhaseClone for T {
+    fn clone_box(&self) -> Box<dyn BroadPhase> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn BroadPhase> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// 内置的粗测方式，用 `create` 创建对应的 `BroadPhase`
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BroadPhaseKind {
+    /// 所有代理两两比较，物体很少时最快
+    #[default]
+    BruteForce,
+    /// 把包围盒放入边长为 `cell_size` 的网格，只比较位于同一格子中的代理。
+    /// 格子的边长通常取常见物体尺寸的 1 到 2 倍
+    SpatialHash { cell_size: Real },
+    /// 沿 x 轴排序包围盒，只比较 x 方向上区间重叠的代理。
+    /// 排序结果在 step 之间保留，物体移动不多时重新排序几乎是线性的，适合横向铺开的场景
+    SweepAndPrune,
+    /// 增量更新的包围盒层次树，同时用于加速射线检测和区域查询。
+    /// 树中保存向外扩大 `margin` 的包围盒，物体移动没有超出时不需要修改树
+    DynamicTree { margin: Real },
+    /// 松散四叉树，每个代理按尺寸放入边长不小于自身的最深一层格子，格子的松散边界向外扩大半个边长。
+    /// 适合物体尺寸相差很大、成簇分布的场景，`max_depth` 为最大的层数，通常取 8 左右
+    Quadtree { max_depth: u32 },
+}
+
+impl BroadPhaseKind {
+    pub fn create(self) -> Box<dyn BroadPhase> {
+        match self {
+            BroadPhaseKind::BruteForce => Box::new(BruteForce::new()),
+            BroadPhaseKind::SpatialHash { cell_size } => Box::new(SpatialHash::new(cell_size)),
+            BroadPhaseKind::SweepAndPrune => Box::new(SweepAndPrune::new()),
+            BroadPhaseKind::DynamicTree { margin } => Box::new(DynamicTree::new(margin)),
+            BroadPhaseKind::Quadtree { max_depth } => Box::new(Quadtree::new(max_depth)),
+        }
+    }
+}
+
+// 粗测阶段的代理：`(代理编号, 包围盒 min, 包围盒 max)`
+type Proxy = (usize, Vec2, Vec2);
+
+// 网格中格子的坐标
+type Cell = (i64, i64);
+
+// 按编号保存代理的包围盒
+#[derive(Clone, Default)]
+struct ProxySlots {
+    bounds: Vec<Option<(Vec2, Vec2)>>,
+}
+
+impl ProxySlots {
+    fn set(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        if proxy >= self.bounds.len() {
+            self.bounds.resize(proxy + 1, None);
+        }
+        self.bounds[proxy] = Some((min, max));
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        if let Some(bounds) = self.bounds.get_mut(proxy) {
+            *bounds = None;
+        }
+    }
+
+    fn get(&self, proxy: usize) -> Option<(Vec2, Vec2)> {
+        self.bounds.get(proxy).copied().flatten()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Proxy> + '_ {
+        self.bounds
+            .iter()
+            .enumerate()
+            .filter_map(|(i, bounds)| bounds.map(|(min, max)| (i, min, max)))
+    }
+
+    fn list(&self) -> Vec<Proxy> {
+        self.iter().collect()
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        let region = (usize::MAX, min, max);
+        for proxy in self.iter() {
+            if overlaps(&proxy, &region) && !callback(proxy.0) {
+                return;
+            }
+        }
+    }
+}
+
+/// 所有代理两两比较，参见 `BroadPhaseKind::BruteForce`
+#[derive(Clone, Default)]
+pub struct BruteForce {
+    proxies: ProxySlots,
+}
+
+impl BruteForce {
+    pub fn new() -> BruteForce {
+        BruteForce::default()
+    }
+}
+
+impl BroadPhase for BruteForce {
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        self.proxies.set(proxy, min, max);
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        self.proxies.remove(proxy);
+    }
+
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        self.proxies.set(proxy, min, max);
+    }
+
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+        brute_force_pairs(&self.proxies.list(), pairs);
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        self.proxies.query(min, max, callback);
+    }
+}
+
+/// 均匀网格，参见 `BroadPhaseKind::SpatialHash`。
+/// 每个代理保存在它的包围盒覆盖的所有格子中，`insert`、`remove` 和 `update` 只修改变化的格子，
+/// `query` 只检查与查询范围重叠的格子
+#[derive(Clone)]
+pub struct SpatialHash {
+    cell_size: Real,
+    proxies: ProxySlots,
+    // 每个非空格子中的代理
+    cells: HashMap<Cell, Vec<usize>>,
+    // 覆盖的格子太多的代理，单独与所有代理比较
+    oversized: Vec<usize>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: Real) -> SpatialHash {
+        SpatialHash {
+            cell_size,
+            proxies: ProxySlots::default(),
+            cells: HashMap::new(),
+            oversized: vec![],
+        }
+    }
+
+    fn valid_cell_size(&self) -> bool {
+        self.cell_size.is_finite() && self.cell_size > 0.
+    }
+
+    fn cell(&self, v: Real) -> i64 {
+        grid_cell(v, self.cell_size)
+    }
+
+    // `[min, max]` 覆盖的格子范围 `(x0, x1, y0, y1)`，包含两端
+    fn cells(&self, min: Vec2, max: Vec2) -> (i64, i64, i64, i64) {
+        (
+            self.cell(min.x),
+            self.cell(max.x),
+            self.cell(min.y),
+            self.cell(max.y),
+        )
+    }
+
+    // 代理覆盖的格子范围，覆盖的格子超过 `MAX_PROXY_CELLS` 个或者格子的边长不合法时返回 None
+    fn cell_range(&self, min: Vec2, max: Vec2) -> Option<(i64, i64, i64, i64)> {
+        if !self.valid_cell_size() {
+            return None;
+        }
+        let (x0, x1, y0, y1) = self.cells(min, max);
+        ((x1 - x0 + 1).saturating_mul(y1 - y0 + 1) <= MAX_PROXY_CELLS).then_some((x0, x1, y0, y1))
+    }
+
+    fn add_to_cells(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        match self.cell_range(min, max) {
+            Some((x0, x1, y0, y1)) => {
+                for x in x0..=x1 {
+                    for y in y0..=y1 {
+                        self.cells.entry((x, y)).or_default().push(proxy);
+                    }
+                }
+            }
+            None => self.oversized.push(proxy),
+        }
+    }
+
+    fn remove_from_cells(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        match self.cell_range(min, max) {
+            Some((x0, x1, y0, y1)) => {
+                for x in x0..=x1 {
+                    for y in y0..=y1 {
+                        remove_from_bucket(&mut self.cells, (x, y), proxy);
+                    }
+                }
+            }
+            None => self.oversized.retain(|&p| p != proxy),
+        }
+    }
+
+    fn proxy(&self, proxy: usize) -> Proxy {
+        let (min, max) = self.proxies.get(proxy).expect("proxy in grid must exist");
+        (proxy, min, max)
+    }
+}
+
+impl BroadPhase for SpatialHash {
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        if let Some((old_min, old_max)) = self.proxies.get(proxy) {
+            self.remove_from_cells(proxy, old_min, old_max);
+        }
+        self.proxies.set(proxy, min, max);
+        self.add_to_cells(proxy, min, max);
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        if let Some((min, max)) = self.proxies.get(proxy) {
+            self.remove_from_cells(proxy, min, max);
+            self.proxies.remove(proxy);
+        }
+    }
+
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        match self.proxies.get(proxy) {
+            // 覆盖的格子没有变化时只更新包围盒
+            Some((old_min, old_max))
+                if self.cell_range(old_min, old_max) == self.cell_range(min, max) =>
+            {
+                self.proxies.set(proxy, min, max);
+            }
+            _ => self.insert(proxy, min, max),
+        }
+    }
+
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+        for bucket in self.cells.values() {
+            for (n, &a) in bucket.iter().enumerate() {
+                let a = self.proxy(a);
+                for &b in &bucket[n + 1..] {
+                    push_pair(&a, &self.proxy(b), pairs);
+                }
+            }
+        }
+        for &a in &self.oversized {
+            let a = self.proxy(a);
+            for b in self.proxies.iter() {
+                // 两个大物体之间只比较一次
+                if b.0 != a.0 && !(b.0 < a.0 && self.cell_range(b.1, b.2).is_none()) {
+                    push_pair(&a, &b, pairs);
+                }
+            }
+        }
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        let region = (usize::MAX, min, max);
+        for &p in &self.oversized {
+            if overlaps(&self.proxy(p), &region) && !callback(p) {
+                return;
+            }
+        }
+        if !self.valid_cell_size() {
+            return;
+        }
+        let (x0, x1, y0, y1) = self.cells(min, max);
+        // 覆盖多个格子的代理只在它与查询范围重叠的第一个格子中报告一次
+        let mut visit = |cell: Cell, bucket: &Vec<usize>| {
+            for &p in bucket {
+                let proxy = self.proxy(p);
+                let first = (self.cell(proxy.1.x).max(x0), self.cell(proxy.1.y).max(y0));
+                if first == cell && overlaps(&proxy, &region) && !callback(p) {
+                    return false;
+                }
+            }
+            true
+        };
+        // 查询范围内的格子比非空的格子还多时，直接遍历所有非空的格子
+        if (x1 - x0 + 1).saturating_mul(y1 - y0 + 1) > self.cells.len() as i64 {
+            for (&(x, y), bucket) in &self.cells {
+                if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) && !visit((x, y), bucket) {
+                    return;
+                }
+            }
+        } else {
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    if let Some(bucket) = self.cells.get(&(x, y)) {
+                        if !visit((x, y), bucket) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_local_query(&self) -> bool {
+        true
+    }
+}
+
+/// 沿 x 轴排序的扫描，参见 `BroadPhaseKind::SweepAndPrune`
+#[derive(Clone, Default)]
+pub struct SweepAndPrune {
+    proxies: ProxySlots,
+    // 上一次按包围盒 min.x 排序的代理编号
+    sorted: Vec<usize>,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> SweepAndPrune {
+        SweepAndPrune::default()
+    }
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        if self.proxies.get(proxy).is_none() {
+            self.sorted.push(proxy);
+        }
+        self.proxies.set(proxy, min, max);
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        if self.proxies.get(proxy).is_some() {
+            self.sorted.retain(|&p| p != proxy);
+        }
+        self.proxies.remove(proxy);
+    }
+
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        self.insert(proxy, min, max);
+    }
+
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+        let bounds: Vec<Proxy> = self
+            .sorted
+            .iter()
+            .map(|&p| {
+                let (min, max) = self.proxies.get(p).expect("sorted proxy must exist");
+                (p, min, max)
+            })
+            .collect();
+        // 插入排序，上一次的顺序基本有序时接近线性
+        let mut order: Vec<usize> = (0..bounds.len()).collect();
+        for k in 1..order.len() {
+            let current = order[k];
+            let key = bounds[current].1.x;
+            let mut m = k;
+            while m > 0 && bounds[order[m - 1]].1.x > key {
+                order[m] = order[m - 1];
+                m -= 1;
+            }
+            order[m] = current;
+        }
+        for (k, &a) in order.iter().enumerate() {
+            let max_x = bounds[a].2.x;
+            for &b in &order[k + 1..] {
+                if bounds[b].1.x > max_x {
+                    break;
+                }
+                push_pair(&bounds[a], &bounds[b], pairs);
+            }
+        }
+        self.sorted = order.iter().map(|&k| bounds[k].0).collect();
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        self.proxies.query(min, max, callback);
+    }
+}
+
+/// 增量更新的包围盒层次树，参见 `BroadPhaseKind::DynamicTree`
+#[derive(Clone)]
+pub struct DynamicTree {
+    tree: DynamicBvh,
+    proxies: ProxySlots,
+    // 每个代理在树中的叶子节点
+    leaves: Vec<Option<usize>>,
+}
+
+impl DynamicTree {
+    pub fn new(margin: Real) -> DynamicTree {
+        DynamicTree {
+            tree: DynamicBvh::new(margin),
+            proxies: ProxySlots::default(),
+            leaves: vec![],
+        }
+    }
+}
+
+impl BroadPhase for DynamicTree {
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        if self.proxies.get(proxy).is_some() {
+            self.update(proxy, min, max);
+            return;
+        }
+        self.proxies.set(proxy, min, max);
+        if proxy >= self.leaves.len() {
+            self.leaves.resize(proxy + 1, None);
+        }
+        self.leaves[proxy] = Some(self.tree.insert(proxy, min, max));
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        self.proxies.remove(proxy);
+        if let Some(leaf) = self.leaves.get_mut(proxy).and_then(Option::take) {
+            self.tree.remove(leaf);
+        }
+    }
+
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        match self.leaves.get(proxy).copied().flatten() {
+            Some(leaf) => {
+                self.proxies.set(proxy, min, max);
+                self.tree.update(leaf, min, max);
+            }
+            None => self.insert(proxy, min, max),
+        }
+    }
+
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+        for proxy in self.proxies.iter() {
+            // 每一对代理只从编号较小的一侧生成一次
+            self.tree.query(proxy.1, proxy.2, |other| {
+                if other > proxy.0 {
+                    if let Some((min, max)) = self.proxies.get(other) {
+                        push_pair(&proxy, &(other, min, max), pairs);
+                    }
+                }
+                true
+            });
+        }
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        self.tree.query(min, max, callback);
+    }
+
+    fn has_local_query(&self) -> bool {
+        true
+    }
+
+    fn ray_query(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        max_t: Real,
+        callback: &mut dyn FnMut(usize) -> bool,
+    ) {
+        self.tree.ray_query(origin, dir, max_t, callback);
+    }
+}
+
+/// 松散四叉树，参见 `BroadPhaseKind::Quadtree`。
+/// 每一层只在哈希表中保存非空的格子，树可以覆盖任意大的范围。代理放在能容纳它的最深一层中
+/// 包含它的中心的格子里，格子的松散边界一定包含代理的包围盒，代理只在换到另一个格子时才需要修改树。
+/// 出现比第 0 层的格子还大的代理时，整棵树按新的尺寸重建
+#[derive(Clone)]
+pub struct Quadtree {
+    max_depth: u32,
+    proxies: ProxySlots,
+    // 第 0 层格子的边长，不小于任何代理的尺寸
+    root_size: Real,
+    // 每一层非空的格子中的代理
+    levels: Vec<HashMap<Cell, Vec<usize>>>,
+    // 每个代理所在的层和格子
+    nodes: Vec<Option<(usize, Cell)>>,
+}
+
+impl Quadtree {
+    pub fn new(max_depth: u32) -> Quadtree {
+        Quadtree {
+            max_depth,
+            proxies: ProxySlots::default(),
+            root_size: 0.,
+            levels: vec![HashMap::new(); max_depth as usize + 1],
+            nodes: vec![],
+        }
+    }
+
+    // 第 `depth` 层格子的边长
+    fn cell_size(&self, depth: usize) -> Real {
+        self.root_size / (2. as Real).powi(depth as i32)
+    }
+
+    // 包围盒为 `[min, max]` 的代理所在的层和格子
+    fn node(&self, min: Vec2, max: Vec2) -> (usize, Cell) {
+        let extent = (max - min).max_element();
+        let mut depth = 0;
+        while depth < self.max_depth as usize && self.cell_size(depth + 1) >= extent {
+            depth += 1;
+        }
+        let size = self.cell_size(depth);
+        let center = (min + max) * 0.5;
+        (
+            depth,
+            (grid_cell(center.x, size), grid_cell(center.y, size)),
+        )
+    }
+
+    fn place(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        let (depth, cell) = self.node(min, max);
+        self.levels[depth].entry(cell).or_default().push(proxy);
+        if proxy >= self.nodes.len() {
+            self.nodes.resize(proxy + 1, None);
+        }
+        self.nodes[proxy] = Some((depth, cell));
+    }
+
+    fn unplace(&mut self, proxy: usize) {
+        if let Some((depth, cell)) = self.nodes.get_mut(proxy).and_then(Option::take) {
+            remove_from_bucket(&mut self.levels[depth], cell, proxy);
+        }
+    }
+
+    // 放入代理之前确保第 0 层的格子能容纳尺寸为 `extent` 的代理，需要时把格子的边长至少翻倍并重建整棵树。
+    // 返回是否重建过，重建时已经放入了所有代理
+    fn fit(&mut self, extent: Real) -> bool {
+        if self.root_size > 0. && extent <= self.root_size {
+            return false;
+        }
+        self.root_size = (self.root_size * 2.).max(extent * 2.).max(Real::EPSILON);
+        for level in &mut self.levels {
+            level.clear();
+        }
+        self.nodes.clear();
+        for (proxy, min, max) in self.proxies.list() {
+            self.place(proxy, min, max);
+        }
+        true
+    }
+
+    // 对松散边界与 `[min, max]` 重叠的格子中的每个代理调用 `callback`，`callback` 返回 false 时停止。
+    // 每个代理只在一个格子中，不会重复访问
+    fn visit(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        for (depth, level) in self.levels.iter().enumerate() {
+            if level.is_empty() {
+                continue;
+            }
+            // 与查询范围重叠的代理的中心到查询范围的距离不超过半个边长，
+            // 这里扩大一个边长，避免舍入误差漏掉恰好接触的代理
+            let size = self.cell_size(depth);
+            let (x0, x1) = (grid_cell(min.x - size, size), grid_cell(max.x + size, size));
+            let (y0, y1) = (grid_cell(min.y - size, size), grid_cell(max.y + size, size));
+            let mut visit_bucket = |bucket: &Vec<usize>| bucket.iter().all(|&p| callback(p));
+            // 查询范围内的格子比这一层已有的格子还多时，直接遍历这一层
+            if (x1 - x0 + 1).saturating_mul(y1 - y0 + 1) > level.len() as i64 {
+                for (&(x, y), bucket) in level {
+                    if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) && !visit_bucket(bucket) {
+                        return;
+                    }
+                }
+            } else {
+                for x in x0..=x1 {
+                    for y in y0..=y1 {
+                        if let Some(bucket) = level.get(&(x, y)) {
+                            if !visit_bucket(bucket) {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl BroadPhase for Quadtree {
+    fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        self.update(proxy, min, max);
+    }
+
+    fn remove(&mut self, proxy: usize) {
+        self.unplace(proxy);
+        self.proxies.remove(proxy);
+    }
+
+    fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+        self.proxies.set(proxy, min, max);
+        if self.fit((max - min).max_element()) {
+            return;
+        }
+        // 仍然在原来的格子中时不需要修改树
+        if self.nodes.get(proxy).copied().flatten() != Some(self.node(min, max)) {
+            self.unplace(proxy);
+            self.place(proxy, min, max);
+        }
+    }
+
+    fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+        for proxy in self.proxies.iter() {
+            self.visit(proxy.1, proxy.2, &mut |other| {
+                // 每一对代理只从编号较小的一侧生成一次
+                if other > proxy.0 {
+                    if let Some((min, max)) = self.proxies.get(other) {
+                        push_pair(&proxy, &(other, min, max), pairs);
+                    }
+                }
+                true
+            });
+        }
+    }
+
+    fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+        let region = (usize::MAX, min, max);
+        self.visit(min, max, &mut |p| match self.proxies.get(p) {
+            Some((p_min, p_max)) if overlaps(&(p, p_min, p_max), &region) => callback(p),
+            _ => true,
+        });
+    }
+
+    fn has_local_query(&self) -> bool {
+        true
+    }
+}
+
+// 同步到粗测结构中的一个物体
+#[derive(Clone, Copy)]
+struct SyncedProxy {
+    // 物体实际的包围盒
+    bounds: (Vec2, Vec2),
+    // 交给粗测结构的、向外扩大了 margin 的包围盒
+    fat: (Vec2, Vec2),
+    // 上一次更新代理对之后扩大后的包围盒是否变化过
+    moved: bool,
+}
+
+/// `World` 持有的粗测结构，以及每个物体已经同步过去的包围盒和扩大后的包围盒互相重叠的代理对
+#[derive(Clone)]
+pub(crate) struct BroadPhaseState {
+    broad_phase: Box<dyn BroadPhase>,
+    margin: Real,
+    // 只读的物体由烘焙的静态 BVH 负责，不在粗测结构中，对应的项为 None
+    proxies: Vec<Option<SyncedProxy>>,
+    // 粗测结构中代理的数量
+    count: usize,
+    // 上一次更新代理对之后扩大后的包围盒变化过的代理，可能包含已经移除的代理
+    moved: Vec<usize>,
+    // 复合形状的物体每个子形状实际的包围盒，其他物体为空
+    fixtures: Vec<Vec<(Vec2, Vec2)>>,
+    // 在 step 之间保留的代理对 (i, j)，i < j
+    pairs: BTreeSet<(usize, usize)>,
+    // 上一次 `update_pairs` 新增和移除的代理对
+    added: Vec<(usize, usize)>,
+    removed: Vec<(usize, usize)>,
+}
+
+impl BroadPhaseState {
+    pub(crate) fn new(broad_phase: Box<dyn BroadPhase>, margin: Real) -> BroadPhaseState {
+        BroadPhaseState {
+            broad_phase,
+            margin,
+            proxies: vec![],
+            count: 0,
+            moved: vec![],
+            fixtures: vec![],
+            pairs: BTreeSet::new(),
+            added: vec![],
+            removed: vec![],
+        }
+    }
+
+    pub(crate) fn margin(&self) -> Real {
+        self.margin
+    }
+
+    /// 修改包围盒向外扩大的距离，所有代理在下一次同步时按新的距离重新加入
+    pub(crate) fn set_margin(&mut self, margin: Real) {
+        self.clear();
+        self.margin = margin;
+    }
+
+    /// 移除所有代理和代理对，物体的下标发生变化之后需要调用
+    pub(crate) fn clear(&mut self) {
+        for (proxy, synced) in self.proxies.iter_mut().enumerate() {
+            if synced.take().is_some() {
+                self.broad_phase.remove(proxy);
+            }
+        }
+        self.count = 0;
+        self.moved.clear();
+        self.fixtures.clear();
+        self.pairs.clear();
+        self.added.clear();
+        self.removed.clear();
+    }
+
+    /// 把 `unbaked` 中的物体包围盒的变化同步到粗测结构中，烘焙过的物体不会移动，由静态 BVH 负责。
+    /// 包围盒仍然在扩大后的包围盒之内时不通知粗测结构，原地抖动的物体不会引起粗测结构的修改
+    pub(crate) fn sync(&mut self, bodies: &[Rc<RefCell<Body>>], unbaked: &[usize]) {
+        for proxy in bodies.len()..self.proxies.len() {
+            if self.proxies[proxy].is_some() {
+                self.broad_phase.remove(proxy);
+                self.count -= 1;
+            }
+        }
+        self.proxies.resize(bodies.len(), None);
+        self.fixtures.resize(bodies.len(), vec![]);
+        let margin = Vec2::new(self.margin, self.margin);
+        for &proxy in unbaked {
+            let body = bodies[proxy].borrow();
+            let bounds = (!body.is_read_only()).then(|| body.bounds());
+            self.fixtures[proxy] = bounds
+                .and_then(|_| body.fixture_bounds())
+                .unwrap_or_default();
+            let synced = &mut self.proxies[proxy];
+            match (synced.as_mut(), bounds) {
+                (None, Some((min, max))) => {
+                    let fat = (min - margin, max + margin);
+                    self.broad_phase.insert(proxy, fat.0, fat.1);
+                    *synced = Some(SyncedProxy {
+                        bounds: (min, max),
+                        fat,
+                        moved: true,
+                    });
+                    self.count += 1;
+                    self.moved.push(proxy);
+                }
+                (Some(synced), Some((min, max))) => {
+                    synced.bounds = (min, max);
+                    let (fat_min, fat_max) = synced.fat;
+                    let contained = fat_min.x <= min.x
+                        && fat_min.y <= min.y
+                        && fat_max.x >= max.x
+                        && fat_max.y >= max.y;
+                    if !contained {
+                        synced.fat = (min - margin, max + margin);
+                        if !synced.moved {
+                            synced.moved = true;
+                            self.moved.push(proxy);
+                        }
+                        self.broad_phase.update(proxy, synced.fat.0, synced.fat.1);
+                    }
+                }
+                (Some(_), None) => {
+                    self.broad_phase.remove(proxy);
+                    *synced = None;
+                    self.count -= 1;
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// 增量更新代理对：移除不再重叠或者已经移除的代理的代理对，只为扩大后的包围盒变化过的代理查询新的代理对。
+    /// 粗测结构的 `query` 需要遍历所有代理（参见 `BroadPhase::has_local_query`），
+    /// 或者变化过的代理超过一半时，改为由粗测结构重新生成所有代理对
+    pub(crate) fn update_pairs(&mut self) {
+        self.added.clear();
+        self.removed.clear();
+        let proxies = &self.proxies;
+        let fat = |i: usize| {
+            proxies
+                .get(i)
+                .copied()
+                .flatten()
+                .map(|p| (i, p.fat.0, p.fat.1))
+        };
+        let removed = &mut self.removed;
+        self.pairs.retain(|&(i, j)| {
+            let get = |k: usize| proxies.get(k).copied().flatten();
+            let keep = match (get(i), get(j)) {
+                (Some(a), Some(b)) => {
+                    !(a.moved || b.moved) || overlaps(&fat(i).unwrap(), &fat(j).unwrap())
+                }
+                _ => false,
+            };
+            if !keep {
+                removed.push((i, j));
+            }
+            keep
+        });
+
+        let mut candidates = vec![];
+        let mut moved = std::mem::take(&mut self.moved);
+        // 移除之后又重新加入的代理可能出现多次
+        moved.sort_unstable();
+        moved.dedup();
+        moved.retain(|&i| proxies.get(i).copied().flatten().is_some_and(|p| p.moved));
+        if !self.broad_phase.has_local_query() || moved.len() * 2 > self.count {
+            self.broad_phase.pairs(&mut candidates);
+        } else {
+            for &i in &moved {
+                let (_, min, max) = fat(i).unwrap();
+                self.broad_phase.query(min, max, &mut |j| {
+                    candidates.push((i, j));
+                    true
+                });
+            }
+        }
+        for (i, j) in candidates {
+            let (Some(a), Some(b)) = (fat(i), fat(j)) else {
+                continue;
+            };
+            let mut pair = vec![];
+            push_pair(&a, &b, &mut pair);
+            if let Some(&pair) = pair.first() {
+                if self.pairs.insert(pair) {
+                    self.added.push(pair);
+                }
+            }
+        }
+        self.added.sort_unstable();
+        self.removed.sort_unstable();
+        for &i in &moved {
+            if let Some(proxy) = &mut self.proxies[i] {
+                proxy.moved = false;
+            }
+        }
+        moved.clear();
+        self.moved = moved;
+    }
+
+    /// 实际的包围盒互相重叠的代理对 (i, j)，i < j，从小到大排列。
+    /// 复合形状的物体按子形状的包围盒检查，很长的复合物体不会与它的包围盒内的所有物体组成代理对
+    pub(crate) fn pairs(&self) -> Vec<(usize, usize)> {
+        // 代理对按扩大后的包围盒保留，这里用实际的包围盒再检查一次
+        let mut pairs = vec![];
+        for &(i, j) in &self.pairs {
+            let overlap = self.shape_bounds(i).iter().any(|&(a_min, a_max)| {
+                self.shape_bounds(j)
+                    .iter()
+                    .any(|&(b_min, b_max)| overlaps(&(i, a_min, a_max), &(j, b_min, b_max)))
+            });
+            if overlap {
+                pairs.push((i, j));
+            }
+        }
+        pairs
+    }
+
+    /// 代理中每个形状实际的包围盒，复合形状的物体为每个子形状的包围盒，其他物体为物体的包围盒
+    pub(crate) fn shape_bounds(&self, proxy: usize) -> &[(Vec2, Vec2)] {
+        match self.proxies.get(proxy) {
+            Some(Some(_)) if !self.fixtures[proxy].is_empty() => &self.fixtures[proxy],
+            Some(Some(synced)) => std::slice::from_ref(&synced.bounds),
+            _ => &[],
+        }
+    }
+
+    /// 上一次 `update_pairs` 新增的代理对
+    pub(crate) fn added_pairs(&self) -> &[(usize, usize)] {
+        &self.added
+    }
+
+    /// 上一次 `update_pairs` 移除的代理对
+    pub(crate) fn removed_pairs(&self) -> &[(usize, usize)] {
+        &self.removed
+    }
+
+    pub(crate) fn broad_phase(&self) -> &dyn BroadPhase {
+        self.broad_phase.as_ref()
+    }
+}
+
+// 一个代理最多放入的格子数，覆盖更多格子的大物体单独与所有代理比较
+const MAX_PROXY_CELLS: i64 = 64;
+
+// 坐标 `v` 所在的边长为 `size` 的格子
+#[inline]
+fn grid_cell(v: Real, size: Real) -> i64 {
+    (v / size).floor() as i64
+}
+
+// 从格子中移除代理，格子变为空时删除格子
+fn remove_from_bucket(cells: &mut HashMap<Cell, Vec<usize>>, cell: Cell, proxy: usize) {
+    if let Some(bucket) = cells.get_mut(&cell) {
+        bucket.retain(|&p| p != proxy);
+        if bucket.is_empty() {
+            cells.remove(&cell);
+        }
+    }
+}
+
+#[inline]
+fn overlaps(a: &Proxy, b: &Proxy) -> bool {
+    a.1.x <= b.2.x && a.2.x >= b.1.x && a.1.y <= b.2.y && a.2.y >= b.1.y
+}
+
+// 包围盒重叠的两个代理产生代理对 (i, j)，i < j
+#[inline]
+fn push_pair(a: &Proxy, b: &Proxy, pairs: &mut Vec<(usize, usize)>) {
+    if a.0 != b.0 && overlaps(a, b) {
+        pairs.push((a.0.min(b.0), a.0.max(b.0)));
+    }
+}
+
+fn brute_force_pairs(proxies: &[Proxy], pairs: &mut Vec<(usize, usize)>) {
+    for (k, a) in proxies.iter().enumerate() {
+        for b in &proxies[k + 1..] {
+            push_pair(a, b, pairs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{split_mix64, unit_float};
+
+    /// Test cases for:
+    /// * SpatialHash
+    /// * SweepAndPrune
+    /// * DynamicTree
+    /// * Quadtree
+    #[test]
+    fn broad_phases_should_match_brute_force() {
+        let mut proxies: Vec<Proxy> = (0..50)
+            .map(|i| {
+                let min = Vec2::new((i % 10) as Real * 1.5, (i / 10) as Real * 1.5);
+                (i, min, min + Vec2::new(2., 2.))
+            })
+            .collect();
+        // 覆盖很多格子的大物体
+        proxies.push((50, Vec2::new(-100., -100.), Vec2::new(100., 0.5)));
+
+        let find = |broad_phase: &mut dyn BroadPhase, proxies: &[Proxy]| {
+            let mut candidates = vec![];
+            broad_phase.pairs(&mut candidates);
+            let mut pairs = vec![];
+            for (i, j) in candidates {
+                let find = |p| proxies.iter().find(|proxy| proxy.0 == p).copied();
+                if let (Some(a), Some(b)) = (find(i), find(j)) {
+                    push_pair(&a, &b, &mut pairs);
+                }
+            }
+            pairs.sort_unstable();
+            pairs.dedup();
+            pairs
+        };
+        let mut broad_phases: Vec<Box<dyn BroadPhase>> = vec![
+            BroadPhaseKind::BruteForce.create(),
+            BroadPhaseKind::SpatialHash { cell_size: 1. }.create(),
+            BroadPhaseKind::SpatialHash { cell_size: 5. }.create(),
+            BroadPhaseKind::SweepAndPrune.create(),
+            BroadPhaseKind::DynamicTree { margin: 0.1 }.create(),
+            BroadPhaseKind::Quadtree { max_depth: 8 }.create(),
+        ];
+        for broad_phase in &mut broad_phases {
+            for &(i, min, max) in &proxies {
+                broad_phase.insert(i, min, max);
+            }
+        }
+        let expected = find(broad_phases[0].as_mut(), &proxies);
+        assert!(!expected.is_empty());
+        for broad_phase in &mut broad_phases[1..] {
+            assert_eq!(find(broad_phase.as_mut(), &proxies), expected);
+        }
+
+        // 移动、移除代理之后结果仍然相同
+        proxies[3] = (3, Vec2::new(20., 20.), Vec2::new(22., 22.));
+        proxies[4] = (4, Vec2::new(21., 21.), Vec2::new(23., 23.));
+        let removed = proxies.remove(10);
+        for broad_phase in &mut broad_phases {
+            broad_phase.update(3, proxies[3].1, proxies[3].2);
+            broad_phase.update(4, proxies[4].1, proxies[4].2);
+            broad_phase.remove(removed.0);
+        }
+        let expected = find(broad_phases[0].as_mut(), &proxies);
+        assert!(expected.contains(&(3, 4)));
+        for broad_phase in &mut broad_phases[1..] {
+            assert_eq!(find(broad_phase.as_mut(), &proxies), expected);
+        }
+    }
+
+    type Calls = Rc<RefCell<Vec<(&'static str, usize)>>>;
+
+    // 记录收到的每次调用，其余行为与 BruteForce 相同
+    #[derive(Clone, Default)]
+    struct Recorder {
+        inner: BruteForce,
+        calls: Calls,
+    }
+
+    impl BroadPhase for Recorder {
+        fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.calls.borrow_mut().push(("insert", proxy));
+            self.inner.insert(proxy, min, max);
+        }
+
+        fn remove(&mut self, proxy: usize) {
+            self.calls.borrow_mut().push(("remove", proxy));
+            self.inner.remove(proxy);
+        }
+
+        fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.calls.borrow_mut().push(("update", proxy));
+            self.inner.update(proxy, min, max);
+        }
+
+        fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+            self.inner.pairs(pairs);
+        }
+
+        fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+            self.inner.query(min, max, callback);
+        }
+    }
+
+    /// Test cases for:
+    /// * BroadPhaseState::sync(bodies, unbaked)
+    /// * BroadPhaseState::update_pairs()
+    #[test]
+    fn sync_should_only_update_proxies_leaving_fat_bounds() {
+        use crate::shape::Circle;
+
+        let recorder = Recorder::default();
+        let calls = recorder.calls.clone();
+        let mut state = BroadPhaseState::new(Box::new(recorder), 0.5);
+        let circle = |x: Real| {
+            let body = Body::new_circle(Circle::new(1.), Vec2::new(x, 0.), 0.5);
+            Rc::new(RefCell::new(body))
+        };
+        let mut bodies = vec![circle(0.), circle(3.)];
+        let sync = |state: &mut BroadPhaseState, bodies: &[Rc<RefCell<Body>>]| {
+            calls.borrow_mut().clear();
+            let unbaked: Vec<usize> = (0..bodies.len()).collect();
+            state.sync(bodies, &unbaked);
+            state.update_pairs();
+            calls.borrow().clone()
+        };
+
+        // 扩大后的包围盒接触就产生代理对，实际的包围盒不重叠
+        assert_eq!(sync(&mut state, &bodies), [("insert", 0), ("insert", 1)]);
+        assert_eq!(state.added_pairs(), [(0, 1)]);
+        assert!(state.pairs().is_empty());
+
+        // 在扩大后的包围盒之内移动，不通知粗测结构，但实际包围盒的变化仍然用于过滤代理对
+        bodies[1].borrow_mut().set_position(Vec2::new(2.6, 0.));
+        assert_eq!(sync(&mut state, &bodies), []);
+        assert!(state.pairs().is_empty());
+        bodies[1].borrow_mut().set_position(Vec2::new(1.8, 0.));
+        assert_eq!(sync(&mut state, &bodies), [("update", 1)]);
+        assert_eq!(state.pairs(), [(0, 1)]);
+        assert!(state.added_pairs().is_empty());
+
+        // 物体减少时移除多出来的代理
+        bodies.pop();
+        assert_eq!(sync(&mut state, &bodies), [("remove", 1)]);
+        assert!(state.pairs().is_empty());
+        assert_eq!(state.removed_pairs(), [(0, 1)]);
+    }
+
+    // 统计 `pairs` 和 `query` 的调用次数，`local` 决定 `has_local_query` 的返回值
+    #[derive(Clone)]
+    struct QueryCounter {
+        inner: BruteForce,
+        local: bool,
+        counts: Rc<RefCell<(usize, usize)>>,
+    }
+
+    impl BroadPhase for QueryCounter {
+        fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.inner.insert(proxy, min, max);
+        }
+
+        fn remove(&mut self, proxy: usize) {
+            self.inner.remove(proxy);
+        }
+
+        fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.inner.update(proxy, min, max);
+        }
+
+        fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+            self.counts.borrow_mut().0 += 1;
+            self.inner.pairs(pairs);
+        }
+
+        fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+            self.counts.borrow_mut().1 += 1;
+            self.inner.query(min, max, callback);
+        }
+
+        fn has_local_query(&self) -> bool {
+            self.local
+        }
+    }
+
+    /// Test cases for:
+    /// * BroadPhaseState::update_pairs()
+    #[test]
+    fn update_pairs_should_only_query_moved_proxies_with_local_query() {
+        use crate::shape::Circle;
+
+        let run = |local: bool| {
+            let counts = Rc::new(RefCell::new((0, 0)));
+            let counter = QueryCounter {
+                inner: BruteForce::new(),
+                local,
+                counts: counts.clone(),
+            };
+            let mut state = BroadPhaseState::new(Box::new(counter), 0.1);
+            let bodies: Vec<_> = (0..10)
+                .map(|i| {
+                    let position = Vec2::new(i as Real * 3., 0.);
+                    Rc::new(RefCell::new(Body::new_circle(
+                        Circle::new(1.),
+                        position,
+                        0.5,
+                    )))
+                })
+                .collect();
+            let unbaked: Vec<usize> = (0..bodies.len()).collect();
+            state.sync(&bodies, &unbaked);
+            state.update_pairs();
+            // 只有一个物体移动，碰到了右边的物体
+            bodies[4].borrow_mut().set_position(Vec2::new(13.5, 0.));
+            *counts.borrow_mut() = (0, 0);
+            state.sync(&bodies, &unbaked);
+            state.update_pairs();
+            assert_eq!(state.added_pairs(), [(4, 5)]);
+            let counts = *counts.borrow();
+            counts
+        };
+        assert_eq!(run(true), (0, 1));
+        assert_eq!(run(false), (1, 0));
+    }
+
+    /// Test cases for:
+    /// * BroadPhaseState::pairs()
+    /// * BroadPhaseState::shape_bounds(proxy)
+    #[test]
+    fn compound_should_pair_by_fixture_bounds() {
+        use crate::shape::{Circle, Compound, Fixture, ShapeType, AABB};
+
+        // 两端各有一块木板的长桥，中间是空的
+        let plank = |x: Real| {
+            let aabb = AABB::from_half_extents(Vec2::new(1., 0.5));
+            Fixture::new(ShapeType::AABB(aabb), Vec2::new(x, 0.), 0.)
+        };
+        let bridge = Compound::new(&[plank(-10.), plank(10.)]);
+        let body = |body: Body| Rc::new(RefCell::new(body));
+        let bodies = [
+            body(Body::new_compound(bridge, Vec2::ZERO, 0.5)),
+            body(Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5)),
+            body(Body::new_circle(Circle::new(1.), Vec2::new(10., 1.), 0.5)),
+        ];
+        let mut state = BroadPhaseState::new(BroadPhaseKind::BruteForce.create(), 0.1);
+        state.sync(&bodies, &[0, 1, 2]);
+        state.update_pairs();
+        assert_eq!(state.shape_bounds(0).len(), 2);
+        assert_eq!(state.shape_bounds(1), [bodies[1].borrow().bounds()]);
+        // 中间的球在桥的包围盒之内，但不与任何木板重叠
+        assert!(state.added_pairs().contains(&(0, 1)));
+        assert_eq!(state.pairs(), [(0, 2)]);
+    }
+
+    // 所有代理两两比较得到的物体对，从小到大排列
+    fn expected_pairs(proxies: &[Proxy]) -> Vec<(usize, usize)> {
+        let mut pairs = vec![];
+        brute_force_pairs(proxies, &mut pairs);
+        pairs.sort_unstable();
+        pairs
+    }
+
+    fn sorted_pairs(broad_phase: &mut dyn BroadPhase) -> Vec<(usize, usize)> {
+        let mut pairs = vec![];
+        broad_phase.pairs(&mut pairs);
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Test cases for:
+    /// * SweepAndPrune::pairs(&mut Vec<(usize, usize)>)
+    #[test]
+    fn sweep_and_prune_should_follow_moving_proxies() {
+        let random = |seed: u64| unit_float(split_mix64(seed));
+        let mut sap = SweepAndPrune::new();
+        let mut proxies: Vec<Proxy> = vec![];
+        for round in 0..20_u64 {
+            // 每一轮随机移动代理，上一次的顺序被打乱，还会加入和移除代理
+            for (i, proxy) in proxies.iter_mut().enumerate() {
+                let seed = round * 1000 + i as u64;
+                let min = Vec2::new(random(seed) * 40., random(seed + 500) * 10.);
+                *proxy = (proxy.0, min, min + Vec2::new(2., 2.));
+                sap.update(proxy.0, proxy.1, proxy.2);
+            }
+            let id = round as usize + 100;
+            let min = Vec2::new(random(round) * 40., 0.);
+            proxies.push((id, min, min + Vec2::new(2., 10.)));
+            sap.insert(id, min, min + Vec2::new(2., 10.));
+            if round % 3 == 2 {
+                let removed = proxies.remove(0);
+                sap.remove(removed.0);
+            }
+            // 每一对物体只出现一次
+            assert_eq!(sorted_pairs(&mut sap), expected_pairs(&proxies));
+        }
+        assert!(!expected_pairs(&proxies).is_empty());
+    }
+
+    /// Test cases for:
+    /// * Quadtree::pairs(&mut Vec<(usize, usize)>)
+    #[test]
+    fn quadtree_should_pair_proxies_of_mixed_sizes() {
+        let random = |seed: u64| unit_float(split_mix64(seed));
+        let mut proxies: Vec<Proxy> = (0..200)
+            .map(|i| {
+                // 尺寸从 0.001 到 100 不等，分布在很大的范围内
+                let size = (10. as Real).powf(random(i) * 5. - 3.);
+                let min = Vec2::new(random(i + 1000), random(i + 2000)) * 1000. - Vec2::splat(500.);
+                (
+                    i as usize,
+                    min,
+                    min + Vec2::new(size, size * random(i + 3000)),
+                )
+            })
+            .collect();
+        // 没有大小的代理和恰好边界接触的代理
+        proxies.push((200, Vec2::new(3., 3.), Vec2::new(3., 3.)));
+        proxies.push((201, Vec2::new(1., 1.), Vec2::new(3., 2.)));
+        proxies.push((202, Vec2::new(3., 2.), Vec2::new(4., 3.)));
+        let expected = expected_pairs(&proxies);
+        assert!(expected.contains(&(200, 202)) && expected.contains(&(201, 202)));
+        for max_depth in [0, 4, 20] {
+            let mut quadtree = Quadtree::new(max_depth);
+            for &(i, min, max) in &proxies {
+                quadtree.insert(i, min, max);
+            }
+            assert_eq!(sorted_pairs(&mut quadtree), expected);
+        }
+    }
+
+    // 包围盒与 `[min, max]` 重叠的代理，从小到大排列，重复报告的代理会保留
+    fn sorted_query(broad_phase: &dyn BroadPhase, min: Vec2, max: Vec2) -> Vec<usize> {
+        let mut result = vec![];
+        broad_phase.query(min, max, &mut |p| {
+            result.push(p);
+            true
+        });
+        result.sort_unstable();
+        result
+    }
+
+    fn expected_query(proxies: &[Proxy], min: Vec2, max: Vec2) -> Vec<usize> {
+        let region = (usize::MAX, min, max);
+        let mut result: Vec<usize> = proxies
+            .iter()
+            .filter(|p| overlaps(p, &region))
+            .map(|p| p.0)
+            .collect();
+        result.sort_unstable();
+        result
+    }
+
+    /// Test cases for:
+    /// * SpatialHash::query(Vec2, Vec2, callback)
+    /// * SpatialHash::update(usize, Vec2, Vec2)
+    #[test]
+    fn spatial_hash_query_should_report_each_proxy_once() {
+        let random = |seed: u64| unit_float(split_mix64(seed));
+        let mut grid = SpatialHash::new(2.);
+        let mut proxies: Vec<Proxy> = (0..100)
+            .map(|i| {
+                let min = Vec2::new(random(i), random(i + 100)) * 50.;
+                let size = Vec2::new(random(i + 200), random(i + 300)) * 6.;
+                (i as usize, min, min + size)
+            })
+            .collect();
+        // 覆盖很多格子的大物体
+        proxies.push((100, Vec2::new(-40., 10.), Vec2::new(90., 12.)));
+        for &(i, min, max) in &proxies {
+            grid.insert(i, min, max);
+        }
+        let regions = [
+            (Vec2::new(10., 10.), Vec2::new(14., 13.)),
+            (Vec2::new(-100., -100.), Vec2::new(100., 100.)),
+            (Vec2::new(3., 3.), Vec2::new(3., 3.)),
+        ];
+        for round in 0..3 {
+            for &(min, max) in &regions {
+                assert_eq!(
+                    sorted_query(&grid, min, max),
+                    expected_query(&proxies, min, max)
+                );
+            }
+            // 移动一部分代理，离开原来的格子之后不会再出现在原来的位置
+            for (i, proxy) in proxies.iter_mut().enumerate().step_by(3) {
+                let offset = Vec2::new(random((round * 1000 + i) as u64) * 20. - 10., 1.);
+                *proxy = (proxy.0, proxy.1 + offset, proxy.2 + offset);
+                grid.update(proxy.0, proxy.1, proxy.2);
+            }
+        }
+        let removed = proxies.remove(100);
+        grid.remove(removed.0);
+        assert!(grid.oversized.is_empty());
+        let (min, max) = regions[1];
+        assert_eq!(
+            sorted_query(&grid, min, max),
+            expected_query(&proxies, min, max)
+        );
+        for proxy in proxies.drain(..) {
+            grid.remove(proxy.0);
+        }
+        assert!(grid.cells.is_empty());
+    }
+
+    /// Test cases for:
+    /// * Quadtree::update(usize, Vec2, Vec2)
+    /// * Quadtree::query(Vec2, Vec2, callback)
+    #[test]
+    fn quadtree_should_move_proxies_between_nodes() {
+        let random = |seed: u64| unit_float(split_mix64(seed));
+        let mut quadtree = Quadtree::new(6);
+        let mut proxies: Vec<Proxy> = (0..100)
+            .map(|i| {
+                let min = Vec2::new(random(i), random(i + 100)) * 100.;
+                (i as usize, min, min + Vec2::splat(random(i + 200) * 3.))
+            })
+            .collect();
+        for &(i, min, max) in &proxies {
+            quadtree.insert(i, min, max);
+        }
+        let root_size = quadtree.root_size;
+        let node = quadtree.nodes[5];
+        // 在格子中移动很小的距离不修改树
+        let nudge = Vec2::splat(1e-4);
+        proxies[5] = (5, proxies[5].1 + nudge, proxies[5].2 + nudge);
+        quadtree.update(5, proxies[5].1, proxies[5].2);
+        assert_eq!(quadtree.nodes[5], node);
+
+        for round in 0..5_u64 {
+            for (i, proxy) in proxies.iter_mut().enumerate().step_by(2) {
+                let offset = Vec2::new(random(round * 1000 + i as u64), 0.5) * 30.;
+                *proxy = (proxy.0, proxy.1 + offset, proxy.2 + offset);
+                quadtree.update(proxy.0, proxy.1, proxy.2);
+            }
+            assert_eq!(sorted_pairs(&mut quadtree), expected_pairs(&proxies));
+            let (min, max) = (Vec2::new(40., 40.), Vec2::new(70., 90.));
+            assert_eq!(
+                sorted_query(&quadtree, min, max),
+                expected_query(&proxies, min, max)
+            );
+        }
+        assert_eq!(quadtree.root_size, root_size);
+
+        // 比第 0 层格子还大的代理使整棵树重建
+        proxies.push((100, Vec2::new(-500., 0.), Vec2::new(500., 1.)));
+        quadtree.insert(100, proxies[100].1, proxies[100].2);
+        assert!(quadtree.root_size >= 1000.);
+        assert_eq!(sorted_pairs(&mut quadtree), expected_pairs(&proxies));
+        for proxy in proxies.drain(..) {
+            quadtree.remove(proxy.0);
+        }
+        assert!(quadtree.levels.iter().all(HashMap::is_empty));
+    }
+}
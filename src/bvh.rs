@@ -0,0 +1,597 @@
+use crate::{math::Real, vec2::Vec2};
+
+// BVH 节点，叶子节点保存物体在 world 中的下标
+enum Node {
+    Leaf {
+        min: Vec2,
+        max: Vec2,
+        body: usize,
+    },
+    Internal {
+        min: Vec2,
+        max: Vec2,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl Node {
+    fn bounds(&self) -> (Vec2, Vec2) {
+        match *self {
+            Node::Leaf { min, max, .. } => (min, max),
+            Node::Internal { min, max, .. } => (min, max),
+        }
+    }
+}
+
+/// 构建之后不可修改的包围盒层次树（Bounding Volume Hierarchy）
+/// 用来加速对大量静态物体的查询
+pub(crate) struct StaticBvh {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+impl StaticBvh {
+    /// 由 `(物体下标, 包围盒 min, 包围盒 max)` 列表构建 BVH
+    pub(crate) fn build(items: &[(usize, Vec2, Vec2)]) -> StaticBvh {
+        let mut bvh = StaticBvh {
+            nodes: Vec::with_capacity(items.len() * 2),
+            root: None,
+        };
+        let mut items = items.to_vec();
+        if !items.is_empty() {
+            bvh.root = Some(bvh.build_node(&mut items));
+        }
+        bvh
+    }
+
+    // 自顶向下构建：沿包围盒较长的轴按中心排序，从中间分成两半
+    fn build_node(&mut self, items: &mut [(usize, Vec2, Vec2)]) -> usize {
+        if items.len() == 1 {
+            let (body, min, max) = items[0];
+            self.nodes.push(Node::Leaf { min, max, body });
+            return self.nodes.len() - 1;
+        }
+        let (min, max) = items
+            .iter()
+            .fold((items[0].1, items[0].2), |(min, max), item| {
+                (min.min(item.1), max.max(item.2))
+            });
+        let size = max - min;
+        if size.x >= size.y {
+            items.sort_by(|a, b| (a.1.x + a.2.x).total_cmp(&(b.1.x + b.2.x)));
+        } else {
+            items.sort_by(|a, b| (a.1.y + a.2.y).total_cmp(&(b.1.y + b.2.y)));
+        }
+        let (left_items, right_items) = items.split_at_mut(items.len() / 2);
+        let left = self.build_node(left_items);
+        let right = self.build_node(right_items);
+        self.nodes.push(Node::Internal {
+            min,
+            max,
+            left,
+            right,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// 对包围盒与 `[min, max]` 重叠的每个物体调用 `callback`，`callback` 返回 false 时停止
+    pub(crate) fn query<F>(&self, min: Vec2, max: Vec2, callback: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.visit(
+            |node_min, node_max| {
+                node_max.x >= min.x
+                    && node_min.x <= max.x
+                    && node_max.y >= min.y
+                    && node_min.y <= max.y
+            },
+            callback,
+        );
+    }
+
+    /// 对包围盒被射线 `origin + dir * t`（`t` 在 `[0, max_t]` 之间）穿过的每个物体调用 `callback`，
+    /// `callback` 返回 false 时停止
+    pub(crate) fn ray_query<F>(&self, origin: Vec2, dir: Vec2, max_t: Real, callback: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.visit(
+            |min, max| ray_overlaps(min, max, origin, dir, max_t),
+            callback,
+        );
+    }
+
+    // 从根节点向下访问包围盒满足 `overlaps` 的节点
+    fn visit<O, F>(&self, overlaps: O, mut callback: F)
+    where
+        O: Fn(Vec2, Vec2) -> bool,
+        F: FnMut(usize) -> bool,
+    {
+        let mut stack = vec![];
+        stack.extend(self.root);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let (min, max) = node.bounds();
+            if !overlaps(min, max) {
+                continue;
+            }
+            match *node {
+                Node::Leaf { body, .. } => {
+                    if !callback(body) {
+                        return;
+                    }
+                }
+                Node::Internal { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+    }
+}
+
+// 射线 `origin + dir * t`，`t` 在 `[0, max_t]` 之间，是否穿过包围盒 `[min, max]`
+fn ray_overlaps(min: Vec2, max: Vec2, origin: Vec2, dir: Vec2, max_t: Real) -> bool {
+    let (mut t0, mut t1) = (0., max_t);
+    for (o, d, lo, hi) in [
+        (origin.x, dir.x, min.x, max.x),
+        (origin.y, dir.y, min.y, max.y),
+    ] {
+        if d == 0. {
+            if o < lo || o > hi {
+                return false;
+            }
+            continue;
+        }
+        let (mut near, mut far) = ((lo - o) / d, (hi - o) / d);
+        if near > far {
+            std::mem::swap(&mut near, &mut far);
+        }
+        t0 = near.max(t0);
+        t1 = far.min(t1);
+        if t0 > t1 {
+            return false;
+        }
+    }
+    true
+}
+
+#[inline]
+fn perimeter(min: Vec2, max: Vec2) -> Real {
+    let size = max - min;
+    2. * (size.x + size.y)
+}
+
+// 动态 BVH 的节点，叶子节点的高度为 0，`children` 无意义
+#[derive(Clone)]
+struct TreeNode {
+    min: Vec2,
+    max: Vec2,
+    parent: Option<usize>,
+    children: [usize; 2],
+    height: i32,
+    body: usize,
+}
+
+impl TreeNode {
+    #[inline]
+    fn is_leaf(&self) -> bool {
+        self.height == 0
+    }
+}
+
+/// 可以增量修改的包围盒层次树，与 Box2D 的 b2DynamicTree 相同：
+/// 叶子节点保存向外扩大 `margin` 的包围盒，物体的包围盒没有超出时不需要修改树；
+/// 插入时按周长代价选择兄弟节点，并通过旋转保持树的平衡
+#[derive(Clone)]
+pub(crate) struct DynamicBvh {
+    nodes: Vec<TreeNode>,
+    free: Vec<usize>,
+    root: Option<usize>,
+    margin: Real,
+}
+
+impl DynamicBvh {
+    pub(crate) fn new(margin: Real) -> DynamicBvh {
+        DynamicBvh {
+            nodes: vec![],
+            free: vec![],
+            root: None,
+            margin,
+        }
+    }
+
+    fn allocate(&mut self, node: TreeNode) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// 插入编号为 `body` 的物体，包围盒为 `[min, max]`，返回叶子节点的编号
+    pub(crate) fn insert(&mut self, body: usize, min: Vec2, max: Vec2) -> usize {
+        let margin = Vec2::new(self.margin, self.margin);
+        let leaf = self.allocate(TreeNode {
+            min: min - margin,
+            max: max + margin,
+            parent: None,
+            children: [0; 2],
+            height: 0,
+            body,
+        });
+        self.insert_leaf(leaf);
+        leaf
+    }
+
+    /// 移除叶子节点 `leaf`
+    pub(crate) fn remove(&mut self, leaf: usize) {
+        self.remove_leaf(leaf);
+        self.free.push(leaf);
+    }
+
+    /// 物体的包围盒变为 `[min, max]`，超出叶子节点扩大后的包围盒时重新插入，此时返回 true
+    pub(crate) fn update(&mut self, leaf: usize, min: Vec2, max: Vec2) -> bool {
+        let node = &self.nodes[leaf];
+        if node.min.x <= min.x && node.min.y <= min.y && node.max.x >= max.x && node.max.y >= max.y
+        {
+            return false;
+        }
+        self.remove_leaf(leaf);
+        let margin = Vec2::new(self.margin, self.margin);
+        self.nodes[leaf].min = min - margin;
+        self.nodes[leaf].max = max + margin;
+        self.insert_leaf(leaf);
+        true
+    }
+
+    /// 对扩大后的包围盒与 `[min, max]` 重叠的每个物体调用 `callback`，`callback` 返回 false 时停止
+    pub(crate) fn query<F>(&self, min: Vec2, max: Vec2, callback: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.visit(
+            |node_min, node_max| {
+                node_max.x >= min.x
+                    && node_min.x <= max.x
+                    && node_max.y >= min.y
+                    && node_min.y <= max.y
+            },
+            callback,
+        );
+    }
+
+    /// 对扩大后的包围盒被射线 `origin + dir * t`（`t` 在 `[0, max_t]` 之间）穿过的每个物体调用 `callback`，
+    /// `callback` 返回 false 时停止
+    pub(crate) fn ray_query<F>(&self, origin: Vec2, dir: Vec2, max_t: Real, callback: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.visit(
+            |min, max| ray_overlaps(min, max, origin, dir, max_t),
+            callback,
+        );
+    }
+
+    // 从根节点向下访问包围盒满足 `overlaps` 的节点
+    fn visit<O, F>(&self, overlaps: O, mut callback: F)
+    where
+        O: Fn(Vec2, Vec2) -> bool,
+        F: FnMut(usize) -> bool,
+    {
+        let mut stack = vec![];
+        stack.extend(self.root);
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(node.min, node.max) {
+                continue;
+            }
+            if !node.is_leaf() {
+                stack.extend(node.children);
+            } else if !callback(node.body) {
+                return;
+            }
+        }
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.nodes[leaf].parent = None;
+            self.root = Some(leaf);
+            return;
+        };
+        let (leaf_min, leaf_max) = (self.nodes[leaf].min, self.nodes[leaf].max);
+
+        // 从根节点向下，选择合并后周长增加最少的兄弟节点
+        let mut index = root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let area = perimeter(node.min, node.max);
+            let combined = perimeter(node.min.min(leaf_min), node.max.max(leaf_max));
+            // 在这里新建父节点的代价，以及向下继续时祖先节点增加的代价
+            let cost = 2. * combined;
+            let inheritance = 2. * (combined - area);
+            let child_cost = |child: &TreeNode| {
+                let merged = perimeter(child.min.min(leaf_min), child.max.max(leaf_max));
+                if child.is_leaf() {
+                    merged + inheritance
+                } else {
+                    merged - perimeter(child.min, child.max) + inheritance
+                }
+            };
+            let [left, right] = node.children;
+            let cost_left = child_cost(&self.nodes[left]);
+            let cost_right = child_cost(&self.nodes[right]);
+            if cost < cost_left && cost < cost_right {
+                break;
+            }
+            index = if cost_left < cost_right { left } else { right };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate(TreeNode {
+            min: self.nodes[sibling].min.min(leaf_min),
+            max: self.nodes[sibling].max.max(leaf_max),
+            parent: old_parent,
+            children: [sibling, leaf],
+            height: self.nodes[sibling].height + 1,
+            body: 0,
+        });
+        match old_parent {
+            Some(parent) => self.replace_child(parent, sibling, new_parent),
+            None => self.root = Some(new_parent),
+        }
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+        self.refit_from(Some(new_parent));
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+        let parent = self.nodes[leaf]
+            .parent
+            .expect("non-root node must have a parent");
+        let grand_parent = self.nodes[parent].parent;
+        let [left, right] = self.nodes[parent].children;
+        let sibling = if left == leaf { right } else { left };
+        self.nodes[sibling].parent = grand_parent;
+        self.free.push(parent);
+        match grand_parent {
+            Some(grand_parent) => {
+                self.replace_child(grand_parent, parent, sibling);
+                self.refit_from(Some(grand_parent));
+            }
+            None => self.root = Some(sibling),
+        }
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        let children = &mut self.nodes[parent].children;
+        if children[0] == old {
+            children[0] = new;
+        } else {
+            children[1] = new;
+        }
+    }
+
+    // 从 `index` 开始向上平衡并重新计算包围盒和高度
+    fn refit_from(&mut self, mut index: Option<usize>) {
+        while let Some(i) = index {
+            let i = self.balance(i);
+            self.refit(i);
+            index = self.nodes[i].parent;
+        }
+    }
+
+    fn refit(&mut self, index: usize) {
+        let [left, right] = self.nodes[index].children;
+        let (left, right) = (&self.nodes[left], &self.nodes[right]);
+        let (min, max) = (left.min.min(right.min), left.max.max(right.max));
+        let height = 1 + left.height.max(right.height);
+        let node = &mut self.nodes[index];
+        node.min = min;
+        node.max = max;
+        node.height = height;
+    }
+
+    // 左右子树的高度相差超过 1 时，把较高的子节点旋转到 `a` 的位置，返回旋转后位于该位置的节点
+    fn balance(&mut self, a: usize) -> usize {
+        if self.nodes[a].height < 2 {
+            return a;
+        }
+        let [b, c] = self.nodes[a].children;
+        let difference = self.nodes[c].height - self.nodes[b].height;
+        if difference > 1 {
+            self.rotate_up(a, 1)
+        } else if difference < -1 {
+            self.rotate_up(a, 0)
+        } else {
+            a
+        }
+    }
+
+    // 把 `a` 的第 `side` 个子节点 `up` 旋转到 `a` 的位置，`a` 成为 `up` 的子节点，
+    // `up` 较高的子节点留在 `up` 下，较矮的子节点交给 `a`
+    fn rotate_up(&mut self, a: usize, side: usize) -> usize {
+        let up = self.nodes[a].children[side];
+        let [f, g] = self.nodes[up].children;
+        let parent = self.nodes[a].parent;
+
+        self.nodes[up].children[0] = a;
+        self.nodes[up].parent = parent;
+        self.nodes[a].parent = Some(up);
+        match parent {
+            Some(parent) => self.replace_child(parent, a, up),
+            None => self.root = Some(up),
+        }
+
+        let (taller, shorter) = if self.nodes[f].height > self.nodes[g].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+        self.nodes[up].children[1] = taller;
+        self.nodes[a].children[side] = shorter;
+        self.nodes[shorter].parent = Some(a);
+        self.refit(a);
+        self.refit(up);
+        up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{split_mix64, unit_float};
+
+    fn overlaps(a: (Vec2, Vec2), b: (Vec2, Vec2)) -> bool {
+        a.1.x >= b.0.x && a.0.x <= b.1.x && a.1.y >= b.0.y && a.0.y <= b.1.y
+    }
+
+    // 检查父子关系、包围盒与高度，返回叶子节点数量
+    fn validate(tree: &DynamicBvh, index: usize) -> usize {
+        let node = &tree.nodes[index];
+        if node.is_leaf() {
+            return 1;
+        }
+        let mut leaves = 0;
+        for child in node.children {
+            let child_node = &tree.nodes[child];
+            assert_eq!(child_node.parent, Some(index));
+            assert!(node.min.x <= child_node.min.x && node.min.y <= child_node.min.y);
+            assert!(node.max.x >= child_node.max.x && node.max.y >= child_node.max.y);
+            leaves += validate(tree, child);
+        }
+        let [left, right] = node.children;
+        let height = 1 + tree.nodes[left].height.max(tree.nodes[right].height);
+        assert_eq!(node.height, height);
+        leaves
+    }
+
+    /// Test cases for:
+    /// * DynamicBvh::insert(body, min, max)
+    /// * DynamicBvh::update(leaf, min, max)
+    /// * DynamicBvh::remove(leaf)
+    /// * DynamicBvh::query(min, max, callback)
+    /// * DynamicBvh::ray_query(origin, dir, max_t, callback)
+    #[test]
+    fn dynamic_bvh_should_match_brute_force() {
+        let margin = 0.5;
+        let mut seed = 0;
+        let mut random = |lo: Real, hi: Real| {
+            seed += 1;
+            lo + (hi - lo) * unit_float(split_mix64(seed))
+        };
+        let mut tree = DynamicBvh::new(margin);
+        // 物体编号对应的 (叶子节点, 包围盒)，None 表示已经移除
+        let mut bodies: Vec<Option<(usize, (Vec2, Vec2))>> = vec![];
+        let alive = |bodies: &[Option<(usize, (Vec2, Vec2))>]| {
+            bodies
+                .iter()
+                .enumerate()
+                .filter_map(|(i, b)| b.map(|(_, aabb)| (i, aabb)))
+                .collect::<Vec<_>>()
+        };
+
+        for round in 0..50 {
+            for _ in 0..20 {
+                let action = random(0., 1.);
+                let live = alive(&bodies);
+                if action < 0.4 || live.len() < 10 {
+                    let min = Vec2::new(random(-50., 50.), random(-50., 50.));
+                    let aabb = (min, min + Vec2::new(random(0.1, 5.), random(0.1, 5.)));
+                    let leaf = tree.insert(bodies.len(), aabb.0, aabb.1);
+                    bodies.push(Some((leaf, aabb)));
+                } else if action < 0.85 {
+                    // 小幅移动和大幅跳跃
+                    let (i, (min, max)) = live[(random(0., 1.) * live.len() as Real) as usize];
+                    let scale = if action < 0.7 { 0.3 } else { 30. };
+                    let offset = Vec2::new(random(-scale, scale), random(-scale, scale));
+                    let leaf = bodies[i].unwrap().0;
+                    tree.update(leaf, min + offset, max + offset);
+                    bodies[i] = Some((leaf, (min + offset, max + offset)));
+                } else {
+                    let (i, _) = live[(random(0., 1.) * live.len() as Real) as usize];
+                    tree.remove(bodies[i].unwrap().0);
+                    bodies[i] = None;
+                }
+            }
+
+            let live = alive(&bodies);
+            assert_eq!(validate(&tree, tree.root.unwrap()), live.len());
+
+            // 区域查询：不遗漏真正重叠的物体，不返回已移除或重复的物体
+            let min = Vec2::new(random(-60., 40.), random(-60., 40.));
+            let region = (min, min + Vec2::new(random(1., 30.), random(1., 30.)));
+            let mut found = vec![];
+            tree.query(region.0, region.1, |body| {
+                found.push(body);
+                true
+            });
+            found.sort_unstable();
+            let count = found.len();
+            found.dedup();
+            assert_eq!(found.len(), count, "round {round}");
+            for &body in &found {
+                let (_, (min, max)) = bodies[body].expect("removed body reported");
+                // 物体大小不变，扩大后的包围盒不会超出真实包围盒 2 * margin
+                let margin = Vec2::new(2. * margin, 2. * margin);
+                assert!(overlaps((min - margin, max + margin), region));
+            }
+            for &(i, aabb) in &live {
+                if overlaps(aabb, region) {
+                    assert!(found.contains(&i), "round {round}: missing {i}");
+                }
+            }
+
+            // 射线查询
+            let origin = Vec2::new(random(-60., 60.), random(-60., 60.));
+            let dir = Vec2::new(random(-1., 1.), random(-1., 1.));
+            let mut hits = vec![];
+            tree.ray_query(origin, dir, 100., |body| {
+                hits.push(body);
+                true
+            });
+            for &body in &hits {
+                assert!(bodies[body].is_some(), "removed body reported");
+            }
+            for &(i, (min, max)) in &live {
+                if ray_overlaps(min, max, origin, dir, 100.) {
+                    assert!(hits.contains(&i), "round {round}: ray missed {i}");
+                }
+            }
+
+            // 用每个物体的包围盒查询得到的重叠对与暴力计算相同
+            let mut pairs = vec![];
+            for &(i, aabb) in &live {
+                tree.query(aabb.0, aabb.1, |j| {
+                    if i < j && overlaps(aabb, bodies[j].unwrap().1) {
+                        pairs.push((i, j));
+                    }
+                    true
+                });
+            }
+            pairs.sort_unstable();
+            let mut expected = vec![];
+            for (k, &(i, a)) in live.iter().enumerate() {
+                for &(j, b) in &live[k + 1..] {
+                    if overlaps(a, b) {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(pairs, expected, "round {round}");
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{math::Real, world::World};
+
+/// 固定时间步长的驱动器，把实际经过的时间换算成 `World::step` 的调用次数
+///
+/// 未执行完的时间累积到下一帧，渲染时可以用 `alpha` 在上一次和这一次 step 的状态之间插值
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PhysicsClock {
+    // 还没有执行 step 的时间
+    accumulator: Real,
+    // 上一次 advance 时 world 的时间间隔
+    dt: Real,
+    // 每次 advance 最多执行的 step 次数
+    max_steps: u32,
+}
+
+impl Default for PhysicsClock {
+    fn default() -> Self {
+        PhysicsClock::new()
+    }
+}
+
+impl PhysicsClock {
+    /// 创建一个驱动器，每次 `advance` 最多执行 5 次 step
+    pub fn new() -> PhysicsClock {
+        PhysicsClock {
+            accumulator: 0.,
+            dt: 0.,
+            max_steps: 5,
+        }
+    }
+
+    /// 设置每次 `advance` 最多执行的 step 次数。
+    /// 一帧的耗时超过能够模拟的时间时，多出来的时间会被丢弃，
+    /// 避免 step 越来越多、帧越来越慢的恶性循环
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        self.max_steps = max_steps.max(1);
+    }
+
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    /// 经过了 `elapsed` 秒的实际时间，按 world 的时间间隔执行相应次数的 step，
+    /// 返回执行的次数
+    pub fn advance(&mut self, world: &mut World, elapsed: Real) -> u32 {
+        self.dt = world.dt();
+        if self.dt <= 0. {
+            return 0;
+        }
+        let max_time = self.dt * self.max_steps as Real;
+        self.accumulator = (self.accumulator + elapsed.max(0.)).min(max_time);
+        let mut steps = 0;
+        while self.accumulator >= self.dt && steps < self.max_steps {
+            world.step();
+            self.accumulator -= self.dt;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// 剩余时间占一次 step 的比例，在 `[0, 1)` 之间。
+    /// 渲染位置为 `previous + (current - previous) * alpha`，参见 `Body::interpolated_transform`
+    pub fn alpha(&self) -> Real {
+        if self.dt > 0. {
+            (self.accumulator / self.dt).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+
+    /// 丢弃累积的时间，例如游戏暂停之后恢复时使用
+    pub fn reset(&mut self) {
+        self.accumulator = 0.;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * PhysicsClock.advance(&mut World, Real)
+    /// * PhysicsClock.alpha()
+    #[test]
+    fn physics_clock_should_work() {
+        let mut world = World::new(0.25, 10, 1.0);
+        let mut clock = PhysicsClock::new();
+        assert_eq!(clock.advance(&mut world, 0.1), 0);
+        assert_eq!(clock.advance(&mut world, 0.5), 2);
+        assert!((clock.alpha() - 0.4).abs() < 1e-5);
+
+        // 超出 max_steps 的时间被丢弃
+        clock.set_max_steps(2);
+        assert_eq!(clock.advance(&mut world, 10.), 2);
+        assert!(clock.alpha() < 1e-5);
+    }
+}
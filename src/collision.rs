@@ -0,0 +1,765 @@
+use crate::{
+    math::Real,
+    narrow_phase::ContactManifold,
+    shape::{ShapeType, MAX_POLYGON_VERTICES},
+    transform::Transform2D,
+    vec2::Vec2,
+};
+
+// GJK 的最大迭代次数
+const MAX_GJK_ITERATIONS: u32 = 20;
+// EPA 的最大迭代次数
+const MAX_EPA_ITERATIONS: u32 = 32;
+// EPA 新的支撑点与最近的边的距离小于该值时停止扩展
+const EPA_TOLERANCE: Real = 0.0001;
+
+/// 世界坐标系中的凸形状，由一组顶点和半径表示：顶点的凸包向外扩大 `radius`。
+/// 圆是一个顶点加半径，胶囊是两个顶点加半径，多边形的半径为 0
+#[derive(Clone, Copy, Debug)]
+pub struct ConvexProxy {
+    count: usize,
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    // 第 i 条边（顶点 i 到顶点 i + 1）的外法线，边的长度为 0 时为零向量
+    normals: [Vec2; MAX_POLYGON_VERTICES],
+    radius: Real,
+}
+
+impl ConvexProxy {
+    /// 由顶点和半径创建，顶点最多 `MAX_POLYGON_VERTICES` 个，多出的顶点会被忽略。
+    /// 顶点可以是顺时针也可以是逆时针顺序
+    pub fn new(points: &[Vec2], radius: Real) -> ConvexProxy {
+        assert!(!points.is_empty(), "convex proxy needs at least one vertex");
+        let count = points.len().min(MAX_POLYGON_VERTICES);
+        let mut vertices = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        vertices[..count].copy_from_slice(&points[..count]);
+        let signed_area: Real = (0..count)
+            .map(|i| vertices[i].perp_dot(vertices[(i + 1) % count]))
+            .sum();
+        if signed_area < 0. {
+            vertices[..count].reverse();
+        }
+        let mut normals = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        for i in 0..count {
+            let edge = vertices[(i + 1) % count] - vertices[i];
+            normals[i] = (-edge.perp()).try_normalize().unwrap_or(Vec2::ZERO);
+        }
+        ConvexProxy {
+            count,
+            vertices,
+            normals,
+            radius,
+        }
+    }
+
+    /// 按 `transform` 放置的形状。折线、复合形状和自定义形状不是凸形状，返回 `None`。
+    /// AABB 始终与坐标轴对齐，只使用 `transform` 的平移
+    pub fn from_shape(shape: &ShapeType, transform: &Transform2D) -> Option<ConvexProxy> {
+        let points = |points: &[Vec2], radius: Real| {
+            let mut proxy = ConvexProxy::new(points, radius);
+            for i in 0..proxy.count {
+                proxy.vertices[i] = transform.transform_point(proxy.vertices[i]);
+                proxy.normals[i] = transform.transform_vector(proxy.normals[i]);
+            }
+            proxy
+        };
+        let proxy = match shape {
+            ShapeType::Circle(circle) => points(&[Vec2::ZERO], circle.radius()),
+            ShapeType::AABB(aabb) => {
+                let (min, max) = (aabb.min(), aabb.max());
+                let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+                let corners = corners.map(|c| c + transform.translation);
+                ConvexProxy::new(&corners, 0.)
+            }
+            ShapeType::Polygon(polygon) => points(polygon.vertices(), 0.),
+            ShapeType::Triangle(triangle) => points(triangle.vertices(), 0.),
+            ShapeType::Capsule(capsule) => points(&[capsule.a(), capsule.b()], capsule.radius()),
+            ShapeType::Segment(segment) => points(&[segment.a(), segment.b()], 0.),
+            ShapeType::Point(_) => points(&[Vec2::ZERO], 0.),
+            ShapeType::Chain(_) | ShapeType::Compound(_) | ShapeType::Custom(_) => return None,
+        };
+        Some(proxy)
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.count]
+    }
+
+    pub fn radius(&self) -> Real {
+        self.radius
+    }
+
+    /// 各条边的外法线，第 i 条边从顶点 i 到顶点 i + 1
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals[..self.count]
+    }
+
+    /// 以自身每条边的法线为轴，求 `other` 相对该边的最小距离，返回其中最大的一个及对应的边。
+    /// 没有边时返回负无穷
+    fn max_separation(&self, other: &ConvexProxy) -> (usize, Real) {
+        let mut result = (0, Real::NEG_INFINITY);
+        for i in 0..self.count {
+            if self.normals[i] == Vec2::ZERO {
+                continue;
+            }
+            let separation = other
+                .vertices()
+                .iter()
+                .map(|v| self.normals[i].dot(*v - self.vertices[i]))
+                .fold(Real::INFINITY, Real::min);
+            if separation > result.1 {
+                result = (i, separation);
+            }
+        }
+        result
+    }
+
+    /// 沿 `direction` 最远的顶点的下标
+    pub fn support(&self, direction: Vec2) -> usize {
+        let mut best = 0;
+        let mut best_value = self.vertices[0].dot(direction);
+        for (i, v) in self.vertices().iter().enumerate().skip(1) {
+            let value = v.dot(direction);
+            if value > best_value {
+                best = i;
+                best_value = value;
+            }
+        }
+        best
+    }
+}
+
+/// `distance` 的结果
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DistanceOutput {
+    /// 两个形状表面之间的距离，重叠时为 0
+    pub distance: Real,
+    /// 形状 A 上离 B 最近的点
+    pub point_a: Vec2,
+    /// 形状 B 上离 A 最近的点
+    pub point_b: Vec2,
+    /// 从 A 指向 B 的单位向量，两个形状的核心（不含半径的部分）重叠时为零向量
+    pub normal: Vec2,
+    /// GJK 的迭代次数
+    pub iterations: u32,
+}
+
+/// `penetration` 的结果
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PenetrationOutput {
+    /// 从 A 指向 B 的单位向量，沿该方向移动 B `depth` 即可分开两个形状。
+    /// 形状退化（例如两个圆心重合的圆）无法确定方向时为零向量
+    pub normal: Vec2,
+    /// 侵入深度
+    pub depth: Real,
+    /// 形状 A 上侵入 B 最深的点
+    pub point_a: Vec2,
+    /// 形状 B 上侵入 A 最深的点，`point_a - point_b` 的长度为 `depth`
+    pub point_b: Vec2,
+}
+
+// 单纯形的顶点：Minkowski 差 B - A 上的点 w = wb - wa
+#[derive(Clone, Copy, Default, Debug)]
+struct SimplexVertex {
+    wa: Vec2,
+    wb: Vec2,
+    w: Vec2,
+    // 离原点最近的点的重心坐标
+    a: Real,
+    index_a: usize,
+    index_b: usize,
+}
+
+impl SimplexVertex {
+    fn new(a: &ConvexProxy, b: &ConvexProxy, index_a: usize, index_b: usize) -> SimplexVertex {
+        let wa = a.vertices[index_a];
+        let wb = b.vertices[index_b];
+        SimplexVertex {
+            wa,
+            wb,
+            w: wb - wa,
+            a: 1.,
+            index_a,
+            index_b,
+        }
+    }
+}
+
+// GJK 结束时的单纯形，最多 3 个顶点，3 个顶点时包含原点
+#[derive(Clone, Copy, Debug)]
+struct Simplex {
+    vertices: [SimplexVertex; 3],
+    count: usize,
+}
+
+impl Simplex {
+    // 下一次的搜索方向，指向原点
+    fn search_direction(&self) -> Vec2 {
+        let v = &self.vertices;
+        match self.count {
+            1 => -v[0].w,
+            _ => {
+                let e = v[1].w - v[0].w;
+                if e.cross(-v[0].w) > 0. {
+                    // 原点在边的左侧
+                    e.perp()
+                } else {
+                    -e.perp()
+                }
+            }
+        }
+    }
+
+    // 两个形状上的最近点
+    fn witness_points(&self) -> (Vec2, Vec2) {
+        let v = &self.vertices;
+        match self.count {
+            1 => (v[0].wa, v[0].wb),
+            2 => (
+                v[0].wa * v[0].a + v[1].wa * v[1].a,
+                v[0].wb * v[0].a + v[1].wb * v[1].a,
+            ),
+            _ => {
+                let p = v[0].wa * v[0].a + v[1].wa * v[1].a + v[2].wa * v[2].a;
+                (p, p)
+            }
+        }
+    }
+
+    // 把单纯形缩减为包含离原点最近的点的最小子集，并计算该点的重心坐标
+    fn solve(&mut self) {
+        match self.count {
+            2 => self.solve2(),
+            3 => self.solve3(),
+            _ => {}
+        }
+    }
+
+    fn solve2(&mut self) {
+        let w1 = self.vertices[0].w;
+        let w2 = self.vertices[1].w;
+        let e12 = w2 - w1;
+        // 原点在 w1 一侧
+        let d12_2 = -w1.dot(e12);
+        if d12_2 <= 0. {
+            self.vertices[0].a = 1.;
+            self.count = 1;
+            return;
+        }
+        // 原点在 w2 一侧
+        let d12_1 = w2.dot(e12);
+        if d12_1 <= 0. {
+            self.vertices[1].a = 1.;
+            self.vertices[0] = self.vertices[1];
+            self.count = 1;
+            return;
+        }
+        let inv = 1. / (d12_1 + d12_2);
+        self.vertices[0].a = d12_1 * inv;
+        self.vertices[1].a = d12_2 * inv;
+    }
+
+    fn solve3(&mut self) {
+        let [v1, v2, v3] = self.vertices;
+        let (w1, w2, w3) = (v1.w, v2.w, v3.w);
+
+        let e12 = w2 - w1;
+        let d12_1 = w2.dot(e12);
+        let d12_2 = -w1.dot(e12);
+
+        let e13 = w3 - w1;
+        let d13_1 = w3.dot(e13);
+        let d13_2 = -w1.dot(e13);
+
+        let e23 = w3 - w2;
+        let d23_1 = w3.dot(e23);
+        let d23_2 = -w2.dot(e23);
+
+        // 三角形的面积坐标
+        let n123 = e12.cross(e13);
+        let d123_1 = n123 * w2.cross(w3);
+        let d123_2 = n123 * w3.cross(w1);
+        let d123_3 = n123 * w1.cross(w2);
+
+        let set = |simplex: &mut Simplex, vertices: &[(SimplexVertex, Real)]| {
+            for (i, (v, a)) in vertices.iter().enumerate() {
+                simplex.vertices[i] = *v;
+                simplex.vertices[i].a = *a;
+            }
+            simplex.count = vertices.len();
+        };
+
+        if d12_2 <= 0. && d13_2 <= 0. {
+            set(self, &[(v1, 1.)]);
+        } else if d12_1 > 0. && d12_2 > 0. && d123_3 <= 0. {
+            let inv = 1. / (d12_1 + d12_2);
+            set(self, &[(v1, d12_1 * inv), (v2, d12_2 * inv)]);
+        } else if d13_1 > 0. && d13_2 > 0. && d123_2 <= 0. {
+            let inv = 1. / (d13_1 + d13_2);
+            set(self, &[(v1, d13_1 * inv), (v3, d13_2 * inv)]);
+        } else if d12_1 <= 0. && d23_2 <= 0. {
+            set(self, &[(v2, 1.)]);
+        } else if d13_1 <= 0. && d23_1 <= 0. {
+            set(self, &[(v3, 1.)]);
+        } else if d23_1 > 0. && d23_2 > 0. && d123_1 <= 0. {
+            let inv = 1. / (d23_1 + d23_2);
+            set(self, &[(v3, d23_2 * inv), (v2, d23_1 * inv)]);
+        } else {
+            // 原点在三角形内部
+            let inv = 1. / (d123_1 + d123_2 + d123_3);
+            set(
+                self,
+                &[(v1, d123_1 * inv), (v2, d123_2 * inv), (v3, d123_3 * inv)],
+            );
+        }
+    }
+}
+
+/// 用 GJK 计算两个凸形状之间的距离和最近点。
+/// 形状重叠时距离为 0，两个最近点重合在重叠区域中
+pub fn distance(a: &ConvexProxy, b: &ConvexProxy) -> DistanceOutput {
+    let (simplex, iterations) = gjk(a, b);
+    let (mut point_a, mut point_b) = simplex.witness_points();
+    let core_distance = point_a.distance(point_b);
+    let radius = a.radius + b.radius;
+    if simplex.count == 3 || core_distance < Real::EPSILON {
+        // 核心重叠，无法确定方向
+        let p = (point_a + point_b) * 0.5;
+        return DistanceOutput {
+            distance: 0.,
+            point_a: p,
+            point_b: p,
+            normal: Vec2::ZERO,
+            iterations,
+        };
+    }
+    let normal = (point_b - point_a) / core_distance;
+    let distance = if core_distance > radius {
+        point_a += normal * a.radius;
+        point_b -= normal * b.radius;
+        core_distance - radius
+    } else {
+        // 只有扩大的部分重叠，取两个表面之间的中点
+        let p = (point_a + normal * a.radius + point_b - normal * b.radius) * 0.5;
+        point_a = p;
+        point_b = p;
+        0.
+    };
+    DistanceOutput {
+        distance,
+        point_a,
+        point_b,
+        normal,
+        iterations,
+    }
+}
+
+/// 按 `transform_a` 和 `transform_b` 放置的两个形状之间的距离，参见 `distance`。
+/// 任意一个形状不是凸形状时返回 `None`
+pub fn shape_distance(
+    shape_a: &ShapeType,
+    transform_a: &Transform2D,
+    shape_b: &ShapeType,
+    transform_b: &Transform2D,
+) -> Option<DistanceOutput> {
+    let a = ConvexProxy::from_shape(shape_a, transform_a)?;
+    let b = ConvexProxy::from_shape(shape_b, transform_b)?;
+    Some(distance(&a, &b))
+}
+
+/// 两个凸形状重叠时求侵入深度和法线，不重叠时返回 `None`。
+/// 只有半径部分重叠时直接由 GJK 的最近点得到，核心也重叠时用 EPA 在 Minkowski 差上寻找离原点最近的边
+pub fn penetration(a: &ConvexProxy, b: &ConvexProxy) -> Option<PenetrationOutput> {
+    let (simplex, _) = gjk(a, b);
+    let (point_a, point_b) = simplex.witness_points();
+    let core_distance = point_a.distance(point_b);
+    let radius = a.radius + b.radius;
+    let (normal, core_depth, point_a, point_b) =
+        if simplex.count < 3 && core_distance >= Real::EPSILON {
+            if core_distance >= radius {
+                return None;
+            }
+            let normal = (point_b - point_a) / core_distance;
+            (normal, -core_distance, point_a, point_b)
+        } else {
+            epa(a, b, simplex)
+        };
+    Some(PenetrationOutput {
+        normal,
+        depth: core_depth + radius,
+        point_a: point_a + normal * a.radius,
+        point_b: point_b - normal * b.radius,
+    })
+}
+
+/// 用分离轴定理检测两个多边形类的凸形状（AABB、旋转的矩形、多边形和三角形，也可以是线段和点），不考虑半径。
+/// 分别以两个形状每条边的法线作为分离轴，任意一个轴上的投影不重叠则两个形状不相交，返回 `None`；
+/// 否则以重叠最小的轴所在的边作为参考边，用参考边的两个侧面裁剪另一个形状上的入射边，
+/// 得到从 A 指向 B 的法线、侵入量和最多两个碰撞点
+pub fn sat(a: &ConvexProxy, b: &ConvexProxy) -> Option<ContactManifold> {
+    let (edge_a, separation_a) = a.max_separation(b);
+    if separation_a > 0. {
+        return None;
+    }
+    let (edge_b, separation_b) = b.max_separation(a);
+    if separation_b > 0. {
+        return None;
+    }
+    if separation_a == Real::NEG_INFINITY && separation_b == Real::NEG_INFINITY {
+        // 两个形状都只有一个顶点，没有分离轴
+        return None;
+    }
+    // 分离量接近时优先选择 A 的边，避免参考边来回切换
+    const TOLERANCE: Real = 0.0005;
+    let (reference, incident, edge, flip) = if separation_b > separation_a + TOLERANCE {
+        (b, a, edge_b, true)
+    } else {
+        (a, b, edge_a, false)
+    };
+    let normal = reference.normals[edge];
+
+    // 入射边是法线与参考边法线最接近反向的边
+    let incident_edge = (0..incident.count)
+        .min_by(|&i, &j| {
+            incident.normals[i]
+                .dot(normal)
+                .total_cmp(&incident.normals[j].dot(normal))
+        })
+        .unwrap_or(0);
+    let mut points = [
+        incident.vertices[incident_edge],
+        incident.vertices[(incident_edge + 1) % incident.count],
+    ];
+
+    // 用参考边两端的侧面裁剪入射边
+    let v1 = reference.vertices[edge];
+    let v2 = reference.vertices[(edge + 1) % reference.count];
+    let tangent = (v2 - v1).normalize();
+    if !clip_segment(&mut points, -tangent, -tangent.dot(v1))
+        || !clip_segment(&mut points, tangent, tangent.dot(v2))
+    {
+        return None;
+    }
+
+    // 只保留位于参考边后方的点，入射的形状只有一个顶点时两个点重合
+    let mut contacts = vec![];
+    let mut penetration: Real = 0.;
+    for point in points {
+        let separation = normal.dot(point - v1);
+        if separation <= 0. && !contacts.contains(&point) {
+            penetration = penetration.max(-separation);
+            contacts.push(point);
+        }
+    }
+    if contacts.is_empty() {
+        return None;
+    }
+    Some(ContactManifold {
+        normal: if flip { -normal } else { normal },
+        penetration,
+        points: contacts,
+    })
+}
+
+// 用半平面 `normal·p <= offset` 裁剪线段，线段完全在半平面外时返回 false
+fn clip_segment(points: &mut [Vec2; 2], normal: Vec2, offset: Real) -> bool {
+    let d0 = normal.dot(points[0]) - offset;
+    let d1 = normal.dot(points[1]) - offset;
+    if d0 > 0. && d1 > 0. {
+        return false;
+    }
+    if d0 * d1 < 0. {
+        let clipped = points[0] + (points[1] - points[0]) * (d0 / (d0 - d1));
+        if d0 > 0. {
+            points[0] = clipped;
+        } else {
+            points[1] = clipped;
+        }
+    }
+    true
+}
+
+/// 按 `transform_a` 和 `transform_b` 放置的两个形状的侵入深度，参见 `penetration`。
+/// 任意一个形状不是凸形状时返回 `None`
+pub fn shape_penetration(
+    shape_a: &ShapeType,
+    transform_a: &Transform2D,
+    shape_b: &ShapeType,
+    transform_b: &Transform2D,
+) -> Option<PenetrationOutput> {
+    let a = ConvexProxy::from_shape(shape_a, transform_a)?;
+    let b = ConvexProxy::from_shape(shape_b, transform_b)?;
+    penetration(&a, &b)
+}
+
+// 不考虑半径，求 Minkowski 差 B - A 上离原点最近的单纯形，返回单纯形和迭代次数
+fn gjk(a: &ConvexProxy, b: &ConvexProxy) -> (Simplex, u32) {
+    let mut simplex = Simplex {
+        vertices: [SimplexVertex::new(a, b, 0, 0); 3],
+        count: 1,
+    };
+    let mut iterations = 0;
+    loop {
+        let saved = simplex;
+        simplex.solve();
+        if simplex.count == 3 {
+            break;
+        }
+        let d = simplex.search_direction();
+        if d.length_squared() < Real::EPSILON * Real::EPSILON {
+            // 原点在单纯形上，两个形状的核心接触
+            break;
+        }
+        if iterations == MAX_GJK_ITERATIONS {
+            break;
+        }
+        let vertex = SimplexVertex::new(a, b, a.support(-d), b.support(d));
+        iterations += 1;
+        // 新的顶点已经在单纯形中，无法再靠近原点
+        let duplicate = saved.vertices[..saved.count]
+            .iter()
+            .any(|v| v.index_a == vertex.index_a && v.index_b == vertex.index_b);
+        if duplicate {
+            break;
+        }
+        simplex.vertices[simplex.count] = vertex;
+        simplex.count += 1;
+    }
+    (simplex, iterations)
+}
+
+// 核心重叠时从 GJK 的单纯形开始扩展多边形，直到找到 Minkowski 差上离原点最近的边。
+// 返回 `(A 指向 B 的法线, 核心的侵入深度, A 上的点, B 上的点)`
+fn epa(a: &ConvexProxy, b: &ConvexProxy, simplex: Simplex) -> (Vec2, Real, Vec2, Vec2) {
+    let mut polytope = simplex.vertices[..simplex.count].to_vec();
+    if !expand_to_triangle(a, b, &mut polytope) {
+        // Minkowski 差退化为点或者线段，无法确定方向
+        let (point_a, point_b) = simplex.witness_points();
+        return (Vec2::ZERO, 0., point_a, point_b);
+    }
+    // 保证多边形按逆时针排列，此时边的右侧是外侧
+    if (polytope[1].w - polytope[0].w).cross(polytope[2].w - polytope[0].w) < 0. {
+        polytope.swap(1, 2);
+    }
+    let mut closest = closest_edge(&polytope);
+    for _ in 0..MAX_EPA_ITERATIONS {
+        let (edge, normal, distance) = closest;
+        let vertex = SimplexVertex::new(a, b, a.support(-normal), b.support(normal));
+        let duplicate = polytope
+            .iter()
+            .any(|v| v.index_a == vertex.index_a && v.index_b == vertex.index_b);
+        if duplicate || vertex.w.dot(normal) - distance < EPA_TOLERANCE {
+            break;
+        }
+        insert_convex(&mut polytope, edge + 1, vertex);
+        closest = closest_edge(&polytope);
+    }
+    let (edge, normal, distance) = closest;
+    let v1 = polytope[edge];
+    let v2 = polytope[(edge + 1) % polytope.len()];
+    // 原点在最近的边上的投影
+    let e = v2.w - v1.w;
+    let t = (-v1.w.dot(e) / e.length_squared()).clamp(0., 1.);
+    // Minkowski 差的外法线指向 A 指向 B 的反方向
+    (
+        -normal,
+        distance,
+        v1.wa.lerp(v2.wa, t),
+        v1.wb.lerp(v2.wb, t),
+    )
+}
+
+// 把 GJK 结束时少于 3 个顶点的单纯形（原点在顶点或边上）扩展为三角形，
+// Minkowski 差没有面积时返回 false
+fn expand_to_triangle(a: &ConvexProxy, b: &ConvexProxy, polytope: &mut Vec<SimplexVertex>) -> bool {
+    let epsilon = Real::EPSILON * 100.;
+    if polytope.len() == 1 {
+        let directions = [Vec2::X, -Vec2::X, Vec2::Y, -Vec2::Y];
+        let found = directions.into_iter().find_map(|d| {
+            let v = SimplexVertex::new(a, b, a.support(-d), b.support(d));
+            (v.w.distance(polytope[0].w) > epsilon).then_some(v)
+        });
+        let Some(v) = found else {
+            return false;
+        };
+        polytope.push(v);
+    }
+    if polytope.len() == 2 {
+        let Some(side) = (polytope[1].w - polytope[0].w)
+            .try_normalize()
+            .map(Vec2::perp)
+        else {
+            return false;
+        };
+        let found = [side, -side].into_iter().find_map(|d| {
+            let v = SimplexVertex::new(a, b, a.support(-d), b.support(d));
+            ((v.w - polytope[0].w).dot(d) > epsilon).then_some(v)
+        });
+        let Some(v) = found else {
+            return false;
+        };
+        polytope.push(v);
+    }
+    true
+}
+
+// 在逆时针排列的凸多边形的 `index` 处插入顶点，并移除因此变为凹点的相邻顶点。
+// GJK 得到的初始顶点不一定在 Minkowski 差的边界上，插入新的顶点后可能不再是凸多边形
+fn insert_convex(polytope: &mut Vec<SimplexVertex>, index: usize, vertex: SimplexVertex) {
+    polytope.insert(index, vertex);
+    let mut index = index;
+    // 三个点不是左转时中间的点是凹点或者共线
+    let is_convex = |p: Vec2, q: Vec2, r: Vec2| (q - p).cross(r - q) > 0.;
+    while polytope.len() > 3 {
+        let n = polytope.len();
+        let prev = (index + n - 1) % n;
+        let before = (index + n - 2) % n;
+        if is_convex(polytope[before].w, polytope[prev].w, polytope[index].w) {
+            break;
+        }
+        polytope.remove(prev);
+        if prev < index {
+            index -= 1;
+        }
+    }
+    while polytope.len() > 3 {
+        let n = polytope.len();
+        let next = (index + 1) % n;
+        let after = (index + 2) % n;
+        if is_convex(polytope[index].w, polytope[next].w, polytope[after].w) {
+            break;
+        }
+        polytope.remove(next);
+        if next < index {
+            index -= 1;
+        }
+    }
+}
+
+// 逆时针排列的多边形中离原点最近的边，返回 `(边的下标, 外法线, 原点到边的距离)`
+fn closest_edge(polytope: &[SimplexVertex]) -> (usize, Vec2, Real) {
+    let mut best = (0, Vec2::ZERO, Real::INFINITY);
+    for i in 0..polytope.len() {
+        let e = polytope[(i + 1) % polytope.len()].w - polytope[i].w;
+        let Some(normal) = Vec2::new(e.y, -e.x).try_normalize() else {
+            continue;
+        };
+        let distance = normal.dot(polytope[i].w);
+        if distance < best.2 {
+            best = (i, normal, distance);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rot::Rot,
+        shape::{Capsule, Circle, Polygon, AABB},
+    };
+
+    fn place(shape: &ShapeType, x: Real, y: Real, angle: Real) -> ConvexProxy {
+        let transform = Transform2D::new(Vec2::new(x, y), Rot::from_angle(angle));
+        ConvexProxy::from_shape(shape, &transform).unwrap()
+    }
+
+    #[test]
+    fn distance_between_separated_shapes() {
+        let circle = ShapeType::Circle(Circle::new(1.));
+        let square = ShapeType::AABB(AABB::from_half_extents(Vec2::new(1., 1.)));
+        let output = distance(&place(&circle, 0., 0., 0.), &place(&square, 5., 0.5, 0.));
+        assert!((output.distance - 3.).abs() < 1e-5);
+        assert!((output.point_a - Vec2::new(1., 0.)).length() < 1e-5);
+        assert!((output.point_b - Vec2::new(4., 0.)).length() < 1e-5);
+        assert!((output.normal - Vec2::X).length() < 1e-5);
+
+        // 旋转 45 度的正方形，最近点是它的顶点
+        let polygon = ShapeType::Polygon(Polygon::new_box(Vec2::new(1., 1.)));
+        let output = distance(
+            &place(&polygon, 0., 0., 0.),
+            &place(&polygon, 4., 0., crate::math::consts::FRAC_PI_4),
+        );
+        let expected = 4. - 1. - Real::sqrt(2.);
+        assert!((output.distance - expected).abs() < 1e-5);
+        assert!((output.point_b - Vec2::new(4. - Real::sqrt(2.), 0.)).length() < 1e-5);
+    }
+
+    #[test]
+    fn overlapping_shapes_have_zero_distance() {
+        let capsule = ShapeType::Capsule(Capsule::new(Vec2::new(-1., 0.), Vec2::new(1., 0.), 0.5));
+        let polygon = ShapeType::Polygon(Polygon::new_box(Vec2::new(1., 1.)));
+        // 只有胶囊的半径部分与方块重叠
+        let output = distance(&place(&capsule, 0., 1.2, 0.), &place(&polygon, 0., 0., 0.));
+        assert_eq!(output.distance, 0.);
+        assert!((output.normal - (-Vec2::Y)).length() < 1e-5);
+        // 核心重叠
+        let output = distance(
+            &place(&polygon, 0., 0., 0.3),
+            &place(&polygon, 0.5, 0.2, 0.),
+        );
+        assert_eq!(output.distance, 0.);
+        assert_eq!(output.normal, Vec2::ZERO);
+    }
+
+    #[test]
+    fn penetration_of_overlapping_shapes() {
+        let square = ShapeType::AABB(AABB::from_half_extents(Vec2::new(1., 1.)));
+        let circle = ShapeType::Circle(Circle::new(0.5));
+        // 圆心在方块内部，靠近左边
+        let output =
+            penetration(&place(&square, 0., 0., 0.), &place(&circle, -0.6, 0.1, 0.)).unwrap();
+        assert!((output.normal - (-Vec2::X)).length() < 1e-5);
+        assert!((output.depth - 0.9).abs() < 1e-5);
+        assert!((output.point_a - Vec2::new(-1., 0.1)).length() < 1e-5);
+        assert!((output.point_b - Vec2::new(-0.1, 0.1)).length() < 1e-5);
+
+        // 核心线段相交的两个胶囊，向上推开最浅
+        let horizontal =
+            ShapeType::Capsule(Capsule::new(Vec2::new(-2., 0.), Vec2::new(2., 0.), 0.5));
+        let vertical = ShapeType::Capsule(Capsule::new(Vec2::new(0., -1.), Vec2::new(0., 1.), 0.5));
+        let output = penetration(
+            &place(&horizontal, 0., 0., 0.),
+            &place(&vertical, 0.5, 0.5, 0.),
+        )
+        .unwrap();
+        assert!((output.normal - Vec2::Y).length() < 1e-5);
+        assert!((output.depth - 1.5).abs() < 1e-5);
+
+        // 分离的形状没有侵入
+        assert!(penetration(&place(&square, 0., 0., 0.), &place(&circle, 3., 0., 0.)).is_none());
+    }
+
+    #[test]
+    fn sat_clips_contact_points() {
+        let square = ShapeType::AABB(AABB::from_half_extents(Vec2::new(1., 1.)));
+        let polygon = ShapeType::Polygon(Polygon::new_box(Vec2::new(0.5, 0.5)));
+        // 小方块压在大方块的上边（y 轴向下），得到两个碰撞点
+        let output = sat(&place(&square, 0., 0., 0.), &place(&polygon, 0.2, -1.4, 0.)).unwrap();
+        assert!((output.normal - (-Vec2::Y)).length() < 1e-5);
+        assert!((output.penetration - 0.1).abs() < 1e-5);
+        assert_eq!(output.points.len(), 2);
+        for point in &output.points {
+            assert!((point.y + 0.9).abs() < 1e-5);
+            assert!(point.x > -0.31 && point.x < 0.71);
+        }
+
+        // 旋转 45 度的方块只有一个角侵入
+        let output = sat(
+            &place(&square, 0., 0., 0.),
+            &place(&polygon, 1.6, 0., crate::math::consts::FRAC_PI_4),
+        )
+        .unwrap();
+        assert!((output.normal - Vec2::X).length() < 1e-5);
+        assert_eq!(output.points.len(), 1);
+
+        let output = sat(&place(&square, 0., 0., 0.), &place(&polygon, 2., 0., 0.));
+        assert!(output.is_none());
+    }
+
+    #[test]
+    fn concave_shapes_have_no_proxy() {
+        let chain = ShapeType::Chain(crate::shape::Chain::new(&[Vec2::ZERO, Vec2::X, Vec2::Y]));
+        assert!(ConvexProxy::from_shape(&chain, &Transform2D::default()).is_none());
+    }
+}
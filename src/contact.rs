@@ -0,0 +1,24 @@
+use crate::{math::Real, vec2::Vec2};
+
+/// 上一次 step 中检测到的一个接触点
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Contact {
+    /// 物体 A 在 world 中的下标
+    pub a: usize,
+    /// 物体 B 在 world 中的下标
+    pub b: usize,
+    /// 接触点的位置
+    pub point: Vec2,
+    /// 从 A 指向 B 的碰撞法线，单位向量
+    pub normal: Vec2,
+    /// 两个物体的侵入量
+    pub penetration: Real,
+    /// 求解碰撞之前 B 在接触点处相对 A 的速度
+    pub relative_velocity: Vec2,
+    /// 物体 A 发生碰撞的子形状，复合形状为子形状的下标，折线为边的下标，其他形状为 0
+    pub sub_shape_a: usize,
+    /// 物体 B 发生碰撞的子形状
+    pub sub_shape_b: usize,
+    /// 该接触点已经连续存在的 step 数，刚出现的接触为 0
+    pub age: u32,
+}
@@ -0,0 +1,154 @@
+use crate::{body::Body, hash::split_mix64, math::Real, vec2::Vec2, world::World};
+
+/// 两次模拟第一次出现差异的位置
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Divergence {
+    /// 出现差异的 step，从 1 开始，0 表示场景刚创建时就不同
+    pub step: usize,
+    /// 出现差异的物体在第一次模拟中的下标
+    pub body: usize,
+    /// 两次模拟中该物体的位置
+    pub positions: (Vec2, Vec2),
+    /// 两次模拟中该物体的速度
+    pub velocities: (Vec2, Vec2),
+}
+
+/// 用 `scene_builder` 创建两次场景，各自执行 `steps` 次 step，
+/// 逐个 step 比较所有物体的位置、速度和朝向是否完全相同（按位比较）。
+/// 返回第一个出现差异的物体，完全一致时返回 `None`
+pub fn run_twice_and_compare<F>(mut scene_builder: F, steps: usize) -> Option<Divergence>
+where
+    F: FnMut() -> World,
+{
+    let mut first = scene_builder();
+    let mut second = scene_builder();
+    let order: Vec<usize> = (0..first.get_bodies().len()).collect();
+    compare_runs(&mut first, &mut second, &order, steps, 0.)
+}
+
+/// 与 `run_twice_and_compare` 类似，但第二次模拟按照由 `seed` 打乱的顺序加入物体，
+/// 用来检查结果是否依赖物体的加入顺序。
+/// `scene_builder` 返回不含物体的 world 和所有物体，world 中不能有按下标引用物体的设置，
+/// 例如轨道约束。打乱顺序后求解的顺序也会改变，位置和速度的差不超过 `tolerance` 时视为相同
+pub fn run_shuffled_and_compare<F>(
+    mut scene_builder: F,
+    steps: usize,
+    seed: u64,
+    tolerance: Real,
+) -> Option<Divergence>
+where
+    F: FnMut() -> (World, Vec<Body>),
+{
+    let (mut first, bodies) = scene_builder();
+    for body in bodies {
+        first.add_body(body);
+    }
+
+    let (mut second, bodies) = scene_builder();
+    let mut order: Vec<usize> = (0..bodies.len()).collect();
+    // Fisher-Yates 洗牌
+    for i in (1..order.len()).rev() {
+        let j = (split_mix64(seed.wrapping_add(i as u64)) % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+    let mut bodies: Vec<Option<Body>> = bodies.into_iter().map(Some).collect();
+    for &i in &order {
+        second.add_body(bodies[i].take().expect("each body is added once"));
+    }
+    compare_runs(&mut first, &mut second, &order, steps, tolerance)
+}
+
+// `second` 的第 k 个物体对应 `first` 的第 `order[k]` 个物体
+fn compare_runs(
+    first: &mut World,
+    second: &mut World,
+    order: &[usize],
+    steps: usize,
+    tolerance: Real,
+) -> Option<Divergence> {
+    for step in 0..=steps {
+        if step > 0 {
+            first.step();
+            second.step();
+        }
+        for (k, &i) in order.iter().enumerate() {
+            let a = first.get_bodies()[i].borrow();
+            let b = second.get_bodies()[k].borrow();
+            let same = if tolerance > 0. {
+                a.position().distance(b.position()) <= tolerance
+                    && a.velocity().distance(b.velocity()) <= tolerance
+                    && (a.rotation().angle() - b.rotation().angle()).abs() <= tolerance
+            } else {
+                bits(a.position()) == bits(b.position())
+                    && bits(a.velocity()) == bits(b.velocity())
+                    && a.rotation().angle().to_bits() == b.rotation().angle().to_bits()
+            };
+            if !same {
+                return Some(Divergence {
+                    step,
+                    body: i,
+                    positions: (a.position(), b.position()),
+                    velocities: (a.velocity(), b.velocity()),
+                });
+            }
+        }
+    }
+    None
+}
+
+// 不开启 f64 特性时 to_bits 返回 u32
+#[allow(clippy::unnecessary_cast)]
+fn bits(v: Vec2) -> [u64; 2] {
+    [v.x.to_bits() as u64, v.y.to_bits() as u64]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{Circle, AABB};
+
+    fn scene() -> (World, Vec<Body>) {
+        let world = World::new(1. / 60., 10, 1.);
+        let mut ground = Body::new_aabb(
+            AABB::from_half_extents(Vec2::new(100., 5.)),
+            Vec2::new(0., 50.),
+            0.5,
+        );
+        ground.make_static();
+        let mut bodies = vec![ground];
+        for i in 0..5 {
+            let position = Vec2::new(i as Real * 3., 40. - i as Real * 12.);
+            bodies.push(Body::new_circle(Circle::new(1.), position, 0.5));
+        }
+        (world, bodies)
+    }
+
+    /// Test cases for:
+    /// * run_twice_and_compare(F, usize)
+    /// * run_shuffled_and_compare(F, usize, u64, Real)
+    #[test]
+    fn determinism_harness_should_work() {
+        let build = || {
+            let (mut world, bodies) = scene();
+            for body in bodies {
+                world.add_body(body);
+            }
+            world
+        };
+        assert_eq!(run_twice_and_compare(build, 120), None);
+        assert_eq!(run_shuffled_and_compare(scene, 120, 7, 0.001), None);
+
+        // 两次场景不同时报告第一个不同的物体
+        let mut offset = 0.;
+        let divergence = run_twice_and_compare(
+            || {
+                offset += 1.;
+                let mut world = World::new(1. / 60., 10, 1.);
+                world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(offset, 0.), 0.5));
+                world
+            },
+            10,
+        );
+        assert_eq!(divergence.map(|d| (d.step, d.body)), Some((0, 0)));
+    }
+}
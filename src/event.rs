@@ -0,0 +1,52 @@
+use crate::math::Real;
+
+/// 一次 step 中产生的碰撞事件
+/// 只有开启了对应上报开关的物体才会产生事件，参见 `Body::set_report_contacts`
+/// 和 `Body::set_report_sensor_overlaps`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CollisionEvent {
+    /// 两个物体开始接触
+    ContactStarted { a: usize, b: usize },
+    /// 两个物体不再接触
+    ContactEnded { a: usize, b: usize },
+    /// 物体进入传感器
+    SensorEntered { sensor: usize, other: usize },
+    /// 物体离开传感器
+    SensorExited { sensor: usize, other: usize },
+}
+
+/// 粗测阶段物体对的变化，参见 `World::pair_events`。
+/// 两个物体扩大后的包围盒开始重叠时新增物体对，不再重叠时移除。
+/// 物体对被移除时两个物体已经不再接触，它们之间的 `ContactEnded` 不会晚于 `Removed` 产生
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PairEvent {
+    Added { a: usize, b: usize },
+    Removed { a: usize, b: usize },
+}
+
+/// 两个物体开始接触时的冲击，用于根据材质播放碰撞音效
+/// 与 `CollisionEvent::ContactStarted` 一样，只有开启了 `Body::set_report_contacts` 的物体才会产生
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ImpactEvent {
+    /// 物体 A 在 world 中的下标
+    pub a: usize,
+    /// 物体 B 在 world 中的下标
+    pub b: usize,
+    /// 物体 A 的表面材质，参见 `Body::set_surface_id`
+    pub surface_a: u32,
+    /// 物体 B 的表面材质
+    pub surface_b: u32,
+    /// 本次碰撞的法向冲量
+    pub impulse: Real,
+    /// 物体 A 发生碰撞的子形状，复合形状为子形状的下标，折线为边的下标，其他形状为 0
+    pub sub_shape_a: usize,
+    /// 物体 B 发生碰撞的子形状
+    pub sub_shape_b: usize,
+}
+
+impl ImpactEvent {
+    /// `(surface_a, surface_b, impulse)`
+    pub fn as_tuple(&self) -> (u32, u32, Real) {
+        (self.surface_a, self.surface_b, self.impulse)
+    }
+}
@@ -0,0 +1,266 @@
+use std::{cmp::Ordering, ops};
+
+/// Signed 16.16 fixed-point number.
+///
+/// All operations are plain integer arithmetic, so results are bit-exact on every platform,
+/// which is what lockstep simulations need. Multiplication and division round towards
+/// negative infinity and wrap on overflow.
+///
+/// The physics pipeline does not use `Fixed` yet: [`Real`](crate::math::Real) is still `f32`
+/// (or `f64` with the `f64` feature), so `Vec2`, `Body` and `World` are not bit-exact across
+/// platforms. `Fixed` is only a standalone scalar for deterministic game logic around them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    /// number of fractional bits
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+    pub const HALF: Self = Self(1 << (Self::FRAC_BITS - 1));
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+    /// the smallest positive value
+    pub const EPSILON: Self = Self(1);
+
+    /// creates a `Fixed` from its raw 16.16 representation
+    #[inline(always)]
+    pub const fn from_bits(bits: i32) -> Fixed {
+        Fixed(bits)
+    }
+
+    /// returns the raw 16.16 representation
+    #[inline(always)]
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    /// creates a `Fixed` from an integer, wrapping if it does not fit in 16 bits
+    #[inline(always)]
+    pub const fn from_int(v: i32) -> Fixed {
+        Fixed(v.wrapping_shl(Self::FRAC_BITS))
+    }
+
+    /// Converts a float to the nearest `Fixed`, saturating at [`Fixed::MIN`] and [`Fixed::MAX`].
+    /// Only use this when loading data, the conversion itself is not guaranteed to be identical
+    /// across platforms for values that are not exactly representable.
+    #[inline]
+    pub fn from_f32(v: f32) -> Fixed {
+        Fixed((v as f64 * Self::ONE.0 as f64).round() as i32)
+    }
+
+    /// converts `self` to the nearest `f32`
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    /// Returns the integer part, rounding towards negative infinity.
+    #[inline]
+    pub const fn floor_to_int(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    #[inline]
+    pub const fn abs(self) -> Fixed {
+        Fixed(self.0.wrapping_abs())
+    }
+
+    #[inline]
+    pub fn min(self, rhs: Fixed) -> Fixed {
+        Ord::min(self, rhs)
+    }
+
+    #[inline]
+    pub fn max(self, rhs: Fixed) -> Fixed {
+        Ord::max(self, rhs)
+    }
+
+    /// Restricts `self` to `[min, max]`, see [`Ord::clamp`].
+    #[inline]
+    pub fn clamp(self, min: Fixed, max: Fixed) -> Fixed {
+        Ord::clamp(self, min, max)
+    }
+
+    /// Returns the square root, rounded down. Negative values return zero.
+    pub fn sqrt(self) -> Fixed {
+        if self.0 <= 0 {
+            return Fixed::ZERO;
+        }
+        // sqrt(x / 2^16) * 2^16 = sqrt(x * 2^16)
+        let mut n = (self.0 as u64) << Self::FRAC_BITS;
+        let mut root = 0u64;
+        let mut bit = 1u64 << 62;
+        while bit > n {
+            bit >>= 2;
+        }
+        while bit != 0 {
+            if n >= root + bit {
+                n -= root + bit;
+                root = (root >> 1) + bit;
+            } else {
+                root >>= 1;
+            }
+            bit >>= 2;
+        }
+        Fixed(root as i32)
+    }
+
+    /// Returns the result of dividing `self` by `rhs`, or `None` if `rhs` is zero.
+    #[inline]
+    pub fn checked_div(self, rhs: Fixed) -> Option<Fixed> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let (n, d) = ((self.0 as i64) << Self::FRAC_BITS, rhs.0 as i64);
+        // integer division truncates towards zero, step down once more when the
+        // remainder has the opposite sign of the divisor
+        let q = if n % d != 0 && (n % d < 0) != (d < 0) {
+            n / d - 1
+        } else {
+            n / d
+        };
+        Some(Fixed(q as i32))
+    }
+}
+
+impl ops::Add for Fixed {
+    type Output = Fixed;
+
+    #[inline]
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl ops::AddAssign for Fixed {
+    #[inline]
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub for Fixed {
+    type Output = Fixed;
+
+    #[inline]
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl ops::SubAssign for Fixed {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Fixed) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Mul for Fixed {
+    type Output = Fixed;
+
+    #[inline]
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+    }
+}
+
+impl ops::MulAssign for Fixed {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+impl ops::Div for Fixed {
+    type Output = Fixed;
+
+    /// # Panics
+    ///
+    /// will panic if `rhs` is zero
+    #[inline]
+    fn div(self, rhs: Fixed) -> Fixed {
+        self.checked_div(rhs).expect("division by zero")
+    }
+}
+
+impl ops::DivAssign for Fixed {
+    #[inline]
+    fn div_assign(&mut self, rhs: Fixed) {
+        *self = *self / rhs;
+    }
+}
+
+impl ops::Neg for Fixed {
+    type Output = Fixed;
+
+    #[inline]
+    fn neg(self) -> Fixed {
+        Fixed(self.0.wrapping_neg())
+    }
+}
+
+impl From<i16> for Fixed {
+    fn from(v: i16) -> Self {
+        Fixed::from_int(v as i32)
+    }
+}
+
+impl PartialEq<i32> for Fixed {
+    fn eq(&self, other: &i32) -> bool {
+        *self == Fixed::from_int(*other)
+    }
+}
+
+impl PartialOrd<i32> for Fixed {
+    fn partial_cmp(&self, other: &i32) -> Option<Ordering> {
+        Some(self.cmp(&Fixed::from_int(*other)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * Fixed + Fixed
+    /// * Fixed - Fixed
+    /// * Fixed * Fixed
+    /// * Fixed / Fixed
+    #[test]
+    fn fixed_arithmetic_should_work() {
+        let a = Fixed::from_f32(2.5);
+        let b = Fixed::from_int(-4);
+        assert_eq!(a + b, Fixed::from_f32(-1.5));
+        assert_eq!(a - b, Fixed::from_f32(6.5));
+        assert_eq!(a * b, -10);
+        assert_eq!(b / a, Fixed::from_f32(-1.6));
+        assert_eq!(a.checked_div(Fixed::ZERO), None);
+        assert_eq!(Fixed::from_f32(-1.5).floor_to_int(), -2);
+        assert!(Fixed::HALF < 1);
+    }
+
+    /// Test cases for:
+    /// * Fixed.checked_div(Fixed)
+    #[test]
+    fn fixed_division_should_round_towards_negative_infinity() {
+        let two = Fixed::from_int(2);
+        assert_eq!(Fixed::EPSILON / two, Fixed::ZERO);
+        assert_eq!(Fixed::EPSILON / -two, Fixed::from_bits(-1));
+        assert_eq!(-Fixed::EPSILON / two, Fixed::from_bits(-1));
+        assert_eq!(-Fixed::EPSILON / -two, Fixed::ZERO);
+        // 1 / -3 = -0.33333..., the raw quotient is -21845.33
+        assert_eq!(Fixed::ONE / Fixed::from_int(-3), Fixed::from_bits(-21846));
+        assert_eq!(Fixed::from_int(-6) / -two, 3);
+    }
+
+    /// Test cases for:
+    /// * Fixed.sqrt()
+    #[test]
+    fn fixed_sqrt_should_work() {
+        assert_eq!(Fixed::from_int(16).sqrt(), 4);
+        assert_eq!(Fixed::from_f32(2.25).sqrt(), Fixed::from_f32(1.5));
+        assert_eq!(Fixed::from_int(-1).sqrt(), Fixed::ZERO);
+        assert!((Fixed::from_int(2).sqrt().to_f32() - 2f32.sqrt()).abs() < 1e-4);
+    }
+}
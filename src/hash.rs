@@ -0,0 +1,16 @@
+// 确定性的整数哈希，用于需要可复现结果的伪随机数
+
+use crate::math::Real;
+
+/// SplitMix64 哈希
+pub(crate) fn split_mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// 把哈希值映射到 `[0, 1)` 之间的浮点数
+pub(crate) fn unit_float(hash: u64) -> Real {
+    (hash >> 40) as Real / (1u64 << 24) as Real
+}
@@ -0,0 +1,91 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use crate::{body::Body, math::Real, rot::Rot, vec2::Vec2};
+
+// 一个物体在某次 step 之前的运动状态
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct BodyState {
+    position: Vec2,
+    velocity: Vec2,
+    rotation: Rot,
+    angular_velocity: Real,
+}
+
+impl BodyState {
+    fn capture(body: &Body) -> BodyState {
+        BodyState {
+            position: body.position(),
+            velocity: body.velocity(),
+            rotation: body.rotation(),
+            angular_velocity: body.angular_velocity(),
+        }
+    }
+
+    fn restore(&self, body: &mut Body) {
+        body.set_position(self.position);
+        body.set_velocity(self.velocity);
+        body.set_rotation(self.rotation);
+        body.set_angular_velocity(self.angular_velocity);
+    }
+}
+
+// 最近若干次 step 之前所有物体的状态，最旧的在前面
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct StepHistory {
+    capacity: usize,
+    snapshots: VecDeque<Vec<BodyState>>,
+}
+
+impl StepHistory {
+    pub(crate) fn new(capacity: usize) -> StepHistory {
+        StepHistory {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    // 记录 step 之前的状态，超出容量时丢弃最旧的记录
+    pub(crate) fn push(&mut self, bodies: &[Rc<RefCell<Body>>]) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        let snapshot = bodies.iter().map(|b| BodyState::capture(&b.borrow()));
+        self.snapshots.push_back(snapshot.collect());
+    }
+
+    // 物体的下标变化之后调整每个快照，`remap[i]` 为原来下标为 i 的物体的新下标，
+    // 被移除的物体为 None。保留的物体的先后顺序不变，只需要丢弃被移除的物体
+    pub(crate) fn remap(&mut self, remap: &[Option<usize>]) {
+        for snapshot in &mut self.snapshots {
+            let mut i = 0;
+            snapshot.retain(|_| {
+                i += 1;
+                remap.get(i - 1).is_some_and(Option::is_some)
+            });
+        }
+    }
+
+    // 回到 `steps` 次 step 之前的状态，返回实际回退的次数。
+    // 记录之后才加入的物体不受影响
+    pub(crate) fn rewind(&mut self, steps: usize, bodies: &[Rc<RefCell<Body>>]) -> usize {
+        let steps = steps.min(self.snapshots.len());
+        if steps == 0 {
+            return 0;
+        }
+        self.snapshots.truncate(self.snapshots.len() - steps + 1);
+        let Some(snapshot) = self.snapshots.pop_back() else {
+            return 0;
+        };
+        for (state, body) in snapshot.iter().zip(bodies) {
+            state.restore(&mut body.borrow_mut());
+        }
+        steps
+    }
+}
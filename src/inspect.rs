@@ -0,0 +1,58 @@
+use std::fmt;
+
+use crate::{math::Real, shape::ShapeKind, vec2::Vec2};
+
+/// 属性的值
+#[derive(Clone, PartialEq, Debug)]
+pub enum PropertyValue {
+    Real(Real),
+    Vec2(Vec2),
+    Bool(bool),
+    Kind(ShapeKind),
+    /// 顶点列表，例如多边形和折线的顶点
+    Points(Vec<Vec2>),
+}
+
+/// `Body::properties` 返回的一项属性
+#[derive(Clone, PartialEq, Debug)]
+pub struct Property {
+    /// 属性名，可以传给 `Body::set_property`
+    pub name: &'static str,
+    pub value: PropertyValue,
+    /// 是否可以通过 `Body::set_property` 修改
+    pub editable: bool,
+}
+
+impl Property {
+    pub(crate) fn new(name: &'static str, value: PropertyValue, editable: bool) -> Property {
+        Property {
+            name,
+            value,
+            editable,
+        }
+    }
+}
+
+/// `Body::set_property` 失败的原因
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PropertyError {
+    /// 没有这个属性
+    Unknown,
+    /// 属性是只读的，形状的尺寸需要通过 `Body::set_shape` 修改
+    ReadOnly,
+    /// 值的类型与属性不符
+    TypeMismatch,
+    /// 物体是只读的，参见 `Body::is_read_only`
+    BodyReadOnly,
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyError::Unknown => write!(f, "unknown property"),
+            PropertyError::ReadOnly => write!(f, "property is read only"),
+            PropertyError::TypeMismatch => write!(f, "value type does not match property"),
+            PropertyError::BodyReadOnly => write!(f, "body is read only"),
+        }
+    }
+}
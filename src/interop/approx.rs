@@ -0,0 +1,74 @@
+use ::approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use crate::{math::Real, vec2::Vec2};
+
+impl AbsDiffEq for Vec2 {
+    type Epsilon = Real;
+
+    #[inline]
+    fn default_epsilon() -> Real {
+        Real::default_epsilon()
+    }
+
+    #[inline]
+    fn abs_diff_eq(&self, other: &Self, epsilon: Real) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon) && self.y.abs_diff_eq(&other.y, epsilon)
+    }
+}
+
+impl RelativeEq for Vec2 {
+    #[inline]
+    fn default_max_relative() -> Real {
+        Real::default_max_relative()
+    }
+
+    #[inline]
+    fn relative_eq(&self, other: &Self, epsilon: Real, max_relative: Real) -> bool {
+        self.x.relative_eq(&other.x, epsilon, max_relative)
+            && self.y.relative_eq(&other.y, epsilon, max_relative)
+    }
+}
+
+impl UlpsEq for Vec2 {
+    #[inline]
+    fn default_max_ulps() -> u32 {
+        Real::default_max_ulps()
+    }
+
+    #[inline]
+    fn ulps_eq(&self, other: &Self, epsilon: Real, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&other.x, epsilon, max_ulps) && self.y.ulps_eq(&other.y, epsilon, max_ulps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::approx::{abs_diff_eq, abs_diff_ne, relative_eq, relative_ne, ulps_eq};
+
+    /// Test cases for:
+    /// * AbsDiffEq for Vec2
+    #[test]
+    fn abs_diff_eq_should_compare_each_component() {
+        let v = Vec2::new(1., -2.);
+        assert!(abs_diff_eq!(v, v));
+        assert!(abs_diff_eq!(v, Vec2::new(1.05, -2.), epsilon = 0.1));
+        assert!(abs_diff_ne!(v, Vec2::new(1., -2.2), epsilon = 0.1));
+        assert!(abs_diff_ne!(v, Vec2::new(1.2, -2.), epsilon = 0.1));
+    }
+
+    /// Test cases for:
+    /// * RelativeEq for Vec2
+    /// * UlpsEq for Vec2
+    #[test]
+    fn relative_eq_should_scale_with_magnitude() {
+        let v = Vec2::new(1000., -0.001);
+        let nudged = Vec2::new(1000.5, -0.001);
+        assert!(relative_eq!(v, nudged, max_relative = 0.001));
+        assert!(relative_ne!(v, nudged, max_relative = 0.0001));
+        // 误差相同时，数值越小相对误差越大
+        assert!(relative_ne!(Vec2::new(1., 0.), Vec2::new(1.5, 0.), max_relative = 0.001));
+        assert!(ulps_eq!(v, v));
+        assert!(!ulps_eq!(v, nudged));
+    }
+}
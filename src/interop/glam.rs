@@ -0,0 +1,95 @@
+use crate::{mat2::Mat2, rot::Rot, transform::Transform2D, vec2::Vec2};
+
+// glam 中与 `Real` 精度相同的类型
+#[cfg(not(feature = "f64"))]
+use ::glam::{Affine2 as GlamAffine2, Mat2 as GlamMat2, Vec2 as GlamVec2};
+#[cfg(feature = "f64")]
+use ::glam::{DAffine2 as GlamAffine2, DMat2 as GlamMat2, DVec2 as GlamVec2};
+
+impl From<GlamVec2> for Vec2 {
+    #[inline]
+    fn from(v: GlamVec2) -> Self {
+        Vec2::new(v.x, v.y)
+    }
+}
+
+impl From<Vec2> for GlamVec2 {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        GlamVec2::new(v.x, v.y)
+    }
+}
+
+impl From<GlamMat2> for Mat2 {
+    #[inline]
+    fn from(m: GlamMat2) -> Self {
+        Mat2::from_cols(m.x_axis.into(), m.y_axis.into())
+    }
+}
+
+impl From<Mat2> for GlamMat2 {
+    #[inline]
+    fn from(m: Mat2) -> Self {
+        GlamMat2::from_cols(m.x_axis.into(), m.y_axis.into())
+    }
+}
+
+impl From<Rot> for GlamMat2 {
+    #[inline]
+    fn from(rot: Rot) -> Self {
+        rot.to_mat2().into()
+    }
+}
+
+impl From<Transform2D> for GlamAffine2 {
+    #[inline]
+    fn from(transform: Transform2D) -> Self {
+        GlamAffine2::from_mat2_translation(
+            transform.rotation.into(),
+            transform.translation.into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * From<GlamVec2> for Vec2
+    /// * From<Vec2> for GlamVec2
+    #[test]
+    fn vec2_should_round_trip_through_glam() {
+        let v = Vec2::new(1.5, -2.);
+        let glam: GlamVec2 = v.into();
+        assert_eq!((glam.x, glam.y), (1.5, -2.));
+        assert_eq!(Vec2::from(glam), v);
+    }
+
+    /// Test cases for:
+    /// * From<GlamMat2> for Mat2
+    /// * From<Mat2> for GlamMat2
+    /// * From<Rot> for GlamMat2
+    #[test]
+    fn mat2_should_round_trip_through_glam() {
+        let m = Mat2::new(1., 2., 3., 4.);
+        let glam: GlamMat2 = m.into();
+        assert_eq!(glam, GlamMat2::from_cols_array_2d(&[[1., 3.], [2., 4.]]));
+        assert_eq!(Mat2::from(glam), m);
+
+        let rot = Rot::from_angle(0.5);
+        assert_eq!(Mat2::from(GlamMat2::from(rot)), rot.to_mat2());
+    }
+
+    /// Test cases for:
+    /// * From<Transform2D> for GlamAffine2
+    #[test]
+    fn transform_should_convert_to_glam_affine() {
+        let transform = Transform2D::new(Vec2::new(3., -1.), Rot::from_angle(1.2));
+        let affine: GlamAffine2 = transform.into();
+        let point = Vec2::new(0.5, 2.);
+        let expected = transform.transform_point(point);
+        let actual = Vec2::from(affine.transform_point2(point.into()));
+        assert!(actual.distance(expected) < 1e-5);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{mat2::Mat2, math::Real, vec2::Vec2};
+
+impl From<::mint::Vector2<Real>> for Vec2 {
+    #[inline]
+    fn from(v: ::mint::Vector2<Real>) -> Self {
+        Vec2::new(v.x, v.y)
+    }
+}
+
+impl From<Vec2> for ::mint::Vector2<Real> {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        ::mint::Vector2 { x: v.x, y: v.y }
+    }
+}
+
+impl From<::mint::Point2<Real>> for Vec2 {
+    #[inline]
+    fn from(p: ::mint::Point2<Real>) -> Self {
+        Vec2::new(p.x, p.y)
+    }
+}
+
+impl From<Vec2> for ::mint::Point2<Real> {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        ::mint::Point2 { x: v.x, y: v.y }
+    }
+}
+
+impl From<::mint::ColumnMatrix2<Real>> for Mat2 {
+    #[inline]
+    fn from(m: ::mint::ColumnMatrix2<Real>) -> Self {
+        Mat2::from_cols(m.x.into(), m.y.into())
+    }
+}
+
+impl From<Mat2> for ::mint::ColumnMatrix2<Real> {
+    #[inline]
+    fn from(m: Mat2) -> Self {
+        ::mint::ColumnMatrix2 {
+            x: m.x_axis.into(),
+            y: m.y_axis.into(),
+        }
+    }
+}
+
+impl ::mint::IntoMint for Vec2 {
+    type MintType = ::mint::Vector2<Real>;
+}
+
+impl ::mint::IntoMint for Mat2 {
+    type MintType = ::mint::ColumnMatrix2<Real>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * From<mint::Vector2> for Vec2
+    /// * From<Vec2> for mint::Vector2
+    /// * From<mint::Point2> for Vec2
+    /// * From<Vec2> for mint::Point2
+    #[test]
+    fn vec2_should_round_trip_through_mint() {
+        let v = Vec2::new(1.5, -2.);
+        let vector: ::mint::Vector2<Real> = v.into();
+        assert_eq!(vector, ::mint::Vector2 { x: 1.5, y: -2. });
+        assert_eq!(Vec2::from(vector), v);
+        let point: ::mint::Point2<Real> = v.into();
+        assert_eq!(point, ::mint::Point2 { x: 1.5, y: -2. });
+        assert_eq!(Vec2::from(point), v);
+    }
+
+    /// Test cases for:
+    /// * From<mint::ColumnMatrix2> for Mat2
+    /// * From<Mat2> for mint::ColumnMatrix2
+    #[test]
+    fn mat2_should_round_trip_through_mint() {
+        let m = Mat2::new(1., 2., 3., 4.);
+        let mint: ::mint::ColumnMatrix2<Real> = m.into();
+        assert_eq!(mint.x, ::mint::Vector2 { x: 1., y: 3. });
+        assert_eq!(mint.y, ::mint::Vector2 { x: 2., y: 4. });
+        assert_eq!(Mat2::from(mint), m);
+    }
+}
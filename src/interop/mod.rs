@@ -0,0 +1,10 @@
+// 与其他数学库类型之间的转换，每个库对应一个 feature
+
+#[cfg(feature = "approx")]
+mod approx;
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
@@ -0,0 +1,131 @@
+use crate::{mat2::Mat2, math::Real, rot::Rot, transform::Transform2D, vec2::Vec2};
+
+impl From<::nalgebra::Vector2<Real>> for Vec2 {
+    #[inline]
+    fn from(v: ::nalgebra::Vector2<Real>) -> Self {
+        Vec2::new(v.x, v.y)
+    }
+}
+
+impl From<Vec2> for ::nalgebra::Vector2<Real> {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        ::nalgebra::Vector2::new(v.x, v.y)
+    }
+}
+
+impl From<::nalgebra::Point2<Real>> for Vec2 {
+    #[inline]
+    fn from(p: ::nalgebra::Point2<Real>) -> Self {
+        Vec2::new(p.x, p.y)
+    }
+}
+
+impl From<Vec2> for ::nalgebra::Point2<Real> {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        ::nalgebra::Point2::new(v.x, v.y)
+    }
+}
+
+impl From<::nalgebra::Matrix2<Real>> for Mat2 {
+    #[inline]
+    fn from(m: ::nalgebra::Matrix2<Real>) -> Self {
+        Mat2::new(m[(0, 0)], m[(0, 1)], m[(1, 0)], m[(1, 1)])
+    }
+}
+
+impl From<Mat2> for ::nalgebra::Matrix2<Real> {
+    #[inline]
+    fn from(m: Mat2) -> Self {
+        ::nalgebra::Matrix2::new(m.x_axis.x, m.y_axis.x, m.x_axis.y, m.y_axis.y)
+    }
+}
+
+impl From<::nalgebra::UnitComplex<Real>> for Rot {
+    #[inline]
+    fn from(r: ::nalgebra::UnitComplex<Real>) -> Self {
+        Rot {
+            s: r.sin_angle(),
+            c: r.cos_angle(),
+        }
+    }
+}
+
+impl From<Rot> for ::nalgebra::UnitComplex<Real> {
+    #[inline]
+    fn from(r: Rot) -> Self {
+        ::nalgebra::UnitComplex::from_cos_sin_unchecked(r.c, r.s)
+    }
+}
+
+impl From<::nalgebra::Isometry2<Real>> for Transform2D {
+    #[inline]
+    fn from(iso: ::nalgebra::Isometry2<Real>) -> Self {
+        Transform2D::new(iso.translation.vector.into(), iso.rotation.into())
+    }
+}
+
+impl From<Transform2D> for ::nalgebra::Isometry2<Real> {
+    #[inline]
+    fn from(t: Transform2D) -> Self {
+        ::nalgebra::Isometry2::from_parts(
+            ::nalgebra::Translation2::new(t.translation.x, t.translation.y),
+            t.rotation.into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * From<nalgebra::Vector2> for Vec2
+    /// * From<Vec2> for nalgebra::Vector2
+    /// * From<nalgebra::Point2> for Vec2
+    /// * From<Vec2> for nalgebra::Point2
+    #[test]
+    fn vec2_should_round_trip_through_nalgebra() {
+        let v = Vec2::new(1.5, -2.);
+        let vector: ::nalgebra::Vector2<Real> = v.into();
+        assert_eq!(vector, ::nalgebra::Vector2::new(1.5, -2.));
+        assert_eq!(Vec2::from(vector), v);
+        let point: ::nalgebra::Point2<Real> = v.into();
+        assert_eq!(point, ::nalgebra::Point2::new(1.5, -2.));
+        assert_eq!(Vec2::from(point), v);
+    }
+
+    /// Test cases for:
+    /// * From<nalgebra::Matrix2> for Mat2
+    /// * From<Mat2> for nalgebra::Matrix2
+    #[test]
+    fn mat2_should_round_trip_through_nalgebra() {
+        let m = Mat2::new(1., 2., 3., 4.);
+        let matrix: ::nalgebra::Matrix2<Real> = m.into();
+        assert_eq!(matrix, ::nalgebra::Matrix2::new(1., 2., 3., 4.));
+        assert_eq!(Mat2::from(matrix), m);
+        let v = Vec2::new(-1., 0.5);
+        assert_eq!(Vec2::from(matrix * ::nalgebra::Vector2::from(v)), m * v);
+    }
+
+    /// Test cases for:
+    /// * From<nalgebra::UnitComplex> for Rot
+    /// * From<Rot> for nalgebra::UnitComplex
+    /// * From<nalgebra::Isometry2> for Transform2D
+    /// * From<Transform2D> for nalgebra::Isometry2
+    #[test]
+    fn transform_should_round_trip_through_nalgebra() {
+        let rot = Rot::from_angle(0.7);
+        let complex: ::nalgebra::UnitComplex<Real> = rot.into();
+        assert!((complex.angle() - 0.7).abs() < 1e-5);
+        assert_eq!(Rot::from(complex), rot);
+
+        let transform = Transform2D::new(Vec2::new(3., -1.), Rot::from_angle(1.2));
+        let isometry: ::nalgebra::Isometry2<Real> = transform.into();
+        let point = Vec2::new(0.5, 2.);
+        let moved = Vec2::from(isometry * ::nalgebra::Point2::from(point));
+        assert!(moved.distance(transform.transform_point(point)) < 1e-5);
+        assert_eq!(Transform2D::from(isometry), transform);
+    }
+}
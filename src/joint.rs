@@ -0,0 +1,302 @@
+use crate::{
+    body::Body,
+    mat2::Mat2,
+    math::{shortest_angle_between, Real},
+    transform::Transform2D,
+    vec2::Vec2,
+};
+
+/// 鼠标关节，用一个软弹簧把物体拉向目标点
+/// 常用于用鼠标拖拽物体
+#[derive(Clone, Copy, Debug)]
+pub struct MouseJoint {
+    body: usize,
+    target: Vec2,
+    // 被拖拽点在物体局部坐标系中的位置，随物体一起转动
+    local_anchor: Vec2,
+    // 弹簧频率，单位 Hz
+    frequency: Real,
+    // 阻尼比，1 为临界阻尼
+    damping_ratio: Real,
+    // 关节能提供的最大力
+    max_force: Real,
+}
+
+impl MouseJoint {
+    /// 创建鼠标关节
+    /// * `body`: 物体在 world 中的下标
+    /// * `body_transform`: 物体当前的变换
+    /// * `anchor`: 物体上被拖拽的点，世界坐标
+    /// * `max_force`: 关节能提供的最大力
+    pub fn new(body: usize, body_transform: Transform2D, anchor: Vec2, max_force: Real) -> MouseJoint {
+        MouseJoint {
+            body,
+            target: anchor,
+            local_anchor: body_transform.inverse_transform_point(anchor),
+            frequency: 5.0,
+            damping_ratio: 0.7,
+            max_force,
+        }
+    }
+
+    #[inline(always)]
+    pub fn body(&self) -> usize {
+        self.body
+    }
+
+    #[inline(always)]
+    pub fn target(&self) -> Vec2 {
+        self.target
+    }
+
+    #[inline(always)]
+    pub fn set_target(&mut self, target: Vec2) {
+        self.target = target;
+    }
+
+    #[inline(always)]
+    pub fn set_frequency(&mut self, frequency: Real) {
+        self.frequency = frequency;
+    }
+
+    #[inline(always)]
+    pub fn set_damping_ratio(&mut self, damping_ratio: Real) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    #[inline(always)]
+    pub fn set_max_force(&mut self, max_force: Real) {
+        self.max_force = max_force;
+    }
+
+    /// 被拖拽点的世界坐标
+    pub fn anchor(&self, body: &Body) -> Vec2 {
+        body.transform().transform_point(self.local_anchor)
+    }
+
+    /// 被拖拽点到目标点的距离
+    pub(crate) fn violation(&self, body: &Body) -> Real {
+        self.anchor(body).distance(self.target)
+    }
+
+    /// 在被拖拽点对物体施加一次冲量，把它拉向目标点。
+    /// 被拖拽点不在质心时物体同时会转动
+    pub(crate) fn apply_impulse(&self, body: &mut Body, dt: Real) {
+        if body.inverse_mass() == 0. {
+            return;
+        }
+        let (gamma, beta) = soft_coefficients(body.mass(), self.frequency, self.damping_ratio, dt);
+        let r = body.rotation() * self.local_anchor;
+        let c = body.position() + r - self.target;
+        let cdot = body.velocity() + r.perp() * body.angular_velocity();
+        // 被拖拽点的有效质量矩阵的逆
+        let (m, i) = (body.inverse_mass(), body.inverse_inertia());
+        let k = Mat2::new(
+            m + i * r.y * r.y + gamma,
+            -i * r.x * r.y,
+            -i * r.x * r.y,
+            m + i * r.x * r.x + gamma,
+        );
+        let Some(k_inv) = k.inverse() else {
+            return;
+        };
+        let mut impulse = k_inv * -(cdot + c * beta);
+        let max_impulse = self.max_force * dt;
+        if impulse.length_squared() > max_impulse * max_impulse {
+            impulse = impulse.normalize() * max_impulse;
+        }
+        body.apply_impulse(impulse);
+        body.set_angular_velocity(body.angular_velocity() + i * r.perp_dot(impulse));
+    }
+}
+
+/// 轨道约束，用软弹簧把物体约束在一条折线上，物体可以沿折线自由移动
+/// 可以用来实现沿轨道运动的摄像机、滑块等
+#[derive(Clone, Debug)]
+pub struct TrackConstraint {
+    body: usize,
+    // 折线的顶点，只有一个顶点时把物体固定在该点
+    points: Vec<Vec2>,
+    // 弹簧频率，单位 Hz
+    frequency: Real,
+    // 阻尼比，1 为临界阻尼
+    damping_ratio: Real,
+}
+
+impl TrackConstraint {
+    /// 创建轨道约束
+    /// * `body`: 物体在 world 中的下标
+    /// * `points`: 折线的顶点，不能为空
+    ///
+    /// # Panics
+    ///
+    /// `points` 为空时 panic
+    pub fn new(body: usize, points: Vec<Vec2>) -> TrackConstraint {
+        assert!(!points.is_empty(), "track must have at least one point");
+        TrackConstraint {
+            body,
+            points,
+            frequency: 5.0,
+            damping_ratio: 1.0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn body(&self) -> usize {
+        self.body
+    }
+
+    // 物体在 world 中的下标变化之后更新
+    pub(crate) fn set_body(&mut self, body: usize) {
+        self.body = body;
+    }
+
+    #[inline(always)]
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    #[inline(always)]
+    pub fn set_frequency(&mut self, frequency: Real) {
+        self.frequency = frequency;
+    }
+
+    #[inline(always)]
+    pub fn set_damping_ratio(&mut self, damping_ratio: Real) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    /// 折线上离 `point` 最近的点
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        let mut closest = self.points[0];
+        let mut min_dist = point.distance_squared(closest);
+        for segment in self.points.windows(2) {
+            let (a, b) = (segment[0], segment[1]);
+            let ab = b - a;
+            let len_sqr = ab.length_squared();
+            if len_sqr == 0. {
+                continue;
+            }
+            let t = ((point - a).dot(ab) / len_sqr).clamp(0., 1.);
+            let p = a + ab * t;
+            let dist = point.distance_squared(p);
+            if dist < min_dist {
+                min_dist = dist;
+                closest = p;
+            }
+        }
+        closest
+    }
+
+    /// 物体到轨道的距离
+    pub(crate) fn violation(&self, body: &Body) -> Real {
+        body.position().distance(self.closest_point(body.position()))
+    }
+
+    /// 对物体施加一次冲量，把它拉回轨道上
+    pub(crate) fn apply_impulse(&self, body: &mut Body, dt: Real) {
+        if body.inverse_mass() == 0. {
+            return;
+        }
+        let c = body.position() - self.closest_point(body.position());
+        let (gamma, beta) = soft_coefficients(body.mass(), self.frequency, self.damping_ratio, dt);
+        let impulse = match c.try_normalize() {
+            // 只约束偏离轨道的方向，沿轨道方向的速度不受影响
+            Some(n) => {
+                let cn = c.length();
+                let vn = body.velocity().dot(n);
+                n * (-(vn + cn * beta) / (body.inverse_mass() + gamma))
+            }
+            None if self.points.len() == 1 => {
+                -body.velocity() / (body.inverse_mass() + gamma)
+            }
+            None => return,
+        };
+        body.apply_impulse(impulse);
+    }
+}
+
+/// 角度约束，用软弹簧把物体的转角拉向目标角度，不影响物体的平动
+/// 可以用来实现转向目标的炮塔、保持竖直的角色等
+#[derive(Clone, Copy, Debug)]
+pub struct AngleConstraint {
+    body: usize,
+    // 目标角度，单位弧度
+    target: Real,
+    // 弹簧频率，单位 Hz
+    frequency: Real,
+    // 阻尼比，1 为临界阻尼
+    damping_ratio: Real,
+}
+
+impl AngleConstraint {
+    /// 创建角度约束
+    /// * `body`: 物体在 world 中的下标
+    /// * `target`: 目标角度，单位弧度
+    pub fn new(body: usize, target: Real) -> AngleConstraint {
+        AngleConstraint {
+            body,
+            target,
+            frequency: 5.0,
+            damping_ratio: 1.0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn body(&self) -> usize {
+        self.body
+    }
+
+    // 物体在 world 中的下标变化之后更新
+    pub(crate) fn set_body(&mut self, body: usize) {
+        self.body = body;
+    }
+
+    #[inline(always)]
+    pub fn target(&self) -> Real {
+        self.target
+    }
+
+    #[inline(always)]
+    pub fn set_target(&mut self, target: Real) {
+        self.target = target;
+    }
+
+    #[inline(always)]
+    pub fn set_frequency(&mut self, frequency: Real) {
+        self.frequency = frequency;
+    }
+
+    #[inline(always)]
+    pub fn set_damping_ratio(&mut self, damping_ratio: Real) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    /// 物体转角与目标角度的差，沿较短的方向计算
+    pub(crate) fn violation(&self, body: &Body) -> Real {
+        shortest_angle_between(self.target, body.rotation().angle()).abs()
+    }
+
+    /// 对物体施加一次角冲量，把它转向目标角度
+    pub(crate) fn apply_impulse(&self, body: &mut Body, dt: Real) {
+        let inverse_inertia = body.inverse_inertia();
+        if inverse_inertia == 0. {
+            return;
+        }
+        let c = shortest_angle_between(self.target, body.rotation().angle());
+        let (gamma, beta) =
+            soft_coefficients(inverse_inertia.recip(), self.frequency, self.damping_ratio, dt);
+        let impulse = -(body.angular_velocity() + c * beta) / (inverse_inertia + gamma);
+        body.set_angular_velocity(body.angular_velocity() + impulse * inverse_inertia);
+    }
+}
+
+// 软约束的系数，根据弹簧频率和阻尼比计算 (gamma, beta)
+fn soft_coefficients(mass: Real, frequency: Real, damping_ratio: Real, dt: Real) -> (Real, Real) {
+    let omega = 2. * crate::math::consts::PI * frequency;
+    let d = 2. * mass * damping_ratio * omega;
+    let k = mass * omega * omega;
+    let gamma = (dt * (d + dt * k)).recip();
+    let beta = dt * k * gamma;
+    (gamma, beta)
+}
@@ -1,6 +1,34 @@
 pub mod shape;
 pub mod vec2;
+pub mod mat2;
+pub mod rot;
+pub mod math;
 pub mod body;
+pub mod broad_phase;
+pub mod clock;
+pub mod collision;
 pub mod manifold;
+pub mod narrow_phase;
+pub mod contact;
+pub mod determinism;
+pub mod event;
+pub mod fixed;
+pub mod inspect;
+#[cfg(feature = "replication")]
+pub mod replication;
+pub mod solver;
+pub mod stats;
+pub mod surface;
+pub mod telemetry;
+pub mod template;
+pub mod trajectory;
+pub mod transform;
+pub mod volume;
 pub mod world;
+pub mod joint;
+mod interop;
+mod bvh;
+mod hash;
+mod history;
+pub mod validation;
 pub mod wasm;
@@ -2,10 +2,78 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     body::Body,
-    shape::{Circle, ShapeType, AABB},
+    collision::{sat, shape_penetration, ConvexProxy},
+    hash::{split_mix64, unit_float},
+    math::{closest_point_on_segment, closest_points_on_segments, Real},
+    narrow_phase::NarrowPhase,
+    shape::{Chain, Circle, Compound, Polygon, ShapeType, Triangle, AABB, MAX_POLYGON_VERTICES},
+    solver::SolverConfig,
+    transform::Transform2D,
     vec2::Vec2,
 };
 
+// 允许的侵入量（米），避免物体静止接触时来回抖动
+pub(crate) const LINEAR_SLOP: Real = 0.05;
+
+/// 两个物体位置完全重合时，选择分离方向的策略
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CoincidentPolicy {
+    /// 总是沿给定方向把 B 推离 A
+    Axis(Vec2),
+    /// 由种子和两个物体在 world 中的下标确定分离方向，
+    /// 相同的场景总是得到相同的结果
+    Seeded(u64),
+}
+
+impl Default for CoincidentPolicy {
+    fn default() -> Self {
+        CoincidentPolicy::Axis(Vec2::new(1., 0.))
+    }
+}
+
+impl CoincidentPolicy {
+    /// 下标为 `a` 和 `b` 的两个物体重合时使用的碰撞法线（单位向量）
+    pub fn normal(&self, a: usize, b: usize) -> Vec2 {
+        match *self {
+            CoincidentPolicy::Axis(axis) => axis.try_normalize().unwrap_or(Vec2::new(1., 0.)),
+            CoincidentPolicy::Seeded(seed) => {
+                let hash = split_mix64(seed ^ ((a as u64) << 32 | b as u64));
+                let angle = unit_float(hash) * crate::math::consts::TAU;
+                Vec2::new(angle.cos(), angle.sin())
+            }
+        }
+    }
+}
+
+/// 摩擦力模型
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum FrictionModel {
+    /// 每次迭代单独计算切线冲量，并用本次迭代的法向冲量做截断。
+    /// 计算量小，但结果依赖迭代顺序和方向
+    #[default]
+    Clamped,
+    /// 在一次 step 内累积切线冲量，并把累积值重新投影到库仑摩擦锥内，
+    /// 截断依据的是累积的法向冲量，结果更接近精确的库仑摩擦
+    Cone,
+}
+
+// 按照求解使用的逆质量对物体施加冲量
+fn apply_body_impulse(body: &mut Body, impulse: Vec2, inverse_mass: Real) {
+    let v = body.velocity() + impulse * inverse_mass;
+    body.set_velocity(v);
+}
+
+// 对物体施加角冲量
+fn apply_angular_impulse(body: &mut Body, impulse: Real) {
+    let w = body.angular_velocity() + impulse * body.inverse_inertia();
+    body.set_angular_velocity(w);
+}
+
+// 物体上相对质心偏移为 r 的点的速度
+pub(crate) fn point_velocity(body: &Body, r: Vec2) -> Vec2 {
+    body.velocity() + r.perp() * body.angular_velocity()
+}
+
 pub(crate) struct Manifold {
     a: Rc<RefCell<Body>>,
     b: Rc<RefCell<Body>>,
@@ -13,15 +81,30 @@ pub(crate) struct Manifold {
     // A 的碰撞法线，单位向量
     normal: Vec2,
     // 物体碰撞时的侵入量
-    penetration: f32,
+    penetration: Real,
     // 碰撞求解使用的恢复系数
-    e: f32,
+    e: Real,
     // 所有的碰撞点
     contacts: Vec<Vec2>,
     // 碰撞计算时要使用的静摩擦力
-    sf: f32,
+    sf: Real,
     // 碰撞计算时要使用的动摩擦力
-    df: f32,
+    df: Real,
+    // 两个物体重合时使用的碰撞法线
+    coincident_normal: Vec2,
+    // 本次 step 累积的法向冲量
+    normal_impulse_sum: Real,
+    // 本次 step 累积的切线冲量
+    tangent_impulse_sum: Vec2,
+    // 求解使用的 A 的逆质量
+    inv_mass_a: Real,
+    // 求解使用的 B 的逆质量
+    inv_mass_b: Real,
+    // 扭转摩擦的滚动阻力系数，`None` 表示摩擦力不考虑转动
+    torsional_friction: Option<Real>,
+    // 发生碰撞的子形状，复合形状为子形状的下标，折线为边的下标，其他形状为 0
+    sub_shape_a: usize,
+    sub_shape_b: usize,
 }
 
 impl Manifold {
@@ -35,17 +118,85 @@ impl Manifold {
             contacts: vec![],
             sf: 0.,
             df: 0.,
+            coincident_normal: Vec2::new(1., 0.),
+            normal_impulse_sum: 0.,
+            tangent_impulse_sum: Vec2::ZERO,
+            inv_mass_a: 0.,
+            inv_mass_b: 0.,
+            torsional_friction: None,
+            sub_shape_a: 0,
+            sub_shape_b: 0,
         }
     }
     /// 碰撞求解
     /// 解出碰撞点和碰撞法向量
-    pub(crate) fn solve(a: Rc<RefCell<Body>>, b: Rc<RefCell<Body>>) -> Manifold {
-        // let a = self.a.borrow();
-        let a_type = a.borrow().shape();
-        let b_type = b.borrow().shape();
-        // let b = self.b.borrow();
+    pub(crate) fn solve(
+        a: Rc<RefCell<Body>>,
+        b: Rc<RefCell<Body>>,
+        narrow_phase: &NarrowPhase,
+    ) -> Manifold {
+        Manifold::solve_with(a, b, Vec2::new(1., 0.), narrow_phase)
+    }
+
+    /// 碰撞求解，两个物体重合时使用 `coincident_normal` 作为碰撞法线
+    pub(crate) fn solve_with(
+        a: Rc<RefCell<Body>>,
+        b: Rc<RefCell<Body>>,
+        coincident_normal: Vec2,
+        narrow_phase: &NarrowPhase,
+    ) -> Manifold {
         let mut m = Manifold::new(a, b);
+        m.coincident_normal = coincident_normal;
+        m.collide(narrow_phase);
+        if !m.contacts.is_empty() {
+            m.override_normal();
+        }
+        m
+    }
+
+    // 窄检测，求出两个物体的形状之间的碰撞点、法线和侵入量。
+    // 优先使用 `narrow_phase` 中注册的碰撞函数
+    fn collide(&mut self, narrow_phase: &NarrowPhase) {
+        let custom = narrow_phase.collide(&self.a.borrow(), &self.b.borrow());
+        if let Some(result) = custom {
+            if let Some(result) = result {
+                self.normal = result.normal;
+                self.penetration = result.penetration;
+                self.contacts = result.points;
+            }
+        } else {
+            self.collide_builtin(narrow_phase);
+        }
+        if !self.contacts.is_empty() {
+            self.filter_one_sided();
+        }
+    }
+
+    fn collide_builtin(&mut self, narrow_phase: &NarrowPhase) {
+        let a_type = self.a.borrow().shape();
+        let b_type = self.b.borrow().shape();
+        let m = self;
         match (a_type, b_type) {
+            (ShapeType::Compound(ref compound), _) => {
+                m.compound_2_shape(compound, narrow_phase);
+            }
+            (_, ShapeType::Compound(ref compound)) => {
+                m.shape_2_compound(compound, narrow_phase);
+            }
+            // 没有注册碰撞函数的自定义形状不会发生碰撞
+            (ShapeType::Custom(_), _) | (_, ShapeType::Custom(_)) => {}
+            // 点只与有面积的形状碰撞
+            (
+                ShapeType::Point(_),
+                ShapeType::Point(_) | ShapeType::Segment(_) | ShapeType::Chain(_),
+            )
+            | (ShapeType::Segment(_) | ShapeType::Chain(_), ShapeType::Point(_)) => {}
+            (ShapeType::Point(_), _) => {
+                m.point_2_shape();
+            }
+            (_, ShapeType::Point(_)) => {
+                m.shape_2_point();
+            }
             (ShapeType::Circle(ref circle_a), ShapeType::Circle(ref circle_b)) => {
                 m.circle_2_circle(circle_a, circle_b);
             }
@@ -58,23 +209,111 @@ impl Manifold {
             (ShapeType::AABB(ref aabb_a), ShapeType::AABB(ref aabb_b)) => {
                 m.aabb_2_aabb(aabb_a, aabb_b);
             }
+            (ShapeType::Polygon(ref polygon), ShapeType::Circle(ref circle)) => {
+                m.polygon_2_circle(polygon, circle);
+            }
+            (ShapeType::Circle(ref circle), ShapeType::Polygon(ref polygon)) => {
+                m.circle_2_polygon(circle, polygon);
+            }
+            (ShapeType::Triangle(ref triangle), ShapeType::Circle(ref circle)) => {
+                m.triangle_2_circle(triangle, circle);
+            }
+            (ShapeType::Circle(ref circle), ShapeType::Triangle(ref triangle)) => {
+                m.circle_2_triangle(circle, triangle);
+            }
+            // 线段和折线都是静态的，互相之间不会发生碰撞
+            (
+                ShapeType::Segment(_) | ShapeType::Chain(_),
+                ShapeType::Segment(_) | ShapeType::Chain(_),
+            ) => {}
+            (ShapeType::Chain(ref chain), _) => {
+                m.chain_2_shape(chain);
+            }
+            (_, ShapeType::Chain(ref chain)) => {
+                m.shape_2_chain(chain);
+            }
+            (
+                ShapeType::Capsule(_) | ShapeType::Segment(_),
+                ShapeType::Circle(_) | ShapeType::Capsule(_) | ShapeType::Segment(_),
+            )
+            | (ShapeType::Circle(_), ShapeType::Capsule(_) | ShapeType::Segment(_)) => {
+                m.rounded_2_rounded();
+            }
+            (_, ShapeType::Capsule(_) | ShapeType::Segment(_)) => {
+                m.polygon_2_rounded();
+            }
+            (ShapeType::Capsule(_) | ShapeType::Segment(_), _) => {
+                m.rounded_2_polygon();
+            }
+            (ShapeType::Polygon(_) | ShapeType::Triangle(_), _)
+            | (_, ShapeType::Polygon(_) | ShapeType::Triangle(_)) => {
+                // AABB 当作不会转动的矩形处理
+                m.polygon_2_polygon();
+            }
         }
-        m
     }
 
     pub(crate) fn get_contacts(&self) -> &Vec<Vec2> {
         &self.contacts
     }
 
-    pub(crate) fn initialize(&mut self) {
+    /// 发生碰撞的 `(A 的子形状, B 的子形状)`
+    pub(crate) fn sub_shapes(&self) -> (usize, usize) {
+        (self.sub_shape_a, self.sub_shape_b)
+    }
+
+    pub(crate) fn normal(&self) -> Vec2 {
+        self.normal
+    }
+
+    pub(crate) fn penetration(&self) -> Real {
+        self.penetration
+    }
+
+    /// 两个物体沿碰撞法线方向的接近速度，分离时为 0
+    pub(crate) fn normal_velocity_error(&self) -> Real {
+        let a = self.a.borrow();
+        let b = self.b.borrow();
+        (-(b.velocity() - a.velocity()).dot(self.normal)).max(0.)
+    }
+
+    /// 本次 step 累积的法向冲量
+    pub(crate) fn normal_impulse(&self) -> Real {
+        self.normal_impulse_sum
+    }
+
+    /// 本次 step 累积的切线冲量
+    pub(crate) fn tangent_impulse(&self) -> Vec2 {
+        self.tangent_impulse_sum
+    }
+
+    /// 求解前的初始化
+    /// 两个非静态物体的质量比超过 `config.max_mass_ratio` 时，放大较重物体的逆质量，
+    /// 使求解时的质量比不超过该值
+    pub(crate) fn initialize(&mut self, config: &SolverConfig) {
         let a = self.a.borrow();
         let b = self.b.borrow();
+        self.inv_mass_a = a.inverse_mass();
+        self.inv_mass_b = b.inverse_mass();
+        self.torsional_friction = config.torsional_friction;
+        if let Some(ratio) = config.max_mass_ratio {
+            if self.inv_mass_a > 0. && self.inv_mass_b > 0. {
+                // 逆质量越小物体越重
+                if self.inv_mass_a < self.inv_mass_b {
+                    self.inv_mass_a = self.inv_mass_a.max(self.inv_mass_b / ratio);
+                } else {
+                    self.inv_mass_b = self.inv_mass_b.max(self.inv_mass_a / ratio);
+                }
+            }
+        }
         self.e = a.restitution().min(b.restitution());
         self.sf = (a.static_fraction * a.static_fraction + b.static_fraction * b.static_fraction).sqrt();
         self.df = (a.dynamic_fraction * a.dynamic_fraction + b.dynamic_fraction * b.dynamic_fraction).sqrt();
+        self.normal_impulse_sum = 0.;
+        self.tangent_impulse_sum = Vec2::ZERO;
     }
 
-    pub(crate) fn apply_impulse(&mut self) {
+    pub(crate) fn apply_impulse(&mut self, friction: FrictionModel) {
         let mut a = self.a.borrow_mut();
         let mut b = self.b.borrow_mut();
         // 两个物体的质量都是无穷大
@@ -92,18 +331,41 @@ impl Manifold {
             return;
         }
         // 计算冲量
-        let inv_mass_sum = a.inverse_mass() + b.inverse_mass();
+        let inv_mass_sum = self.inv_mass_a + self.inv_mass_b;
         let mut j = -(1.0 + self.e) * rv;
         j /= inv_mass_sum;
         let impulse = self.normal * j;
         // let mut a = self.a.borrow_mut();
         // let mut b = self.b.borrow_mut();
-        a.apply_impulse(-impulse);
-        b.apply_impulse(impulse);
+        apply_body_impulse(&mut a, -impulse, self.inv_mass_a);
+        apply_body_impulse(&mut b, impulse, self.inv_mass_b);
+        self.normal_impulse_sum += j;
 
         // 应用摩擦力
+        if let Some(resistance) = self.torsional_friction {
+            let impulse = self.apply_torsional_friction(&mut a, &mut b, j, resistance);
+            self.tangent_impulse_sum += impulse;
+            return;
+        }
         let rv_2 = b.velocity() - a.velocity();
-        let mut t = rv_2 - self.normal * (rv_2.dot(self.normal));
+        if friction == FrictionModel::Cone {
+            // 切线方向的相对速度全部消除所需的冲量
+            let rv_t = rv_2.reject_from(self.normal);
+            let old_sum = self.tangent_impulse_sum;
+            let mut new_sum = old_sum - rv_t / inv_mass_sum;
+            // 超出静摩擦锥时，投影回动摩擦锥的边界
+            if new_sum.length() > self.normal_impulse_sum * self.sf {
+                new_sum = new_sum
+                    .try_normalize()
+                    .map_or(Vec2::ZERO, |dir| dir * (self.normal_impulse_sum * self.df));
+            }
+            self.tangent_impulse_sum = new_sum;
+            let tangent_impulse = new_sum - old_sum;
+            apply_body_impulse(&mut a, -tangent_impulse, self.inv_mass_a);
+            apply_body_impulse(&mut b, tangent_impulse, self.inv_mass_b);
+            return;
+        }
+        let mut t = rv_2.reject_from(self.normal);
         // 如果 t 为 0，不需要计算摩擦力
         if (t.length_squared() - 0.).abs() <= 0.0001 {
             return;
@@ -116,14 +378,121 @@ impl Manifold {
             return;
         }
         // 库仑定律
-        let tangent_impulse;
-        if jt.abs() < j * self.sf {
-            tangent_impulse = t * jt;
+        let tangent_impulse = if jt.abs() < j * self.sf {
+            t * jt
         } else {
-            tangent_impulse = t * (-j * self.df);
+            t * (-j * self.df)
+        };
+        apply_body_impulse(&mut a, -tangent_impulse, self.inv_mass_a);
+        apply_body_impulse(&mut b, tangent_impulse, self.inv_mass_b);
+        self.tangent_impulse_sum += tangent_impulse;
+    }
+
+    // 考虑转动的摩擦力：用接触点处的相对速度计算切线冲量，冲量同时改变物体的角速度；
+    // 之后施加滚动阻力，阻碍两个物体的相对转动
+    // * `j`: 本次迭代的法向冲量
+    // 返回施加的切线冲量
+    fn apply_torsional_friction(
+        &self,
+        a: &mut Body,
+        b: &mut Body,
+        j: Real,
+        resistance: Real,
+    ) -> Vec2 {
+        let point = self.contacts[0];
+        let ra = point - a.position();
+        let rb = point - b.position();
+        let t = self.normal.perp();
+        let rv_t = (point_velocity(b, rb) - point_velocity(a, ra)).dot(t);
+        let ra_t = ra.perp_dot(t);
+        let rb_t = rb.perp_dot(t);
+        let k = self.inv_mass_a
+            + self.inv_mass_b
+            + a.inverse_inertia() * ra_t * ra_t
+            + b.inverse_inertia() * rb_t * rb_t;
+        let mut impulse = Vec2::ZERO;
+        if k > 0. {
+            let mut jt = -rv_t / k;
+            // 库仑定律
+            if jt.abs() >= j * self.sf {
+                jt = jt.signum() * j * self.df;
+            }
+            impulse = t * jt;
+            apply_body_impulse(a, -impulse, self.inv_mass_a);
+            apply_body_impulse(b, impulse, self.inv_mass_b);
+            apply_angular_impulse(a, -ra.perp_dot(impulse));
+            apply_angular_impulse(b, rb.perp_dot(impulse));
+        }
+
+        // 滚动阻力
+        let inv_inertia_sum = a.inverse_inertia() + b.inverse_inertia();
+        if inv_inertia_sum > 0. {
+            let max_impulse = j * resistance;
+            let jr = (-(b.angular_velocity() - a.angular_velocity()) / inv_inertia_sum)
+                .clamp(-max_impulse, max_impulse);
+            apply_angular_impulse(a, -jr);
+            apply_angular_impulse(b, jr);
+        }
+        impulse
+    }
+
+    /// 位置修正，把互相侵入的两个物体沿碰撞法线推开一部分侵入量
+    /// * `max_distance`: 本次修正允许推开的最大距离，`None` 表示不限制
+    /// * `length_scale`: 每米对应的长度单位数，用来缩放允许的侵入量
+    pub(crate) fn positional_correction(
+        &mut self,
+        max_distance: Option<Real>,
+        length_scale: Real,
+    ) {
+        // 每次修正侵入量的比例
+        const PERCENT: Real = 0.4;
+        let mut a = self.a.borrow_mut();
+        let mut b = self.b.borrow_mut();
+        let inv_mass_sum = self.inv_mass_a + self.inv_mass_b;
+        if inv_mass_sum == 0. {
+            return;
+        }
+        let mut distance = (self.penetration - LINEAR_SLOP * length_scale).max(0.) * PERCENT;
+        if let Some(max_distance) = max_distance {
+            distance = distance.min(max_distance);
+        }
+        let correction = self.normal * (distance / inv_mass_sum);
+        let a_pos = a.position() - correction * self.inv_mass_a;
+        let b_pos = b.position() + correction * self.inv_mass_b;
+        a.set_position(a_pos);
+        b.set_position(b_pos);
+    }
+
+    // 单面线段只与位于法线一侧、并且沿法线方向被推开的物体碰撞
+    fn filter_one_sided(&mut self) {
+        let a = self.a.borrow();
+        let b = self.b.borrow();
+        for (segment_body, other, normal) in [(&a, &b, self.normal), (&b, &a, -self.normal)] {
+            let ShapeType::Segment(segment) = segment_body.shape() else {
+                continue;
+            };
+            let Some(n) = segment.normal() else {
+                continue;
+            };
+            let transform = segment_body.transform();
+            let n = transform.transform_vector(n);
+            let start = transform.transform_point(segment.a());
+            if n.dot(other.position() - start) < 0. || n.dot(normal) <= 0. {
+                self.contacts.clear();
+                return;
+            }
+        }
+    }
+
+    // 静态物体设置了表面法线函数时，用它替换碰撞检测得到的法线
+    fn override_normal(&mut self) {
+        let a = self.a.borrow();
+        let b = self.b.borrow();
+        if a.is_static() {
+            self.normal = a.surface_normal(b.position(), self.normal);
+        } else if b.is_static() {
+            self.normal = -b.surface_normal(a.position(), -self.normal);
         }
-        a.apply_impulse(-tangent_impulse);
-        b.apply_impulse(tangent_impulse);
     }
 
     fn circle_2_circle(&mut self, circle_a: &Circle, circle_b: &Circle) {
@@ -140,7 +509,7 @@ impl Manifold {
         if (dist - 0.).abs() < 0.00001 {
             // 两个圆处于同一位置
             self.penetration = circle_a.radius();
-            self.normal = Vec2::new(1., 0.);
+            self.normal = self.coincident_normal;
             self.contacts.push(a.position());
         } else {
             self.penetration = r - dist;
@@ -151,8 +520,10 @@ impl Manifold {
 
     fn circle_2_aabb(&mut self, circle: &Circle, aabb: &AABB) {
         std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
         self.aabb_2_circle(aabb, circle);
         self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
         std::mem::swap(&mut self.a, &mut self.b);
     }
 
@@ -161,53 +532,703 @@ impl Manifold {
     fn aabb_2_circle(&mut self, aabb: &AABB, circle: &Circle) {
         let a = self.a.borrow();
         let b = self.b.borrow();
-        let mut difference = b.position() - a.position();
-        let half_extend = (aabb.max() - aabb.min()) / 2.;
+        let center = a.position() + aabb.center();
+        let mut difference = b.position() - center;
+        let half_extend = aabb.half_extents();
 
         let clamped = difference.clamp(-half_extend, half_extend);
-        let closet = a.position() + clamped;
+        let closet = center + clamped;
         difference = closet - b.position();
-        if difference.length_squared() < circle.radius() * circle.radius() {
-            self.contacts.push(closet);
-            self.normal = b.position() - closet;
-            self.normal = self.normal.normalize();
-            self.penetration = 0.;
+        if difference.length_squared() >= circle.radius() * circle.radius() {
+            return;
         }
+        let (normal, penetration, contact) = match (b.position() - closet).try_normalize() {
+            Some(normal) => (normal, circle.radius() - difference.length(), closet),
+            // 圆心在 AABB 内部时最近点就是圆心，用 EPA 求出离圆心最近的面
+            None => epa_contact(&a, &b)
+                .unwrap_or((self.coincident_normal, circle.radius(), closet)),
+        };
+        self.contacts.push(contact);
+        self.normal = normal;
+        self.penetration = penetration;
     }
 
     fn aabb_2_aabb(&mut self, first: &AABB, second: &AABB) {
         let a = self.a.borrow();
         let b = self.b.borrow();
 
-        let n = b.position() - a.position();
-        let mut a_extend = (first.max().x - first.min().x) / 2.;
-        let mut b_extend = (second.max().x - second.min().x) / 2.;
+        let n = (b.position() + second.center()) - (a.position() + first.center());
+        // 两个 AABB 中心重合时，分离方向由重合策略决定
+        let dir = if n == Vec2::ZERO { self.coincident_normal } else { n };
+        let mut a_extend = first.half_extents().x;
+        let mut b_extend = second.half_extents().x;
         let x_overlap = a_extend + b_extend - n.x.abs();
         if x_overlap > 0. {
-            a_extend = (first.max().y - first.min().y) / 2.;
-            b_extend = (second.max().y - second.min().y) / 2.;
+            a_extend = first.half_extents().y;
+            b_extend = second.half_extents().y;
             let y_overlap = a_extend + b_extend - n.y.abs();
             // x y 方向都得发生重叠才会发生碰撞
             if y_overlap > 0. {
                 // 重叠小的方向是碰撞发生的方向
                 if x_overlap < y_overlap {
-                    if n.x < 0. {
+                    if dir.x < 0. {
                         self.normal = Vec2::new(-1., 0.);
                     } else {
                         self.normal = Vec2::new(1., 0.);
                     }
                     self.penetration = x_overlap;
                 } else {
-                    if n.y < 0. {
+                    if dir.y < 0. {
                         self.normal = Vec2::new(0., -1.);
                     } else {
                         self.normal = Vec2::new(0., 1.);
                     }
                     self.penetration = y_overlap;
                 }
-                self.contacts.push(Vec2::new(0., 0.));
+                self.contacts = box_contacts(
+                    self.normal,
+                    (a.position() + first.min(), a.position() + first.max()),
+                    (b.position() + second.min(), b.position() + second.max()),
+                );
+            }
+        }
+    }
+
+    fn circle_2_polygon(&mut self, circle: &Circle, polygon: &Polygon) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.polygon_2_circle(polygon, circle);
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+    }
+
+    fn polygon_2_circle(&mut self, polygon: &Polygon, circle: &Circle) {
+        self.convex_2_circle(polygon.vertices(), polygon.normals(), circle);
+    }
+
+    fn circle_2_triangle(&mut self, circle: &Circle, triangle: &Triangle) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.triangle_2_circle(triangle, circle);
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+    }
+
+    fn triangle_2_circle(&mut self, triangle: &Triangle, circle: &Circle) {
+        self.convex_2_circle(triangle.vertices(), triangle.normals(), circle);
+    }
+
+    // 凸多边形（A）与圆（B）的碰撞，`vertices` 和 `normals` 位于 A 的局部坐标系中
+    fn convex_2_circle(&mut self, vertices: &[Vec2], normals: &[Vec2], circle: &Circle) {
+        let a = self.a.borrow();
+        let b = self.b.borrow();
+        let transform = a.transform();
+        let radius = circle.radius();
+        // 在多边形的局部坐标系中计算
+        let center = transform.inverse_transform_point(b.position());
+        let (edge, separation) = (0..vertices.len())
+            .map(|i| (i, normals[i].dot(center - vertices[i])))
+            .fold((0, Real::NEG_INFINITY), |max, s| if s.1 > max.1 { s } else { max });
+        if separation > radius {
+            return;
+        }
+        let v1 = vertices[edge];
+        let v2 = vertices[(edge + 1) % vertices.len()];
+        let (normal, contact, distance) = if separation < 0.00001 {
+            // 圆心在多边形内部，沿分离量最小的边推出
+            (normals[edge], center - normals[edge] * separation, separation)
+        } else if (center - v1).dot(v2 - v1) <= 0. {
+            // 圆心离顶点 v1 最近
+            let d = center.distance(v1);
+            if d > radius {
+                return;
+            }
+            ((center - v1) / d, v1, d)
+        } else if (center - v2).dot(v1 - v2) <= 0. {
+            // 圆心离顶点 v2 最近
+            let d = center.distance(v2);
+            if d > radius {
+                return;
             }
+            ((center - v2) / d, v2, d)
+        } else {
+            (normals[edge], center - normals[edge] * separation, separation)
+        };
+        self.normal = transform.transform_vector(normal);
+        self.penetration = radius - distance;
+        self.contacts.push(transform.transform_point(contact));
+    }
+
+    // 多边形、三角形和 AABB 之间的碰撞，参见 `collision::sat`
+    fn polygon_2_polygon(&mut self) {
+        let contact = {
+            let a = self.a.borrow();
+            let b = self.b.borrow();
+            let proxy = |body: &Body| {
+                ConvexProxy::from_shape(&body.shape(), &body.transform()).expect("not a polygon")
+            };
+            sat(&proxy(&a), &proxy(&b))
+        };
+        if let Some(contact) = contact {
+            self.normal = contact.normal;
+            self.penetration = contact.penetration;
+            self.contacts = contact.points;
         }
     }
 
+    // 圆和胶囊体之间的碰撞：先求两条核心线段上最近的两个点，再按两个圆处理
+    fn rounded_2_rounded(&mut self) {
+        let (a1, b1, radius_a) = rounded_core(&self.a.borrow());
+        let (a2, b2, radius_b) = rounded_core(&self.b.borrow());
+        let (point_a, point_b) = closest_points_on_segments(a1, b1, a2, b2);
+        // 核心线段相交时最近点无法给出方向，用 EPA 求出侵入最浅的方向
+        let contact = if point_a.distance(point_b) < 0.00001 {
+            epa_contact(&self.a.borrow(), &self.b.borrow())
+        } else {
+            None
+        };
+        let contact = contact.or_else(|| {
+            rounded_contact(point_a, radius_a, point_b, radius_b, self.coincident_normal)
+        });
+        self.push_contact(contact);
+    }
+
+    fn point_2_shape(&mut self) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.shape_2_point();
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+    }
+
+    // 有面积的形状（A）与点（B）的碰撞，点位于形状内部时沿最近的方向推出
+    fn shape_2_point(&mut self) {
+        let a = self.a.borrow();
+        let point = self.b.borrow().position();
+        let contact = match a.shape() {
+            ShapeType::Circle(circle) => {
+                rounded_contact(a.position(), circle.radius(), point, 0., self.coincident_normal)
+            }
+            ShapeType::Capsule(_) => {
+                let (p, q, radius) = rounded_core(&a);
+                let closest = closest_point_on_segment(point, p, q);
+                rounded_contact(closest, radius, point, 0., self.coincident_normal)
+            }
+            ShapeType::AABB(aabb) => {
+                let local = point - (a.position() + aabb.center());
+                let depth = aabb.half_extents() - local.abs();
+                if depth.x <= 0. || depth.y <= 0. {
+                    None
+                } else if depth.x < depth.y {
+                    let sign = if local.x < 0. { -1. } else { 1. };
+                    Some((Vec2::new(sign, 0.), depth.x, point))
+                } else {
+                    let sign = if local.y < 0. { -1. } else { 1. };
+                    Some((Vec2::new(0., sign), depth.y, point))
+                }
+            }
+            ShapeType::Polygon(polygon) => {
+                convex_point_contact(polygon.vertices(), polygon.normals(), a.transform(), point)
+            }
+            ShapeType::Triangle(triangle) => {
+                convex_point_contact(triangle.vertices(), triangle.normals(), a.transform(), point)
+            }
+            _ => None,
+        };
+        drop(a);
+        self.push_contact(contact);
+    }
+
+    // 记录窄检测得到的 `(法线, 侵入量, 碰撞点)`
+    fn push_contact(&mut self, contact: Option<(Vec2, Real, Vec2)>) {
+        if let Some((normal, penetration, point)) = contact {
+            self.normal = normal;
+            self.penetration = penetration;
+            self.contacts.push(point);
+        }
+    }
+
+    fn rounded_2_polygon(&mut self) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.polygon_2_rounded();
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+    }
+
+    fn shape_2_chain(&mut self, chain: &Chain) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.chain_2_shape(chain);
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+        std::mem::swap(&mut self.sub_shape_a, &mut self.sub_shape_b);
+    }
+
+    // 折线与其他形状的碰撞：分别检测每条边，取侵入量最大的一个。
+    // 碰撞点位于边的端点附近、并且法线指向相邻的边一侧时，这个碰撞属于相邻的边，
+    // 跳过它可以避免物体在两条边的接缝处被卡住
+    fn chain_2_shape(&mut self, chain: &Chain) {
+        // 允许的误差
+        const TOLERANCE: Real = 0.001;
+        let transform = self.a.borrow().transform();
+        let other = self.b.borrow();
+        let polygon = match other.shape() {
+            ShapeType::AABB(_) | ShapeType::Polygon(_) | ShapeType::Triangle(_) => {
+                Some(world_polygon(&other))
+            }
+            _ => None,
+        };
+        let mut best: Option<(Vec2, Real, Vec2)> = None;
+        let mut best_edge = 0;
+        for i in 0..chain.edge_count() {
+            let (ghost1, v1, v2, ghost2) = chain.ghost_edge(i);
+            let v1 = transform.transform_point(v1);
+            let v2 = transform.transform_point(v2);
+            let contact = match &polygon {
+                Some(polygon) => polygon_rounded_contact(polygon, v1, v2, 0.)
+                    .map(|(normal, penetration, point)| (-normal, penetration, point)),
+                None => {
+                    let (p, q, radius) = rounded_core(&other);
+                    let (point_a, point_b) = closest_points_on_segments(v1, v2, p, q);
+                    let fallback = (v2 - v1).perp().try_normalize().unwrap_or(self.coincident_normal);
+                    rounded_contact(point_a, 0., point_b, radius, fallback)
+                }
+            };
+            let Some((normal, penetration, point)) = contact else {
+                continue;
+            };
+            let edge = v2 - v1;
+            let u = (point - v1).dot(edge) / edge.length_squared();
+            if let Some(ghost1) = ghost1 {
+                let prev = (v1 - transform.transform_point(ghost1)).try_normalize();
+                if u <= TOLERANCE && prev.is_some_and(|e| normal.dot(e) < -TOLERANCE) {
+                    continue;
+                }
+            }
+            if let Some(ghost2) = ghost2 {
+                let next = (transform.transform_point(ghost2) - v2).try_normalize();
+                if u >= 1. - TOLERANCE && next.is_some_and(|e| normal.dot(e) > TOLERANCE) {
+                    continue;
+                }
+            }
+            if best.is_none_or(|b| penetration > b.1) {
+                best = Some((normal, penetration, point));
+                best_edge = i;
+            }
+        }
+        drop(other);
+        self.sub_shape_a = best_edge;
+        self.push_contact(best);
+    }
+
+    fn shape_2_compound(&mut self, compound: &Compound, narrow_phase: &NarrowPhase) {
+        std::mem::swap(&mut self.a, &mut self.b);
+        self.coincident_normal = -self.coincident_normal;
+        self.compound_2_shape(compound, narrow_phase);
+        self.normal = -self.normal;
+        self.coincident_normal = -self.coincident_normal;
+        std::mem::swap(&mut self.a, &mut self.b);
+        std::mem::swap(&mut self.sub_shape_a, &mut self.sub_shape_b);
+    }
+
+    // 复合形状与其他形状的碰撞：每个子形状分别与另一个物体检测，
+    // 法线和侵入量取侵入量最大的子形状的结果，并合并法线方向与之一致的子形状的碰撞点
+    fn compound_2_shape(&mut self, compound: &Compound, narrow_phase: &NarrowPhase) {
+        // 允许的误差
+        const TOLERANCE: Real = 0.001;
+        let transform = self.a.borrow().transform();
+        let children: Vec<(usize, Manifold)> = compound
+            .fixtures()
+            .iter()
+            .enumerate()
+            .map(|(i, fixture)| {
+                let proxy = Body::proxy(fixture.shape().clone(), transform * fixture.transform());
+                let mut child = Manifold::new(Rc::new(RefCell::new(proxy)), self.b.clone());
+                child.coincident_normal = self.coincident_normal;
+                child.collide(narrow_phase);
+                (i, child)
+            })
+            .filter(|(_, child)| !child.contacts.is_empty())
+            .collect();
+        let Some((index, deepest)) = children
+            .iter()
+            .max_by(|x, y| x.1.penetration.total_cmp(&y.1.penetration))
+        else {
+            return;
+        };
+        self.normal = deepest.normal;
+        self.penetration = deepest.penetration;
+        self.sub_shape_a = *index;
+        self.sub_shape_b = deepest.sub_shape_b;
+        for (_, child) in &children {
+            if child.normal.dot(deepest.normal) >= 1. - TOLERANCE {
+                self.contacts.extend_from_slice(&child.contacts);
+            }
+        }
+    }
+
+    // 多边形或者 AABB 与胶囊体、线段的碰撞
+    fn polygon_2_rounded(&mut self) {
+        let polygon = world_polygon(&self.a.borrow());
+        let (p, q, radius) = rounded_core(&self.b.borrow());
+        self.push_contact(polygon_rounded_contact(&polygon, p, q, radius));
+    }
+}
+
+// 多边形、三角形或者 AABB 物体在世界坐标系下的凸多边形，AABB 不会转动，只有平移
+fn world_polygon(body: &Body) -> WorldPolygon {
+    match body.shape() {
+        ShapeType::Polygon(polygon) => {
+            WorldPolygon::new(polygon.vertices(), polygon.normals(), body.transform())
+        }
+        ShapeType::Triangle(triangle) => {
+            WorldPolygon::new(triangle.vertices(), triangle.normals(), body.transform())
+        }
+        ShapeType::AABB(aabb) => {
+            let polygon = Polygon::new_box(aabb.half_extents());
+            let xf = Transform2D::from_translation(body.position() + aabb.center());
+            WorldPolygon::new(polygon.vertices(), polygon.normals(), xf)
+        }
+        _ => unreachable!("not a polygon"),
+    }
+}
+
+// 圆、胶囊体或者线段在世界坐标系下的核心线段和半径，圆的线段退化为一个点，线段的半径为 0
+fn rounded_core(body: &Body) -> (Vec2, Vec2, Real) {
+    match body.shape() {
+        ShapeType::Circle(circle) => (body.position(), body.position(), circle.radius()),
+        ShapeType::Capsule(capsule) => {
+            let transform = body.transform();
+            (
+                transform.transform_point(capsule.a()),
+                transform.transform_point(capsule.b()),
+                capsule.radius(),
+            )
+        }
+        ShapeType::Segment(segment) => {
+            let transform = body.transform();
+            (
+                transform.transform_point(segment.a()),
+                transform.transform_point(segment.b()),
+                0.,
+            )
+        }
+        ShapeType::Point(_) => (body.position(), body.position(), 0.),
+        _ => unreachable!("not a rounded shape"),
+    }
+}
+
+// 变换到世界坐标系下的凸多边形
+struct WorldPolygon {
+    count: usize,
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    normals: [Vec2; MAX_POLYGON_VERTICES],
+}
+
+impl WorldPolygon {
+    fn new(vertices: &[Vec2], normals: &[Vec2], transform: Transform2D) -> WorldPolygon {
+        let mut result = WorldPolygon {
+            count: vertices.len(),
+            vertices: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+            normals: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+        };
+        for (i, (v, n)) in vertices.iter().zip(normals).enumerate() {
+            result.vertices[i] = transform.transform_point(*v);
+            result.normals[i] = transform.transform_vector(*n);
+        }
+        result
+    }
+}
+
+// 两个重叠的 AABB 的碰撞点：B 上朝向 A 的面在两个 AABB 的重叠范围内的两个端点，
+// 与多边形碰撞时用 A 的面裁剪 B 的入射边得到的碰撞点相同。重叠范围只有一个点时只返回一个碰撞点
+fn box_contacts(
+    normal: Vec2,
+    (a_min, a_max): (Vec2, Vec2),
+    (b_min, b_max): (Vec2, Vec2),
+) -> Vec<Vec2> {
+    let (low, high) = (a_min.max(b_min), a_max.min(b_max));
+    let points = if normal.x != 0. {
+        let x = if normal.x > 0. { b_min.x } else { b_max.x };
+        [Vec2::new(x, low.y), Vec2::new(x, high.y)]
+    } else {
+        let y = if normal.y > 0. { b_min.y } else { b_max.y };
+        [Vec2::new(low.x, y), Vec2::new(high.x, y)]
+    };
+    if points[0] == points[1] {
+        vec![points[0]]
+    } else {
+        points.to_vec()
+    }
+}
+
+// 以 `point_a`、`point_b` 为圆心的两个圆之间的碰撞，两个圆心重合时使用 `fallback` 作为法线，
+// 返回 `(A 指向 B 的法线, 侵入量, 碰撞点)`
+fn rounded_contact(
+    point_a: Vec2,
+    radius_a: Real,
+    point_b: Vec2,
+    radius_b: Real,
+    fallback: Vec2,
+) -> Option<(Vec2, Real, Vec2)> {
+    let r = radius_a + radius_b;
+    let dist = point_a.distance(point_b);
+    if dist >= r {
+        return None;
+    }
+    let normal = if dist < 0.00001 {
+        fallback
+    } else {
+        (point_b - point_a) / dist
+    };
+    Some((normal, r - dist, point_a + normal * radius_a))
+}
+
+// 用 EPA 求两个物体的凸形状之间的侵入，形状退化无法确定方向时返回 `None`。
+// 返回 `(A 指向 B 的法线, 侵入量, 碰撞点)`
+fn epa_contact(a: &Body, b: &Body) -> Option<(Vec2, Real, Vec2)> {
+    let output = shape_penetration(&a.shape(), &a.transform(), &b.shape(), &b.transform())?;
+    (output.normal != Vec2::ZERO).then_some((output.normal, output.depth, output.point_a))
+}
+
+// 按 `transform` 放置的凸多边形与点 `point` 的碰撞，点位于多边形内部时沿分离量最小的边推出。
+// 返回 `(多边形指向点的法线, 侵入量, 碰撞点)`
+fn convex_point_contact(
+    vertices: &[Vec2],
+    normals: &[Vec2],
+    transform: Transform2D,
+    point: Vec2,
+) -> Option<(Vec2, Real, Vec2)> {
+    let local = transform.inverse_transform_point(point);
+    let (edge, separation) = (0..vertices.len())
+        .map(|i| (i, normals[i].dot(local - vertices[i])))
+        .fold((0, Real::NEG_INFINITY), |max, s| if s.1 > max.1 { s } else { max });
+    if separation >= 0. {
+        return None;
+    }
+    Some((transform.transform_vector(normals[edge]), -separation, point))
+}
+
+// 多边形与核心线段为 `(p, q)`、半径为 `radius` 的胶囊体的碰撞，线段当作半径为 0 的胶囊体。
+// 先用分离轴定理检测多边形与胶囊体的核心线段，分离轴为多边形各条边的法线和线段的法线；
+// 核心线段与多边形不相交时求两者之间的最近点，按圆处理。
+// 返回 `(多边形指向胶囊体的法线, 侵入量, 碰撞点)`
+fn polygon_rounded_contact(
+    polygon: &WorldPolygon,
+    p: Vec2,
+    q: Vec2,
+    radius: Real,
+) -> Option<(Vec2, Real, Vec2)> {
+    let vertices = &polygon.vertices[..polygon.count];
+    // (分离量, 法线, 碰撞点)
+    let mut best = (Real::NEG_INFINITY, Vec2::ZERO, Vec2::ZERO);
+    for (n, v) in polygon.normals.iter().zip(vertices) {
+        let (separation, point) = [p, q]
+            .into_iter()
+            .map(|point| (n.dot(point - *v), point))
+            .fold((Real::INFINITY, p), |min, s| if s.0 < min.0 { s } else { min });
+        if separation > best.0 {
+            best = (separation, *n, point - *n * separation);
+        }
+    }
+    if let Some(axis) = (q - p).try_normalize() {
+        for side in [axis.perp(), -axis.perp()] {
+            let (separation, vertex) = vertices
+                .iter()
+                .map(|v| (side.dot(*v - p), *v))
+                .fold((Real::INFINITY, p), |min, s| if s.0 < min.0 { s } else { min });
+            if separation > best.0 {
+                best = (separation, -side, vertex);
+            }
+        }
+    }
+    let (separation, normal, contact) = best;
+    if separation > radius {
+        return None;
+    }
+    if separation > 0. {
+        // 核心线段在多边形外部，最近点可能在顶点附近，需要求精确的距离
+        let (point_a, point_b) = (0..polygon.count)
+            .map(|i| {
+                let v1 = vertices[i];
+                let v2 = vertices[(i + 1) % polygon.count];
+                closest_points_on_segments(v1, v2, p, q)
+            })
+            .min_by(|x, y| x.0.distance_squared(x.1).total_cmp(&y.0.distance_squared(y.1)))
+            .unwrap_or((contact, p));
+        return rounded_contact(point_a, 0., point_b, radius, normal);
+    }
+    Some((normal, radius - separation, contact))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::Point;
+
+    fn body(body: Body) -> Rc<RefCell<Body>> {
+        Rc::new(RefCell::new(body))
+    }
+
+    fn polygon_box(position: Vec2) -> Rc<RefCell<Body>> {
+        body(Body::new_polygon(Polygon::new_box(Vec2::new(1., 1.)), position, 0.5))
+    }
+
+    fn assert_manifold(m: &Manifold, normal: Vec2, penetration: Real, contacts: usize) {
+        assert!(m.normal().distance(normal) < 0.0001, "{:?} != {:?}", m.normal(), normal);
+        assert!((m.penetration() - penetration).abs() < 0.0001);
+        assert_eq!(m.get_contacts().len(), contacts);
+    }
+
+    /// Test cases for:
+    /// * polygon_2_polygon()
+    #[test]
+    fn polygon_polygon_should_collide() {
+        let narrow_phase = NarrowPhase::new();
+        let a = polygon_box(Vec2::ZERO);
+        let b = polygon_box(Vec2::new(1.5, 0.));
+        let m = Manifold::solve(a.clone(), b.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(1., 0.), 0.5, 2);
+        for p in m.get_contacts() {
+            assert!(p.x >= 0.5 - 0.0001 && p.x <= 1. + 0.0001);
+        }
+        let m = Manifold::solve(b, a, &narrow_phase);
+        assert_manifold(&m, Vec2::new(-1., 0.), 0.5, 2);
+
+        let far = polygon_box(Vec2::new(2.5, 0.));
+        assert!(Manifold::solve(polygon_box(Vec2::ZERO), far, &narrow_phase).get_contacts().is_empty());
+    }
+
+    /// Test cases for:
+    /// * polygon_2_circle(&Polygon, &Circle)
+    /// * circle_2_polygon(&Circle, &Polygon)
+    #[test]
+    fn polygon_circle_should_collide() {
+        let narrow_phase = NarrowPhase::new();
+        let polygon = polygon_box(Vec2::ZERO);
+        let circle = body(Body::new_circle(Circle::new(1.), Vec2::new(1.8, 0.), 0.5));
+        let m = Manifold::solve(polygon.clone(), circle.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(1., 0.), 0.2, 1);
+        assert!(m.get_contacts()[0].distance(Vec2::new(0.8, 0.)) < 0.2 + 0.0001);
+        let m = Manifold::solve(circle, polygon, &narrow_phase);
+        assert_manifold(&m, Vec2::new(-1., 0.), 0.2, 1);
+    }
+
+    /// Test cases for:
+    /// * polygon_2_polygon()
+    #[test]
+    fn polygon_aabb_should_collide() {
+        let narrow_phase = NarrowPhase::new();
+        let polygon = polygon_box(Vec2::ZERO);
+        let aabb = AABB::from_half_extents(Vec2::new(1., 1.));
+        let aabb = body(Body::new_aabb(aabb, Vec2::new(0., 1.7), 0.5));
+        let m = Manifold::solve(polygon.clone(), aabb.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., 1.), 0.3, 2);
+        let m = Manifold::solve(aabb, polygon, &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., -1.), 0.3, 2);
+    }
+
+    /// Test cases for:
+    /// * box_contacts(normal, a, b)
+    #[test]
+    fn box_contacts_should_span_overlap() {
+        let a = (Vec2::new(0., 0.), Vec2::new(2., 2.));
+        // B 压在 A 的下方，碰撞点在 B 的上表面上、x 方向重叠范围的两端
+        let b = (Vec2::new(1., 1.9), Vec2::new(3., 3.9));
+        let contacts = box_contacts(Vec2::new(0., 1.), a, b);
+        assert_eq!(contacts, [Vec2::new(1., 1.9), Vec2::new(2., 1.9)]);
+        // 交换 A、B 之后碰撞点在 A 的下表面上
+        let contacts = box_contacts(Vec2::new(0., -1.), b, a);
+        assert_eq!(contacts, [Vec2::new(1., 2.), Vec2::new(2., 2.)]);
+        // B 在 A 的左侧
+        let b = (Vec2::new(-1.9, 0.5), Vec2::new(0.1, 1.5));
+        let contacts = box_contacts(Vec2::new(-1., 0.), a, b);
+        assert_eq!(contacts, [Vec2::new(0.1, 0.5), Vec2::new(0.1, 1.5)]);
+
+        // 两个 AABB 物体上下叠放
+        let narrow_phase = NarrowPhase::new();
+        let aabb = AABB::from_half_extents(Vec2::new(1., 1.));
+        let top = body(Body::new_aabb(aabb, Vec2::ZERO, 0.5));
+        let bottom = body(Body::new_aabb(aabb, Vec2::new(1., 1.9), 0.5));
+        let m = Manifold::solve(top, bottom, &narrow_phase);
+        assert!((m.penetration() - 0.1).abs() < 0.0001);
+        assert_eq!(m.normal(), Vec2::new(0., 1.));
+        let contacts = m.get_contacts();
+        assert_eq!(contacts.len(), 2);
+        assert!(contacts[0].distance(Vec2::new(0., 0.9)) < 0.0001);
+        assert!(contacts[1].distance(Vec2::new(1., 0.9)) < 0.0001);
+    }
+
+    /// Test cases for:
+    /// * box_contacts(normal, a, b)
+    #[test]
+    fn box_contacts_should_merge_corner_contact() {
+        let a = (Vec2::new(0., 0.), Vec2::new(2., 2.));
+        // 两个 AABB 只在角上接触，重叠范围只有一个点
+        let b = (Vec2::new(1.9, 2.), Vec2::new(3.9, 4.));
+        assert_eq!(box_contacts(Vec2::new(1., 0.), a, b), [Vec2::new(1.9, 2.)]);
+        let b = (Vec2::new(2., 1.9), Vec2::new(4., 3.9));
+        assert_eq!(box_contacts(Vec2::new(0., 1.), a, b), [Vec2::new(2., 1.9)]);
+    }
+
+    /// Test cases for:
+    /// * triangle_2_circle(&Triangle, &Circle)
+    /// * circle_2_triangle(&Circle, &Triangle)
+    /// * polygon_2_polygon()
+    #[test]
+    fn triangle_should_collide() {
+        let narrow_phase = NarrowPhase::new();
+        let shape = Triangle::new(Vec2::new(-1., -1.), Vec2::new(1., -1.), Vec2::new(0., 2.));
+        let triangle = body(Body::new_triangle(shape, Vec2::ZERO, 0.5));
+        let circle = body(Body::new_circle(Circle::new(1.), Vec2::new(0., -1.8), 0.5));
+        let m = Manifold::solve(triangle.clone(), circle.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., -1.), 0.2, 1);
+        assert!(m.get_contacts()[0].distance(Vec2::new(0., -1.)) < 0.2 + 0.0001);
+        let m = Manifold::solve(circle, triangle.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., 1.), 0.2, 1);
+
+        // 圆在顶点附近时法线从顶点指向圆心
+        let circle = body(Body::new_circle(Circle::new(1.), Vec2::new(0., 2.9), 0.5));
+        let m = Manifold::solve(triangle.clone(), circle, &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., 1.), 0.1, 1);
+
+        let m = Manifold::solve(triangle, polygon_box(Vec2::new(0., -1.7)), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., -1.), 0.3, 2);
+    }
+
+    /// Test cases for:
+    /// * shape_2_point()
+    /// * point_2_shape()
+    #[test]
+    fn point_should_collide_with_shapes_that_have_area() {
+        let narrow_phase = NarrowPhase::new();
+        let point = |position: Vec2| body(Body::new_point(Point::new(1.), position, 0.5));
+        let aabb = AABB::from_half_extents(Vec2::new(2., 1.));
+        let aabb = body(Body::new_aabb(aabb, Vec2::ZERO, 0.5));
+        // 点在 AABB 内部靠近上表面的位置，沿最近的方向推出
+        let m = Manifold::solve(aabb.clone(), point(Vec2::new(1., -0.8)), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., -1.), 0.2, 1);
+        assert_eq!(m.get_contacts()[0], Vec2::new(1., -0.8));
+        let m = Manifold::solve(point(Vec2::new(1.9, 0.)), aabb.clone(), &narrow_phase);
+        assert_manifold(&m, Vec2::new(-1., 0.), 0.1, 1);
+        assert!(Manifold::solve(aabb, point(Vec2::new(2.1, 0.)), &narrow_phase)
+            .get_contacts()
+            .is_empty());
+
+        let circle = body(Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5));
+        let m = Manifold::solve(circle, point(Vec2::new(0.7, 0.)), &narrow_phase);
+        assert_manifold(&m, Vec2::new(1., 0.), 0.3, 1);
+        let m = Manifold::solve(polygon_box(Vec2::ZERO), point(Vec2::new(0., 0.9)), &narrow_phase);
+        assert_manifold(&m, Vec2::new(0., 1.), 0.1, 1);
+
+        // 点与点之间不会发生碰撞
+        let m = Manifold::solve(point(Vec2::ZERO), point(Vec2::ZERO), &narrow_phase);
+        assert!(m.get_contacts().is_empty());
+    }
 }
@@ -0,0 +1,189 @@
+use std::ops;
+
+use crate::{math::Real, vec2::Vec2};
+
+/// 2x2 matrix, stored as two column vectors
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mat2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+}
+
+impl Mat2 {
+    pub const ZERO: Self = Self::from_cols(Vec2::ZERO, Vec2::ZERO);
+    pub const IDENTITY: Self = Self::from_cols(Vec2::X, Vec2::Y);
+
+    /// creates a `Mat2` from two column vectors
+    #[inline(always)]
+    pub const fn from_cols(x_axis: Vec2, y_axis: Vec2) -> Mat2 {
+        Mat2 { x_axis, y_axis }
+    }
+
+    /// creates a `Mat2` from its elements in row-major order
+    #[inline]
+    pub fn new(m00: Real, m01: Real, m10: Real, m11: Real) -> Mat2 {
+        Mat2::from_cols(Vec2::new(m00, m10), Vec2::new(m01, m11))
+    }
+
+    /// Creates a rotation matrix for the given counter-clockwise `angle` (in radians).
+    #[inline]
+    pub fn from_angle(angle: Real) -> Mat2 {
+        let (sin, cos) = angle.sin_cos();
+        Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos))
+    }
+
+    /// Creates a diagonal matrix scaling each axis by the matching component of `scale`.
+    #[inline]
+    pub fn from_scale(scale: Vec2) -> Mat2 {
+        Mat2::from_cols(Vec2::new(scale.x, 0.), Vec2::new(0., scale.y))
+    }
+
+    /// Returns the `index`-th row.
+    ///
+    /// # Panics
+    ///
+    /// will panic if `index` is greater than 1
+    #[inline]
+    pub fn row(&self, index: usize) -> Vec2 {
+        Vec2::new(self.x_axis[index], self.y_axis[index])
+    }
+
+    /// Returns the transpose of `self`.
+    #[must_use]
+    #[inline]
+    pub fn transpose(&self) -> Mat2 {
+        Mat2::from_cols(self.row(0), self.row(1))
+    }
+
+    /// Returns the determinant of `self`.
+    #[inline]
+    pub fn determinant(&self) -> Real {
+        self.x_axis.perp_dot(self.y_axis)
+    }
+
+    /// Returns the inverse of `self`, or `None` if `self` is not invertible.
+    #[must_use]
+    #[inline]
+    pub fn inverse(&self) -> Option<Mat2> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        Some(Mat2::from_cols(
+            Vec2::new(self.y_axis.y, -self.x_axis.y) * inv_det,
+            Vec2::new(-self.y_axis.x, self.x_axis.x) * inv_det,
+        ))
+    }
+
+    /// Transforms `rhs` by the transpose of `self`, which for a rotation matrix is the inverse
+    /// rotation.
+    #[inline]
+    pub fn mul_transpose_vec2(&self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x_axis.dot(rhs), self.y_axis.dot(rhs))
+    }
+}
+
+impl ops::Mul<Vec2> for Mat2 {
+    type Output = Vec2;
+
+    #[inline]
+    fn mul(self, rhs: Vec2) -> Self::Output {
+        self.x_axis * rhs.x + self.y_axis * rhs.y
+    }
+}
+
+impl ops::Mul<Mat2> for Mat2 {
+    type Output = Mat2;
+
+    #[inline]
+    fn mul(self, rhs: Mat2) -> Self::Output {
+        Mat2::from_cols(self * rhs.x_axis, self * rhs.y_axis)
+    }
+}
+
+impl ops::Mul<Real> for Mat2 {
+    type Output = Mat2;
+
+    #[inline]
+    fn mul(self, rhs: Real) -> Self::Output {
+        Mat2::from_cols(self.x_axis * rhs, self.y_axis * rhs)
+    }
+}
+
+impl ops::Add<Mat2> for Mat2 {
+    type Output = Mat2;
+
+    #[inline]
+    fn add(self, rhs: Mat2) -> Self::Output {
+        Mat2::from_cols(self.x_axis + rhs.x_axis, self.y_axis + rhs.y_axis)
+    }
+}
+
+impl ops::Sub<Mat2> for Mat2 {
+    type Output = Mat2;
+
+    #[inline]
+    fn sub(self, rhs: Mat2) -> Self::Output {
+        Mat2::from_cols(self.x_axis - rhs.x_axis, self.y_axis - rhs.y_axis)
+    }
+}
+
+impl Default for Mat2 {
+    fn default() -> Self {
+        Mat2::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::consts::FRAC_PI_2;
+
+    fn assert_vec2_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    /// Test cases for:
+    /// * Mat2::from_angle(Real)
+    /// * Mat2 * Vec2
+    /// * Mat2.mul_transpose_vec2(Vec2)
+    #[test]
+    fn mat2_rotation_should_work() {
+        let rotation = Mat2::from_angle(FRAC_PI_2);
+        assert_vec2_near(rotation * Vec2::X, Vec2::Y);
+        assert_vec2_near(rotation * Vec2::Y, -Vec2::X);
+        assert_vec2_near(rotation.mul_transpose_vec2(Vec2::Y), Vec2::X);
+
+        let v = Vec2::new(3., -2.);
+        assert_vec2_near(rotation * v, v.rotate(Vec2::from_angle(FRAC_PI_2)));
+    }
+
+    /// Test cases for:
+    /// * Mat2.transpose()
+    /// * Mat2.determinant()
+    #[test]
+    fn mat2_transpose_determinant_should_work() {
+        let m = Mat2::new(1., 2., 3., 4.);
+        assert_eq!(m.transpose(), Mat2::new(1., 3., 2., 4.));
+        assert_eq!(m.row(0), Vec2::new(1., 2.));
+        assert_eq!(m.determinant(), -2.);
+        assert_eq!(Mat2::from_angle(1.2).transpose(), Mat2::from_angle(-1.2));
+    }
+
+    /// Test cases for:
+    /// * Mat2.inverse()
+    /// * Mat2 * Mat2
+    #[test]
+    fn mat2_inverse_should_work() {
+        let m = Mat2::new(4., 7., 2., 6.);
+        let inverse = m.inverse().unwrap();
+        let identity = m * inverse;
+        assert_vec2_near(identity.x_axis, Vec2::X);
+        assert_vec2_near(identity.y_axis, Vec2::Y);
+
+        assert_eq!(Mat2::new(1., 2., 2., 4.).inverse(), None);
+        assert_eq!(Mat2::ZERO.inverse(), None);
+    }
+}
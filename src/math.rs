@@ -0,0 +1,214 @@
+use crate::vec2::Vec2;
+
+/// The floating point type used by the whole pipeline, `f64` with the `f64` feature enabled,
+/// otherwise `f32`.
+#[cfg(not(feature = "f64"))]
+pub type Real = f32;
+/// The floating point type used by the whole pipeline, `f64` with the `f64` feature enabled,
+/// otherwise `f32`.
+#[cfg(feature = "f64")]
+pub type Real = f64;
+
+/// Basic mathematical constants for [`Real`].
+#[cfg(not(feature = "f64"))]
+pub use std::f32::consts;
+/// Basic mathematical constants for [`Real`].
+#[cfg(feature = "f64")]
+pub use std::f64::consts;
+
+/// Moves `current` towards `target` like a critically damped spring, and returns the new value.
+///
+/// `velocity` is the current velocity and is updated in place, keep it between calls.
+/// `smoothing` is roughly the time it takes to reach the target, must be greater than zero.
+/// Unlike `current.lerp(target, t)` with a constant `t`, the result does not depend on the
+/// frame rate: stepping twice with `dt / 2` gives (almost) the same result as stepping once
+/// with `dt`.
+#[inline]
+pub fn critically_damped_spring(
+    current: Real,
+    target: Real,
+    velocity: &mut Real,
+    smoothing: Real,
+    dt: Real,
+) -> Real {
+    let (factor, omega) = spring_factor(smoothing, dt);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * dt;
+    *velocity = (*velocity - omega * temp) * factor;
+    target + (change + temp) * factor
+}
+
+/// Vector version of [`critically_damped_spring`].
+#[inline]
+pub fn critically_damped_spring_vec2(
+    current: Vec2,
+    target: Vec2,
+    velocity: &mut Vec2,
+    smoothing: Real,
+    dt: Real,
+) -> Vec2 {
+    let (factor, omega) = spring_factor(smoothing, dt);
+    let change = current - target;
+    let temp = (*velocity + change * omega) * dt;
+    *velocity = (*velocity - temp * omega) * factor;
+    target + (change + temp) * factor
+}
+
+// Returns the decay factor `exp(-omega * dt)` (approximated) and `omega`.
+#[inline]
+fn spring_factor(smoothing: Real, dt: Real) -> (Real, Real) {
+    let omega = 2. / smoothing.max(0.0001);
+    let x = omega * dt;
+    let factor = 1. / (1. + x + 0.48 * x * x + 0.235 * x * x * x);
+    (factor, omega)
+}
+
+/// Wraps `angle` (in radians) into the range `[-π, π)`.
+#[inline]
+pub fn wrap_angle(angle: Real) -> Real {
+    (angle + consts::PI).rem_euclid(consts::TAU) - consts::PI
+}
+
+/// Returns the signed smallest rotation (in radians) from `from` to `to`, in the range `[-π, π)`.
+/// Positive values are counter-clockwise.
+#[inline]
+pub fn shortest_angle_between(from: Real, to: Real) -> Real {
+    wrap_angle(to - from)
+}
+
+/// Restricts `angle` to the arc going counter-clockwise from `min` to `max`, all in radians.
+/// Angles outside the arc snap to the nearer end. The result is wrapped into `[-π, π)`.
+pub fn clamp_angle(angle: Real, min: Real, max: Real) -> Real {
+    let span = (max - min).rem_euclid(consts::TAU);
+    let offset = (angle - min).rem_euclid(consts::TAU);
+    if offset <= span {
+        wrap_angle(angle)
+    } else if offset - span < consts::TAU - offset {
+        wrap_angle(max)
+    } else {
+        wrap_angle(min)
+    }
+}
+
+/// Returns the point on the segment `a`-`b` closest to `point`.
+#[inline]
+pub fn closest_point_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let len_sqr = ab.length_squared();
+    if len_sqr == 0. {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / len_sqr).clamp(0., 1.);
+    a + ab * t
+}
+
+/// Returns the closest pair of points between the segments `p1`-`q1` and `p2`-`q2`,
+/// the first point lies on the first segment.
+pub fn closest_points_on_segments(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> (Vec2, Vec2) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+    if a == 0. {
+        return (p1, closest_point_on_segment(p1, p2, q2));
+    }
+    let c = d1.dot(r);
+    if e == 0. {
+        return (closest_point_on_segment(p2, p1, q1), p2);
+    }
+    let b = d1.dot(d2);
+    let denom = a * e - b * b;
+    // parallel segments pick an arbitrary point on the first segment
+    let mut s = if denom > 0. {
+        ((b * f - c * e) / denom).clamp(0., 1.)
+    } else {
+        0.
+    };
+    let mut t = (b * s + f) / e;
+    if t < 0. {
+        t = 0.;
+        s = (-c / a).clamp(0., 1.);
+    } else if t > 1. {
+        t = 1.;
+        s = ((b - c) / a).clamp(0., 1.);
+    }
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critically_damped_spring_should_reach_target() {
+        let mut current = 0.;
+        let mut velocity = 0.;
+        for _ in 0..120 {
+            let next = critically_damped_spring(current, 10., &mut velocity, 0.2, 1. / 60.);
+            // critically damped, never overshoots
+            assert!(next >= current && next <= 10.);
+            current = next;
+        }
+        assert!((current - 10.).abs() < 0.01);
+    }
+
+    #[test]
+    fn critically_damped_spring_should_be_frame_rate_independent() {
+        let mut coarse = Vec2::ZERO;
+        let mut coarse_velocity = Vec2::ZERO;
+        let mut fine = Vec2::ZERO;
+        let mut fine_velocity = Vec2::ZERO;
+        let target = Vec2::new(10., -5.);
+        for _ in 0..30 {
+            coarse =
+                critically_damped_spring_vec2(coarse, target, &mut coarse_velocity, 0.3, 1. / 30.);
+            for _ in 0..4 {
+                fine =
+                    critically_damped_spring_vec2(fine, target, &mut fine_velocity, 0.3, 1. / 120.);
+            }
+        }
+        assert!(coarse.distance(fine) < 0.05);
+    }
+
+    #[test]
+    fn closest_points_on_segments_should_work() {
+        let (a, b) = closest_points_on_segments(
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 3.),
+        );
+        assert_eq!((a, b), (Vec2::new(1., 0.), Vec2::new(1., 1.)));
+
+        let (a, b) = closest_points_on_segments(
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(3., 1.),
+            Vec2::new(5., 1.),
+        );
+        assert_eq!((a, b), (Vec2::new(2., 0.), Vec2::new(3., 1.)));
+        assert_eq!(
+            closest_point_on_segment(Vec2::new(-1., 4.), Vec2::ZERO, Vec2::Y),
+            Vec2::Y
+        );
+    }
+
+    #[test]
+    fn angle_utilities_should_work() {
+        use consts::{FRAC_PI_2, PI};
+        let near = |a: Real, b: Real| (a - b).abs() < 1e-5;
+        assert!(near(wrap_angle(3. * PI / 2.), -FRAC_PI_2));
+        assert!(near(wrap_angle(-5. * PI / 2.), -FRAC_PI_2));
+        assert!(near(wrap_angle(PI), -PI));
+        assert!(near(shortest_angle_between(3., -3.), 2. * PI - 6.));
+        assert!(near(shortest_angle_between(-FRAC_PI_2, FRAC_PI_2), -PI));
+
+        // arc crossing ±π
+        assert!(near(clamp_angle(PI, 2.5, -2.5), -PI));
+        assert!(near(clamp_angle(0.1, 2.5, -2.5), 2.5));
+        assert!(near(clamp_angle(-0.1, 2.5, -2.5), -2.5));
+        assert!(near(clamp_angle(0.5, 0., 1.), 0.5));
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::{body::Body, math::Real, shape::ShapeKind, vec2::Vec2};
+
+/// 自定义碰撞函数的检测结果
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContactManifold {
+    /// 从 A 指向 B 的碰撞法线，单位向量
+    pub normal: Vec2,
+    /// 两个物体的侵入量
+    pub penetration: Real,
+    /// 世界坐标系中的碰撞点
+    pub points: Vec<Vec2>,
+}
+
+/// 自定义碰撞函数，检测物体 A 和 B 是否碰撞，不碰撞时返回 `None`
+pub type CollideFn = fn(&Body, &Body) -> Option<ContactManifold>;
+
+/// 窄检测的碰撞函数表，按形状对查找碰撞函数。
+/// 注册过的形状对使用注册的函数，其余形状对使用内置的检测
+#[derive(Clone, Default)]
+pub struct NarrowPhase {
+    // 碰撞函数，以及调用时是否需要交换两个物体
+    handlers: HashMap<(ShapeKind, ShapeKind), (CollideFn, bool)>,
+}
+
+impl NarrowPhase {
+    pub fn new() -> NarrowPhase {
+        NarrowPhase::default()
+    }
+
+    /// 注册形状对 `(a, b)` 的碰撞函数，`collide` 的第一个物体的形状为 `a`。
+    /// 同时用于 `(b, a)`，此时两个物体会被交换，法线也随之反向。
+    /// 可以覆盖内置的检测，后注册的函数覆盖先注册的
+    pub fn register(&mut self, a: ShapeKind, b: ShapeKind, collide: CollideFn) {
+        self.handlers.insert((a, b), (collide, false));
+        if a != b {
+            self.handlers.insert((b, a), (collide, true));
+        }
+    }
+
+    /// 移除形状对 `(a, b)` 和 `(b, a)` 的碰撞函数
+    pub fn unregister(&mut self, a: ShapeKind, b: ShapeKind) {
+        self.handlers.remove(&(a, b));
+        self.handlers.remove(&(b, a));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    // 用注册的函数检测两个物体，没有对应的函数时返回 `None`
+    pub(crate) fn collide(&self, a: &Body, b: &Body) -> Option<Option<ContactManifold>> {
+        if self.handlers.is_empty() {
+            return None;
+        }
+        let &(collide, flipped) = self.handlers.get(&(a.shape_kind(), b.shape_kind()))?;
+        if !flipped {
+            return Some(collide(a, b));
+        }
+        Some(collide(b, a).map(|mut m| {
+            m.normal = -m.normal;
+            m
+        }))
+    }
+}
@@ -0,0 +1,303 @@
+//! 物理状态的网络同步工具，需要开启 `replication` feature
+//!
+//! 服务端用 `StateEncoder` 把物体的位置和速度按配置的精度量化，只发送相对于上一个包发生
+//! 变化的物体，并且只发送量化值的差量；客户端用 `StateDecoder` 还原状态，再平滑地应用到
+//! 本地的 world 上。包需要按顺序可靠地送达，丢包之后可以发送一个完整包重新同步。
+
+use crate::{math::Real, vec2::Vec2, world::World};
+
+/// 量化精度，数值越小精度越高，包也越大
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct QuantizationConfig {
+    /// 位置的最小单位
+    pub position_precision: Real,
+    /// 速度的最小单位
+    pub velocity_precision: Real,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        QuantizationConfig {
+            position_precision: 0.01,
+            velocity_precision: 0.01,
+        }
+    }
+}
+
+// 量化后的状态：位置 x、y，速度 x、y
+type Quantized = [i32; 4];
+
+/// 一个物体的状态差量
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BodyDelta {
+    /// 物体在 world 中的下标
+    pub body: usize,
+    /// 量化后的位置和速度相对上一个包的差量
+    pub delta: [i32; 4],
+}
+
+/// 一个状态包
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct StatePacket {
+    /// 完整包的差量相对于全 0 的状态，接收方收到后会丢弃之前的状态
+    pub full: bool,
+    pub bodies: Vec<BodyDelta>,
+}
+
+impl StatePacket {
+    /// 序列化为字节，整数使用 zigzag + varint 编码，差量越小占用的字节越少
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.full as u8];
+        write_varint(&mut bytes, self.bodies.len() as u64);
+        for body in &self.bodies {
+            write_varint(&mut bytes, body.body as u64);
+            for d in body.delta {
+                write_varint(&mut bytes, zigzag(d));
+            }
+        }
+        bytes
+    }
+
+    /// 从字节反序列化，数据不完整或格式错误时返回 `None`
+    pub fn from_bytes(bytes: &[u8]) -> Option<StatePacket> {
+        let (&flag, mut rest) = bytes.split_first()?;
+        let count = read_varint(&mut rest)?;
+        let mut bodies = Vec::new();
+        for _ in 0..count {
+            let body = read_varint(&mut rest)? as usize;
+            let mut delta = [0; 4];
+            for d in &mut delta {
+                *d = unzigzag(read_varint(&mut rest)?);
+            }
+            bodies.push(BodyDelta { body, delta });
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(StatePacket {
+            full: flag != 0,
+            bodies,
+        })
+    }
+}
+
+/// 服务端的状态编码器
+pub struct StateEncoder {
+    config: QuantizationConfig,
+    last: Vec<Quantized>,
+}
+
+impl StateEncoder {
+    pub fn new(config: QuantizationConfig) -> StateEncoder {
+        StateEncoder {
+            config,
+            last: vec![],
+        }
+    }
+
+    /// 生成状态包，只包含量化状态相对上一个包发生变化的物体
+    /// `full` 为 true 时生成完整包，包含所有物体
+    pub fn encode(&mut self, world: &World, full: bool) -> StatePacket {
+        if full {
+            self.last.clear();
+        }
+        let mut packet = StatePacket {
+            full,
+            bodies: vec![],
+        };
+        for (i, body) in world.get_bodies().iter().enumerate() {
+            let body = body.borrow();
+            let state = quantize(&self.config, body.position(), body.velocity());
+            if i >= self.last.len() {
+                self.last.push([0; 4]);
+            }
+            let last = self.last[i];
+            if full || state != last {
+                let delta = [0, 1, 2, 3].map(|k| state[k].wrapping_sub(last[k]));
+                packet.bodies.push(BodyDelta { body: i, delta });
+                self.last[i] = state;
+            }
+        }
+        packet
+    }
+}
+
+/// 客户端的状态解码器
+pub struct StateDecoder {
+    config: QuantizationConfig,
+    states: Vec<Quantized>,
+    // 平滑系数，每次 apply 时位置向目标靠近的比例
+    smoothing: Real,
+}
+
+impl StateDecoder {
+    /// * `smoothing`: `(0, 1]` 之间，每次 `apply` 时位置向目标靠近的比例，1 表示直接设置
+    pub fn new(config: QuantizationConfig, smoothing: Real) -> StateDecoder {
+        StateDecoder {
+            config,
+            states: vec![],
+            smoothing: smoothing.clamp(0., 1.),
+        }
+    }
+
+    /// 接收一个状态包，更新各个物体的目标状态
+    pub fn receive(&mut self, packet: &StatePacket) {
+        if packet.full {
+            self.states.clear();
+        }
+        for body in &packet.bodies {
+            if body.body >= self.states.len() {
+                self.states.resize(body.body + 1, [0; 4]);
+            }
+            let state = &mut self.states[body.body];
+            for (s, d) in state.iter_mut().zip(body.delta) {
+                *s = s.wrapping_add(d);
+            }
+        }
+    }
+
+    /// 物体的目标位置和速度，没有收到过该物体的状态时返回 `None`
+    pub fn target(&self, body: usize) -> Option<(Vec2, Vec2)> {
+        self.states.get(body).map(|state| dequantize(&self.config, state))
+    }
+
+    /// 把目标状态平滑地应用到本地的 world 上，通常每帧调用一次
+    /// 位置按平滑系数向目标靠近，速度直接设置为目标速度
+    pub fn apply(&self, world: &mut World) {
+        for (i, body) in world.get_bodies().iter().enumerate() {
+            let Some((position, velocity)) = self.target(i) else {
+                break;
+            };
+            let mut body = body.borrow_mut();
+            let smoothed = body.position().lerp(position, self.smoothing);
+            body.set_position(smoothed);
+            body.set_velocity(velocity);
+        }
+    }
+}
+
+fn quantize(config: &QuantizationConfig, position: Vec2, velocity: Vec2) -> Quantized {
+    let p = (position / config.position_precision).round();
+    let v = (velocity / config.velocity_precision).round();
+    [p.x as i32, p.y as i32, v.x as i32, v.y as i32]
+}
+
+fn dequantize(config: &QuantizationConfig, state: &Quantized) -> (Vec2, Vec2) {
+    (
+        Vec2::new(state[0] as Real, state[1] as Real) * config.position_precision,
+        Vec2::new(state[2] as Real, state[3] as Real) * config.velocity_precision,
+    )
+}
+
+fn zigzag(v: i32) -> u64 {
+    ((v << 1) ^ (v >> 31)) as u32 as u64
+}
+
+fn unzigzag(v: u64) -> i32 {
+    let v = v as u32;
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        bytes.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut result = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&b, rest) = bytes.split_first()?;
+        *bytes = rest;
+        result |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        body::Body,
+        shape::{Circle, AABB},
+    };
+
+    fn scene() -> World {
+        let mut world = World::new(1. / 60., 10, 1.);
+        let mut ground = Body::new_aabb(
+            AABB::from_half_extents(Vec2::new(100., 5.)),
+            Vec2::new(0., 50.),
+            0.5,
+        );
+        ground.make_static();
+        world.add_body(ground);
+        for i in 0..3 {
+            let position = Vec2::new(i as Real * 3., 40.);
+            world.add_body(Body::new_circle(Circle::new(1.), position, 0.5));
+        }
+        world
+    }
+
+    /// Test cases for:
+    /// * StateEncoder::encode(&World, bool)
+    /// * StateDecoder::receive(&StatePacket)
+    /// * StateDecoder::apply(&mut World)
+    /// * StatePacket::to_bytes()
+    /// * StatePacket::from_bytes(&[u8])
+    #[test]
+    fn replicated_state_should_follow_server() {
+        let config = QuantizationConfig::default();
+        let mut server = scene();
+        let mut client = scene();
+        for body in client.get_bodies() {
+            body.borrow_mut().set_position(Vec2::ZERO);
+        }
+        let mut encoder = StateEncoder::new(config);
+        let mut decoder = StateDecoder::new(config, 1.);
+
+        for step in 0..60 {
+            server.step();
+            let packet = encoder.encode(&server, step == 0);
+            // 静止的地面只在完整包中发送
+            assert_eq!(packet.bodies.len(), if step == 0 { 4 } else { 3 });
+            let bytes = packet.to_bytes();
+            assert_eq!(StatePacket::from_bytes(&bytes).as_ref(), Some(&packet));
+            decoder.receive(&packet);
+            decoder.apply(&mut client);
+            for (s, c) in server.get_bodies().iter().zip(client.get_bodies()) {
+                let (s, c) = (s.borrow(), c.borrow());
+                assert!(s.position().distance(c.position()) <= config.position_precision);
+                assert!(s.velocity().distance(c.velocity()) <= config.velocity_precision);
+            }
+        }
+        // 差量包比完整包小，不完整的数据无法解析
+        let bytes = encoder.encode(&server, true).to_bytes();
+        server.step();
+        assert!(encoder.encode(&server, false).to_bytes().len() < bytes.len());
+        assert_eq!(StatePacket::from_bytes(&bytes[..bytes.len() - 1]), None);
+    }
+
+    /// Test cases for:
+    /// * StateDecoder::apply(&mut World)
+    #[test]
+    fn decoder_should_smooth_positions() {
+        let config = QuantizationConfig::default();
+        let server = scene();
+        let mut client = scene();
+        client.get_bodies()[1].borrow_mut().set_position(Vec2::ZERO);
+        let mut decoder = StateDecoder::new(config, 0.5);
+        assert_eq!(decoder.target(1), None);
+        decoder.receive(&StateEncoder::new(config).encode(&server, true));
+        assert_eq!(decoder.target(1), Some((Vec2::new(0., 40.), Vec2::ZERO)));
+        let position = |world: &World| world.get_bodies()[1].borrow().position();
+        decoder.apply(&mut client);
+        assert_eq!(position(&client), Vec2::new(0., 20.));
+        decoder.apply(&mut client);
+        assert_eq!(position(&client), Vec2::new(0., 30.));
+    }
+}
@@ -0,0 +1,160 @@
+use std::ops;
+
+use crate::{mat2::Mat2, math::Real, vec2::Vec2};
+
+/// 2d rotation, stored as the sine and cosine of the angle so that rotating a vector
+/// does not need any trigonometric function
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rot {
+    /// sine of the angle
+    pub s: Real,
+    /// cosine of the angle
+    pub c: Real,
+}
+
+impl Rot {
+    pub const IDENTITY: Self = Self { s: 0.0, c: 1.0 };
+
+    /// Creates a rotation by the given counter-clockwise `angle` (in radians).
+    #[inline]
+    pub fn from_angle(angle: Real) -> Rot {
+        let (s, c) = angle.sin_cos();
+        Rot { s, c }
+    }
+
+    /// Returns the angle (in radians) of the rotation in the range `[-π, +π]`.
+    #[inline]
+    pub fn angle(self) -> Real {
+        self.s.atan2(self.c)
+    }
+
+    /// Returns the rotation in the opposite direction.
+    #[must_use]
+    #[inline]
+    pub fn inverse(self) -> Rot {
+        Rot {
+            s: -self.s,
+            c: self.c,
+        }
+    }
+
+    /// Rotates `rhs` by the inverse of `self`.
+    #[inline]
+    pub fn inv_mul_vec2(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(
+            self.c * rhs.x + self.s * rhs.y,
+            self.c * rhs.y - self.s * rhs.x,
+        )
+    }
+
+    /// Returns `self` renormalized, used to remove the drift accumulated by repeated
+    /// composition. Returns [`Rot::IDENTITY`] if `self` is degenerate.
+    #[must_use]
+    #[inline]
+    pub fn normalize(self) -> Rot {
+        match Vec2::new(self.c, self.s).try_normalize() {
+            Some(v) => Rot { s: v.y, c: v.x },
+            None => Rot::IDENTITY,
+        }
+    }
+
+    /// Returns the x axis of the rotated frame.
+    #[inline]
+    pub fn x_axis(self) -> Vec2 {
+        Vec2::new(self.c, self.s)
+    }
+
+    /// Returns the y axis of the rotated frame.
+    #[inline]
+    pub fn y_axis(self) -> Vec2 {
+        Vec2::new(-self.s, self.c)
+    }
+
+    /// Returns the matrix form of the rotation.
+    #[inline]
+    pub fn to_mat2(self) -> Mat2 {
+        Mat2::from_cols(self.x_axis(), self.y_axis())
+    }
+}
+
+impl ops::Mul<Vec2> for Rot {
+    type Output = Vec2;
+
+    #[inline]
+    fn mul(self, rhs: Vec2) -> Self::Output {
+        Vec2::new(
+            self.c * rhs.x - self.s * rhs.y,
+            self.s * rhs.x + self.c * rhs.y,
+        )
+    }
+}
+
+/// Composition, `a * b` rotates by `b` first and then by `a`.
+impl ops::Mul<Rot> for Rot {
+    type Output = Rot;
+
+    #[inline]
+    fn mul(self, rhs: Rot) -> Self::Output {
+        Rot {
+            s: self.s * rhs.c + self.c * rhs.s,
+            c: self.c * rhs.c - self.s * rhs.s,
+        }
+    }
+}
+
+impl ops::MulAssign<Rot> for Rot {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Rot) {
+        *self = *self * rhs;
+    }
+}
+
+impl Default for Rot {
+    fn default() -> Self {
+        Rot::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn assert_vec2_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    /// Test cases for:
+    /// * Rot::from_angle(Real)
+    /// * Rot * Vec2
+    /// * Rot.inv_mul_vec2(Vec2)
+    #[test]
+    fn rot_mul_vec2_should_work() {
+        let rot = Rot::from_angle(FRAC_PI_2);
+        assert_vec2_near(rot * Vec2::X, Vec2::Y);
+        assert_vec2_near(rot.inv_mul_vec2(Vec2::Y), Vec2::X);
+        assert_vec2_near(rot.inverse() * Vec2::Y, Vec2::X);
+
+        let v = Vec2::new(3., -2.);
+        let rot = Rot::from_angle(0.7);
+        assert_vec2_near(rot * v, rot.to_mat2() * v);
+    }
+
+    /// Test cases for:
+    /// * Rot * Rot
+    /// * Rot.angle()
+    /// * Rot.normalize()
+    #[test]
+    fn rot_composition_should_work() {
+        let rot = Rot::from_angle(FRAC_PI_4) * Rot::from_angle(FRAC_PI_4);
+        assert!((rot.angle() - FRAC_PI_2).abs() < 1e-5);
+
+        let mut rot = Rot::IDENTITY;
+        for _ in 0..8 {
+            rot *= Rot::from_angle(FRAC_PI_4);
+        }
+        assert_vec2_near(rot.normalize() * Vec2::X, Vec2::X);
+        assert_eq!(Rot { s: 0., c: 0. }.normalize(), Rot::IDENTITY);
+    }
+}
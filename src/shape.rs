@@ -1,46 +1,146 @@
-use crate::vec2::Vec2;
+use std::{any::Any, fmt, rc::Rc};
+
+use crate::{
+    inspect::{Property, PropertyValue},
+    math::{closest_point_on_segment, Real},
+    rot::Rot,
+    transform::Transform2D,
+    vec2::Vec2,
+};
 
 pub trait Shape {
-    fn mass_recip(&self) -> f32 {
+    fn mass_recip(&self) -> Real {
         self.mass().recip()
     }
 
-    fn mass(&self) -> f32;
+    fn mass(&self) -> Real;
+
+    /// 绕质心的转动惯量
+    fn inertia(&self) -> Real;
+
+    /// 面积，线段和折线为 0，复合形状为各个子形状的面积之和
+    fn area(&self) -> Real;
+
+    /// 形心在局部坐标系中的位置
+    fn centroid(&self) -> Vec2;
+
+    /// 周长，线段和折线为长度，复合形状为各个子形状的周长之和
+    fn perimeter(&self) -> Real;
+
+    /// 局部坐标系中的点 `point` 是否在形状内部，线段和折线没有内部，总是返回 false
+    fn contains_point(&self, point: Vec2) -> bool;
+
+    /// 质量为 `mass` 时绕质心的转动惯量，形状不变时转动惯量与质量成正比。
+    /// 没有质量的形状返回 0
+    fn inertia_for_mass(&self, mass: Real) -> Real {
+        let own_mass = self.mass();
+        if own_mass > 0. {
+            self.inertia() * (mass / own_mass)
+        } else {
+            0.
+        }
+    }
+}
+
+/// 自定义形状，由其他 crate 实现，配合 `NarrowPhase::register` 注册的碰撞函数使用。
+/// 没有注册碰撞函数的形状对之间不会发生碰撞
+pub trait CustomShape: Shape {
+    /// 区分不同自定义形状的编号，注册碰撞函数时使用 `ShapeKind::Custom(kind)`
+    fn kind(&self) -> u32;
+
+    /// 用于在碰撞函数中转换回具体的类型
+    fn as_any(&self) -> &dyn Any;
+
+    /// 按 `transform` 放置的形状的包围盒，返回 `(min, max)`
+    fn bounds(&self, transform: &Transform2D) -> (Vec2, Vec2);
+
+    /// 射线检测，参见 `ShapeType::raycast`，默认不会被射线击中
+    fn raycast(&self, _transform: &Transform2D, _ray: Ray) -> Option<Real> {
+        None
+    }
+
+    /// 形状在各个方向上最小的尺寸，用于连续碰撞检测，默认为 0 即不做连续碰撞检测
+    fn min_extent(&self) -> Real {
+        0.
+    }
 }
 
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle {
-    density: f32,
-    radius: f32,
+    density: Real,
+    radius: Real,
 }
 
 impl Circle {
-    pub fn new(radius: f32) -> Circle {
-        Circle {
-            density: 1.0,
-            radius,
-        }
+    /// 密度为 1 的圆
+    pub fn new(radius: Real) -> Circle {
+        Circle::with_density(radius, 1.0)
     }
 
-    pub fn radius(&self) -> f32 {
+    /// 指定密度的圆，质量为 `PI * radius^2 * density`
+    pub fn with_density(radius: Real, density: Real) -> Circle {
+        Circle { density, radius }
+    }
+
+    pub fn radius(&self) -> Real {
         self.radius
     }
+
+    /// # Panics
+    ///
+    /// `radius` 不是正的有限数时 panic
+    pub fn set_radius(&mut self, radius: Real) {
+        assert!(valid_scale_factor(radius), "radius must be positive and finite");
+        self.radius = radius;
+    }
+
+    pub fn density(&self) -> Real {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: Real) {
+        self.density = density;
+    }
 }
 
 impl Shape for Circle {
-    fn mass(&self) -> f32 {
-        std::f32::consts::PI * (self.radius.powf(2.)) * self.density
+    fn mass(&self) -> Real {
+        crate::math::consts::PI * (self.radius.powf(2.)) * self.density
+    }
+
+    fn inertia(&self) -> Real {
+        0.5 * self.mass() * self.radius * self.radius
+    }
+
+    fn area(&self) -> Real {
+        crate::math::consts::PI * self.radius * self.radius
+    }
+
+    fn centroid(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    fn perimeter(&self) -> Real {
+        2. * crate::math::consts::PI * self.radius
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        point.length_squared() <= self.radius * self.radius
     }
 }
 
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AABB {
-    density: f32,
+    density: Real,
     min: Vec2,
     max: Vec2,
 }
 
 impl AABB {
+    /// `min`、`max` 是物体局部坐标系中的坐标，即相对于物体位置的偏移，
+    /// 物体位于 `position` 时 AABB 覆盖 `position + min` 到 `position + max`
     pub fn new(min: Vec2, max: Vec2) -> AABB {
         AABB {
             density: 3.0,
@@ -49,6 +149,16 @@ impl AABB {
         }
     }
 
+    /// 以原点为中心、半边长为 `half_extents` 的 AABB
+    pub fn from_half_extents(half_extents: Vec2) -> AABB {
+        AABB::new(-half_extents, half_extents)
+    }
+
+    /// 以 `center` 为中心、半边长为 `half_extents` 的 AABB
+    pub fn from_center_half_extents(center: Vec2, half_extents: Vec2) -> AABB {
+        AABB::new(center - half_extents, center + half_extents)
+    }
+
     pub fn max(&self) -> Vec2 {
         self.max
     }
@@ -57,20 +167,1872 @@ impl AABB {
         self.min
     }
 
+    /// 中心在物体局部坐标系中的位置
     pub fn center(&self) -> Vec2 {
         (self.min + self.max) / 2.
     }
+
+    /// 半边长
+    pub fn half_extents(&self) -> Vec2 {
+        (self.max - self.min) / 2.
+    }
+
+    /// 点是否在 AABB 内部，边界上的点也算在内部
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+
+    /// 两个 AABB 是否重叠，只有边界接触也算重叠
+    pub fn intersects(&self, other: &AABB) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    /// 同时包含两个 AABB 的最小 AABB，密度与 `self` 相同
+    pub fn union(&self, other: &AABB) -> AABB {
+        AABB {
+            density: self.density,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// 向各个方向扩大 `margin` 的 AABB，`margin` 为负时缩小
+    pub fn expand(&self, margin: Real) -> AABB {
+        AABB {
+            density: self.density,
+            min: self.min - Vec2::splat(margin),
+            max: self.max + Vec2::splat(margin),
+        }
+    }
+
+    /// 以物体的位置（局部坐标系原点）为中心缩放 `factor` 倍
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        self.min *= factor;
+        self.max *= factor;
+    }
+
+    pub fn density(&self) -> Real {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: Real) {
+        self.density = density;
+    }
 }
 
 impl Shape for AABB {
-    fn mass(&self) -> f32 {
+    fn mass(&self) -> Real {
         let area = self.max - self.min;
         area.x * area.y * self.density
     }
+
+    fn inertia(&self) -> Real {
+        let size = self.max - self.min;
+        self.mass() * size.length_squared() / 12.
+    }
+
+    fn area(&self) -> Real {
+        let size = self.max - self.min;
+        size.x * size.y
+    }
+
+    fn centroid(&self) -> Vec2 {
+        self.center()
+    }
+
+    fn perimeter(&self) -> Real {
+        let size = self.max - self.min;
+        2. * (size.x + size.y)
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        self.contains(point)
+    }
+}
+
+/// 射线 `origin + dir * t`，`t` 在 `[0, max_t]` 之间
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray {
+    pub origin: Vec2,
+    pub dir: Vec2,
+    pub max_t: Real,
+}
+
+impl Ray {
+    pub fn new(origin: Vec2, dir: Vec2, max_t: Real) -> Ray {
+        Ray {
+            origin,
+            dir,
+            max_t,
+        }
+    }
+
+    /// 射线上参数为 `t` 的点
+    pub fn at(&self, t: Real) -> Vec2 {
+        self.origin + self.dir * t
+    }
+}
+
+/// 射线检测击中的物体
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RayHit {
+    /// 物体在 world 中的下标
+    pub body: usize,
+    /// 击中点的射线参数，击中点为 `origin + dir * t`
+    pub t: Real,
+    /// 被击中的子形状，复合形状为子形状的下标，折线为边的下标，其他形状为 0
+    pub sub_shape: usize,
+}
+
+/// 由不合法的参数创建形状时 `try_new` 返回的错误
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShapeError {
+    /// 顶点数不在形状允许的范围内
+    VertexCount,
+    /// 坐标不是有限的数
+    NonFinite,
+    /// 面积为 0
+    ZeroArea,
+    /// 多边形有重复的相邻顶点
+    DuplicateVertices,
+    /// 多边形不是凸多边形
+    NotConvex,
+    /// 半径不是正数
+    NonPositiveRadius,
+    /// 线段的两个端点重合
+    ZeroLength,
 }
 
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::VertexCount => write!(f, "wrong number of vertices"),
+            ShapeError::NonFinite => write!(f, "coordinates must be finite"),
+            ShapeError::ZeroArea => write!(f, "shape must have a non-zero area"),
+            ShapeError::DuplicateVertices => {
+                write!(f, "polygon must not have duplicate adjacent vertices")
+            }
+            ShapeError::NotConvex => write!(f, "polygon must be convex"),
+            ShapeError::NonPositiveRadius => write!(f, "radius must be positive"),
+            ShapeError::ZeroLength => write!(f, "segment must have a non-zero length"),
+        }
+    }
+}
+
+/// 凸多边形最多的顶点数
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// 凸多边形，顶点按逆时针顺序保存在物体的局部坐标系中，质心位于原点
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
+    density: Real,
+    count: usize,
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    // 第 i 条边（顶点 i 到顶点 i + 1）的外法线，单位向量
+    normals: [Vec2; MAX_POLYGON_VERTICES],
+}
+
+impl Polygon {
+    /// 由凸多边形的顶点创建多边形，顶点可以是顺时针也可以是逆时针顺序。
+    /// 多边形会被平移到质心位于原点，物体的位置就是多边形的质心
+    ///
+    /// # Panics
+    ///
+    /// 顶点数不在 `3..=MAX_POLYGON_VERTICES` 之间、坐标不是有限的数、面积为 0、
+    /// 有重复的相邻顶点或者不是凸多边形时 panic，任意点集请使用 `Polygon::convex_hull`
+    pub fn new(points: &[Vec2]) -> Polygon {
+        Polygon::try_new(points).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// 与 `new` 相同，参数不合法时返回错误而不是 panic，适合处理来自用户或脚本的数据
+    pub fn try_new(points: &[Vec2]) -> Result<Polygon, ShapeError> {
+        let count = points.len();
+        if !(3..=MAX_POLYGON_VERTICES).contains(&count) {
+            return Err(ShapeError::VertexCount);
+        }
+        if !points.iter().all(|p| p.is_finite()) {
+            return Err(ShapeError::NonFinite);
+        }
+        let mut vertices = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        vertices[..count].copy_from_slice(points);
+        let signed_area = (0..count)
+            .map(|i| vertices[i].perp_dot(vertices[(i + 1) % count]))
+            .sum::<Real>()
+            / 2.;
+        if signed_area == 0. {
+            return Err(ShapeError::ZeroArea);
+        }
+        if signed_area < 0. {
+            vertices[..count].reverse();
+        }
+        let mut polygon = Polygon {
+            density: 1.0,
+            count,
+            vertices,
+            normals: [Vec2::ZERO; MAX_POLYGON_VERTICES],
+        };
+        let centroid = polygon.centroid();
+        for i in 0..count {
+            polygon.vertices[i] -= centroid;
+        }
+        for i in 0..count {
+            let edge = polygon.vertices[(i + 1) % count] - polygon.vertices[i];
+            polygon.normals[i] = -edge
+                .perp()
+                .try_normalize()
+                .ok_or(ShapeError::DuplicateVertices)?;
+        }
+        // 凸多边形的所有顶点都位于每条边的内侧
+        let vertices = polygon.vertices();
+        let tolerance = 0.0001 * vertices.iter().map(|v| v.length()).fold(0., Real::max);
+        for (i, normal) in polygon.normals().iter().enumerate() {
+            if !vertices
+                .iter()
+                .all(|v| normal.dot(*v - vertices[i]) <= tolerance)
+            {
+                return Err(ShapeError::NotConvex);
+            }
+        }
+        Ok(polygon)
+    }
+
+    /// 由任意点集的凸包创建多边形，点的顺序和重复的点都没有要求，凸包上共线的点会被去掉。
+    /// 凸包的顶点超过 `MAX_POLYGON_VERTICES` 个时，依次去掉对面积影响最小的顶点。
+    /// 点数不足或者所有点共线时返回 `None`
+    pub fn convex_hull(points: &[Vec2]) -> Option<Polygon> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        sorted.dedup();
+        if sorted.len() < 3 {
+            return None;
+        }
+
+        // Andrew 单调链算法，得到逆时针顺序的凸包
+        let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() + 1);
+        for &p in &sorted {
+            push_hull_point(&mut hull, 0, p);
+        }
+        // 每条链的最后一个点是下一条链的第一个点
+        hull.pop();
+        let start = hull.len();
+        for &p in sorted.iter().rev() {
+            push_hull_point(&mut hull, start, p);
+        }
+        hull.pop();
+        if hull.len() < 3 {
+            return None;
+        }
+
+        while hull.len() > MAX_POLYGON_VERTICES {
+            let count = hull.len();
+            let removed = (0..count)
+                .map(|i| {
+                    let prev = hull[(i + count - 1) % count];
+                    let next = hull[(i + 1) % count];
+                    (i, (hull[i] - prev).perp_dot(next - hull[i]))
+                })
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .map(|(i, _)| i)?;
+            hull.remove(removed);
+        }
+        Some(Polygon::new(&hull))
+    }
+
+    /// 以原点为中心、半边长为 `half_extents` 的矩形
+    pub fn new_box(half_extents: Vec2) -> Polygon {
+        let Vec2 { x, y } = half_extents;
+        Polygon::new(&[
+            Vec2::new(-x, -y),
+            Vec2::new(x, -y),
+            Vec2::new(x, y),
+            Vec2::new(-x, y),
+        ])
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.count]
+    }
+
+    /// 每条边的外法线，第 i 条边从第 i 个顶点指向第 i + 1 个顶点
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals[..self.count]
+    }
+
+    pub fn density(&self) -> Real {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: Real) {
+        self.density = density;
+    }
+
+    /// 以质心为中心缩放 `factor` 倍，法线不变
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        for v in &mut self.vertices[..self.count] {
+            *v *= factor;
+        }
+    }
+
+}
+
+impl Shape for Polygon {
+    fn mass(&self) -> Real {
+        self.area() * self.density
+    }
+
+    fn inertia(&self) -> Real {
+        // 把多边形分成以原点（质心）为公共顶点的三角形，累加各个三角形的转动惯量
+        let v = self.vertices();
+        let mut inertia = 0.;
+        for i in 0..v.len() {
+            let (p, q) = (v[i], v[(i + 1) % v.len()]);
+            let cross = p.perp_dot(q);
+            inertia += cross * (p.dot(p) + p.dot(q) + q.dot(q));
+        }
+        inertia / 12. * self.density
+    }
+
+    fn area(&self) -> Real {
+        let v = self.vertices();
+        (0..v.len())
+            .map(|i| v[i].perp_dot(v[(i + 1) % v.len()]))
+            .sum::<Real>()
+            / 2.
+    }
+
+    fn centroid(&self) -> Vec2 {
+        let v = self.vertices();
+        let mut centroid = Vec2::ZERO;
+        for i in 0..v.len() {
+            let (p, q) = (v[i], v[(i + 1) % v.len()]);
+            centroid += (p + q) * p.perp_dot(q);
+        }
+        centroid / (6. * self.area())
+    }
+
+    fn perimeter(&self) -> Real {
+        closed_length(self.vertices())
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        convex_contains_point(self.vertices(), self.normals(), point)
+    }
+}
+
+/// 没有大小的点，用于大量碎片、粒子之类只需要简单碰撞的物体。
+/// 点不会转动，只与有面积的形状碰撞，与线段、折线和其他点之间不会发生碰撞
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    mass: Real,
+}
+
+impl Point {
+    pub fn new(mass: Real) -> Point {
+        Point { mass }
+    }
+}
+
+impl Shape for Point {
+    fn mass(&self) -> Real {
+        self.mass
+    }
+
+    fn inertia(&self) -> Real {
+        0.
+    }
+
+    fn area(&self) -> Real {
+        0.
+    }
+
+    fn centroid(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    fn perimeter(&self) -> Real {
+        0.
+    }
+
+    fn contains_point(&self, _point: Vec2) -> bool {
+        false
+    }
+}
+
+/// 三角形，地图编辑器导出的地形通常由三角形组成。
+/// 与圆的碰撞使用专门的检测，不需要构造多边形
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle {
+    density: Real,
+    // 逆时针顺序，质心位于原点
+    vertices: [Vec2; 3],
+    // 第 i 条边从第 i 个顶点指向第 i + 1 个顶点
+    normals: [Vec2; 3],
+}
+
+impl Triangle {
+    /// 由三个顶点创建三角形，顶点可以是顺时针也可以是逆时针顺序。
+    /// 三角形会被平移到质心位于原点，物体的位置就是三角形的质心
+    ///
+    /// # Panics
+    ///
+    /// 坐标不是有限的数或者面积为 0 时 panic
+    pub fn new(a: Vec2, b: Vec2, c: Vec2) -> Triangle {
+        Triangle::try_new(a, b, c).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// 与 `new` 相同，参数不合法时返回错误而不是 panic
+    pub fn try_new(a: Vec2, b: Vec2, c: Vec2) -> Result<Triangle, ShapeError> {
+        if !(a.is_finite() && b.is_finite() && c.is_finite()) {
+            return Err(ShapeError::NonFinite);
+        }
+        let signed_area = (b - a).perp_dot(c - a);
+        if signed_area == 0. {
+            return Err(ShapeError::ZeroArea);
+        }
+        let centroid = (a + b + c) / 3.;
+        let vertices = if signed_area > 0. {
+            [a - centroid, b - centroid, c - centroid]
+        } else {
+            [a - centroid, c - centroid, b - centroid]
+        };
+        let mut normals = [Vec2::ZERO; 3];
+        for i in 0..3 {
+            normals[i] = -(vertices[(i + 1) % 3] - vertices[i]).perp().normalize();
+        }
+        Ok(Triangle {
+            density: 1.0,
+            vertices,
+            normals,
+        })
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    /// 每条边的外法线，第 i 条边从第 i 个顶点指向第 i + 1 个顶点
+    pub fn normals(&self) -> &[Vec2] {
+        &self.normals
+    }
+
+    pub fn density(&self) -> Real {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: Real) {
+        self.density = density;
+    }
+
+    /// 以质心为中心缩放 `factor` 倍，法线不变
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        for v in &mut self.vertices {
+            *v *= factor;
+        }
+    }
+}
+
+impl Shape for Triangle {
+    fn mass(&self) -> Real {
+        self.area() * self.density
+    }
+
+    fn inertia(&self) -> Real {
+        // 质心位于原点时 I = m * (|a|^2 + |b|^2 + |c|^2) / 12
+        let sum: Real = self.vertices.iter().map(|v| v.length_squared()).sum();
+        self.mass() * sum / 12.
+    }
+
+    fn area(&self) -> Real {
+        let [a, b, c] = self.vertices;
+        (b - a).perp_dot(c - a) / 2.
+    }
+
+    fn centroid(&self) -> Vec2 {
+        let [a, b, c] = self.vertices;
+        (a + b + c) / 3.
+    }
+
+    fn perimeter(&self) -> Real {
+        closed_length(&self.vertices)
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        convex_contains_point(&self.vertices, &self.normals, point)
+    }
+}
+
+/// 胶囊体，到线段 `(a, b)` 的距离不超过 `radius` 的点的集合，常用作角色的碰撞体。
+/// 线段的中点位于原点
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capsule {
+    density: Real,
+    a: Vec2,
+    b: Vec2,
+    radius: Real,
+}
+
+impl Capsule {
+    /// 由线段的两个端点和半径创建胶囊体，线段会被平移到中点位于原点
+    pub fn new(a: Vec2, b: Vec2, radius: Real) -> Capsule {
+        let center = (a + b) / 2.;
+        Capsule {
+            density: 1.0,
+            a: a - center,
+            b: b - center,
+            radius,
+        }
+    }
+
+    /// 与 `new` 相同，端点不是有限的数或者半径不是正数时返回错误
+    pub fn try_new(a: Vec2, b: Vec2, radius: Real) -> Result<Capsule, ShapeError> {
+        if !a.is_finite() || !b.is_finite() {
+            return Err(ShapeError::NonFinite);
+        }
+        if !(radius > 0. && radius.is_finite()) {
+            return Err(ShapeError::NonPositiveRadius);
+        }
+        Ok(Capsule::new(a, b, radius))
+    }
+
+    /// 竖直的胶囊体，`half_height` 为线段长度的一半
+    pub fn vertical(half_height: Real, radius: Real) -> Capsule {
+        Capsule::new(Vec2::new(0., -half_height), Vec2::new(0., half_height), radius)
+    }
+
+    pub fn a(&self) -> Vec2 {
+        self.a
+    }
+
+    pub fn b(&self) -> Vec2 {
+        self.b
+    }
+
+    pub fn radius(&self) -> Real {
+        self.radius
+    }
+
+    pub fn density(&self) -> Real {
+        self.density
+    }
+
+    pub fn set_density(&mut self, density: Real) {
+        self.density = density;
+    }
+
+    /// 以中点为中心缩放 `factor` 倍，半径同样缩放
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        self.a *= factor;
+        self.b *= factor;
+        self.radius *= factor;
+    }
+}
+
+impl Shape for Capsule {
+    fn mass(&self) -> Real {
+        let length = self.a.distance(self.b);
+        let r = self.radius;
+        (crate::math::consts::PI * r * r + 2. * r * length) * self.density
+    }
+
+    fn inertia(&self) -> Real {
+        // 中间的矩形加上两端的半圆，半圆的转动惯量用平行轴定理移到原点
+        let length = self.a.distance(self.b);
+        let r = self.radius;
+        let circle_mass = crate::math::consts::PI * r * r * self.density;
+        let box_mass = 2. * r * length * self.density;
+        let h = length / 2.;
+        // 半圆质心到直径的距离
+        let lc = 4. * r / (3. * crate::math::consts::PI);
+        let circle_inertia = circle_mass * (0.5 * r * r + h * h + 2. * h * lc);
+        let box_inertia = box_mass * (4. * r * r + length * length) / 12.;
+        circle_inertia + box_inertia
+    }
+
+    fn area(&self) -> Real {
+        let r = self.radius;
+        crate::math::consts::PI * r * r + 2. * r * self.a.distance(self.b)
+    }
+
+    fn centroid(&self) -> Vec2 {
+        (self.a + self.b) / 2.
+    }
+
+    fn perimeter(&self) -> Real {
+        2. * crate::math::consts::PI * self.radius + 2. * self.a.distance(self.b)
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        let closest = closest_point_on_segment(point, self.a, self.b);
+        point.distance_squared(closest) <= self.radius * self.radius
+    }
+}
+
+/// 线段，用于静态的墙和地面，使用线段的物体总是静态的。
+/// 端点位于物体的局部坐标系中，物体的位置设为原点时可以直接使用世界坐标
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    a: Vec2,
+    b: Vec2,
+    // 单面线段的法线，只有位于法线一侧的物体会与线段碰撞
+    normal: Option<Vec2>,
+}
+
+impl Segment {
+    /// 双面的线段
+    pub fn new(a: Vec2, b: Vec2) -> Segment {
+        Segment { a, b, normal: None }
+    }
+
+    /// 与 `new` 相同，端点不是有限的数或者两个端点重合时返回错误
+    pub fn try_new(a: Vec2, b: Vec2) -> Result<Segment, ShapeError> {
+        if !a.is_finite() || !b.is_finite() {
+            return Err(ShapeError::NonFinite);
+        }
+        if a == b {
+            return Err(ShapeError::ZeroLength);
+        }
+        Ok(Segment::new(a, b))
+    }
+
+    /// 单面的线段，只与位于 `normal` 一侧的物体碰撞，从背面穿过线段的物体不会被阻挡
+    pub fn one_sided(a: Vec2, b: Vec2, normal: Vec2) -> Segment {
+        Segment {
+            a,
+            b,
+            normal: normal.try_normalize(),
+        }
+    }
+
+    pub fn a(&self) -> Vec2 {
+        self.a
+    }
+
+    pub fn b(&self) -> Vec2 {
+        self.b
+    }
+
+    /// 单面线段的法线（单位向量），双面线段返回 `None`
+    pub fn normal(&self) -> Option<Vec2> {
+        self.normal
+    }
+
+    /// 以物体的位置为中心缩放 `factor` 倍
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        self.a *= factor;
+        self.b *= factor;
+    }
+}
+
+impl Shape for Segment {
+    fn mass(&self) -> Real {
+        0.
+    }
+
+    fn inertia(&self) -> Real {
+        0.
+    }
+
+    fn area(&self) -> Real {
+        0.
+    }
+
+    fn centroid(&self) -> Vec2 {
+        (self.a + self.b) / 2.
+    }
+
+    fn perimeter(&self) -> Real {
+        self.a.distance(self.b)
+    }
+
+    fn contains_point(&self, _point: Vec2) -> bool {
+        false
+    }
+}
+
+/// 由多条首尾相连的线段组成的折线，用于静态的地形，使用折线的物体总是静态的。
+/// 每条边会参考相邻的边（幽灵顶点）过滤掉属于相邻边的碰撞，
+/// 在多条线段拼成的平地上滚动的物体不会被线段之间的接缝卡住
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chain {
+    // 顶点位于物体的局部坐标系中
+    points: Rc<[Vec2]>,
+    // 最后一个顶点是否与第一个顶点相连
+    looped: bool,
+}
+
+impl Chain {
+    /// 依次连接 `points` 的折线
+    ///
+    /// # Panics
+    ///
+    /// 顶点数少于 2 时 panic
+    pub fn new(points: &[Vec2]) -> Chain {
+        assert!(points.len() >= 2, "chain must have at least 2 points");
+        Chain {
+            points: points.into(),
+            looped: false,
+        }
+    }
+
+    /// 与 `new` 相同，顶点数少于 2 或者坐标不是有限的数时返回错误
+    pub fn try_new(points: &[Vec2]) -> Result<Chain, ShapeError> {
+        if points.len() < 2 {
+            return Err(ShapeError::VertexCount);
+        }
+        if !points.iter().all(|p| p.is_finite()) {
+            return Err(ShapeError::NonFinite);
+        }
+        Ok(Chain::new(points))
+    }
+
+    /// 首尾相连的闭合折线
+    ///
+    /// # Panics
+    ///
+    /// 顶点数少于 3 时 panic
+    pub fn new_loop(points: &[Vec2]) -> Chain {
+        assert!(points.len() >= 3, "chain loop must have at least 3 points");
+        Chain {
+            points: points.into(),
+            looped: true,
+        }
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    pub fn is_loop(&self) -> bool {
+        self.looped
+    }
+
+    /// 以物体的位置为中心缩放 `factor` 倍
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        self.points = self.points.iter().map(|p| *p * factor).collect();
+    }
+
+    /// 边的数量
+    pub fn edge_count(&self) -> usize {
+        if self.looped {
+            self.points.len()
+        } else {
+            self.points.len() - 1
+        }
+    }
+
+    /// 所有的边，每条边为 `(起点, 终点)`
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        (0..self.edge_count()).map(|i| {
+            let (_, a, b, _) = self.ghost_edge(i);
+            (a, b)
+        })
+    }
+
+    // 第 `i` 条边及其两侧的幽灵顶点 `(前一条边的起点, 起点, 终点, 后一条边的终点)`，
+    // 折线的两端没有幽灵顶点
+    pub(crate) fn ghost_edge(&self, i: usize) -> (Option<Vec2>, Vec2, Vec2, Option<Vec2>) {
+        let n = self.points.len();
+        let point = |j: usize| self.points[j % n];
+        let prev = if i > 0 || self.looped {
+            Some(point(i + n - 1))
+        } else {
+            None
+        };
+        let next = if i + 2 < n || self.looped {
+            Some(point(i + 2))
+        } else {
+            None
+        };
+        (prev, point(i), point(i + 1), next)
+    }
+}
+
+impl Shape for Chain {
+    fn mass(&self) -> Real {
+        0.
+    }
+
+    fn inertia(&self) -> Real {
+        0.
+    }
+
+    fn area(&self) -> Real {
+        0.
+    }
+
+    /// 按长度加权的各条边中点的平均位置
+    fn centroid(&self) -> Vec2 {
+        let (sum, length) = self.edges().fold((Vec2::ZERO, 0.), |(sum, length), (a, b)| {
+            let l = a.distance(b);
+            (sum + (a + b) / 2. * l, length + l)
+        });
+        if length > 0. {
+            sum / length
+        } else {
+            self.points[0]
+        }
+    }
+
+    fn perimeter(&self) -> Real {
+        self.edges().map(|(a, b)| a.distance(b)).sum()
+    }
+
+    fn contains_point(&self, _point: Vec2) -> bool {
+        false
+    }
+}
+
+/// 复合形状中的一个子形状，以及它在物体局部坐标系中的位置和旋转
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixture {
+    shape: ShapeType,
+    transform: Transform2D,
+}
+
+impl Fixture {
+    /// 把 `shape` 平移到 `offset`，并旋转 `angle` 弧度。
+    /// AABB 子形状始终与坐标轴对齐，不会随物体转动，需要转动的矩形请使用 `Polygon::new_box`
+    pub fn new(shape: ShapeType, offset: Vec2, angle: Real) -> Fixture {
+        Fixture {
+            shape,
+            transform: Transform2D::new(offset, Rot::from_angle(angle)),
+        }
+    }
+
+    pub fn shape(&self) -> &ShapeType {
+        &self.shape
+    }
+
+    /// 子形状在物体局部坐标系中的变换
+    pub fn transform(&self) -> Transform2D {
+        self.transform
+    }
+}
+
+/// 由多个子形状组成的形状，例如 L 形的箱子、带保险杠的车身。
+/// 子形状之间不会发生碰撞，与其他物体碰撞时分别检测每个子形状
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compound {
+    fixtures: Rc<[Fixture]>,
+    mass: Real,
+    inertia: Real,
+}
+
+impl Compound {
+    /// 由多个子形状创建复合形状。
+    /// 子形状会被整体平移到质心位于原点，物体的位置就是复合形状的质心
+    ///
+    /// # Panics
+    ///
+    /// `fixtures` 为空时 panic
+    pub fn new(fixtures: &[Fixture]) -> Compound {
+        assert!(!fixtures.is_empty(), "compound must have at least 1 fixture");
+        let mut fixtures = fixtures.to_vec();
+        let mass: Real = fixtures.iter().map(|f| f.shape.mass()).sum();
+        // 子形状的质心在复合形状局部坐标系中的位置，AABB 的质心不一定在原点
+        let center = |f: &Fixture| f.transform.transform_point(f.shape.centroid());
+        if mass > 0. {
+            let centroid = fixtures
+                .iter()
+                .fold(Vec2::ZERO, |c, f| c + center(f) * f.shape.mass())
+                / mass;
+            for fixture in &mut fixtures {
+                fixture.transform.translation -= centroid;
+            }
+        }
+        // 平行轴定理，子形状绕自身质心的转动惯量加上质量乘以偏移的平方
+        let inertia = fixtures
+            .iter()
+            .map(|f| f.shape.inertia() + f.shape.mass() * center(f).length_squared())
+            .sum();
+        Compound {
+            fixtures: fixtures.into(),
+            mass,
+            inertia,
+        }
+    }
+
+    pub fn fixtures(&self) -> &[Fixture] {
+        &self.fixtures
+    }
+
+    /// 把所有子形状的密度设为 `density`，并重新计算质量和转动惯量
+    pub fn set_density(&mut self, density: Real) {
+        let mut fixtures = self.fixtures.to_vec();
+        for fixture in &mut fixtures {
+            fixture.shape.set_density(density);
+        }
+        *self = Compound::new(&fixtures);
+    }
+
+    /// 以质心为中心缩放 `factor` 倍，子形状和它们的偏移同样缩放
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        let mut fixtures = self.fixtures.to_vec();
+        for fixture in &mut fixtures {
+            fixture.shape.scale(factor);
+            fixture.transform.translation *= factor;
+        }
+        *self = Compound::new(&fixtures);
+    }
+}
+
+impl Shape for Compound {
+    fn mass(&self) -> Real {
+        self.mass
+    }
+
+    fn inertia(&self) -> Real {
+        self.inertia
+    }
+
+    fn area(&self) -> Real {
+        self.fixtures.iter().map(|f| f.shape.area()).sum()
+    }
+
+    /// 按面积加权的各个子形状形心的平均位置，所有子形状都没有面积时取简单平均
+    fn centroid(&self) -> Vec2 {
+        let area = self.area();
+        let centroid = |f: &Fixture| f.transform.transform_point(f.shape.centroid());
+        if area > 0. {
+            self.fixtures
+                .iter()
+                .fold(Vec2::ZERO, |sum, f| sum + centroid(f) * f.shape.area())
+                / area
+        } else {
+            self.fixtures.iter().fold(Vec2::ZERO, |sum, f| sum + centroid(f))
+                / self.fixtures.len() as Real
+        }
+    }
+
+    fn perimeter(&self) -> Real {
+        self.fixtures.iter().map(|f| f.shape.perimeter()).sum()
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        self.fixtures
+            .iter()
+            .any(|f| f.shape.contains_point(&f.transform, point))
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShapeType {
     Circle(Circle),
     AABB(AABB),
+    Polygon(Polygon),
+    Triangle(Triangle),
+    Capsule(Capsule),
+    Segment(Segment),
+    Chain(Chain),
+    Compound(Compound),
+    Point(Point),
+    /// 自定义形状无法序列化
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Rc<dyn CustomShape>),
+}
+
+/// 形状的种类，用于按形状对注册碰撞函数
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShapeKind {
+    Circle,
+    AABB,
+    Polygon,
+    Triangle,
+    Capsule,
+    Segment,
+    Chain,
+    Compound,
+    Point,
+    Custom(u32),
+}
+
+impl Shape for ShapeType {
+    fn mass(&self) -> Real {
+        match self {
+            ShapeType::Circle(circle) => circle.mass(),
+            ShapeType::AABB(aabb) => aabb.mass(),
+            ShapeType::Polygon(polygon) => polygon.mass(),
+            ShapeType::Triangle(triangle) => triangle.mass(),
+            ShapeType::Capsule(capsule) => capsule.mass(),
+            ShapeType::Segment(segment) => segment.mass(),
+            ShapeType::Chain(chain) => chain.mass(),
+            ShapeType::Compound(compound) => compound.mass(),
+            ShapeType::Point(point) => point.mass(),
+            ShapeType::Custom(custom) => custom.mass(),
+        }
+    }
+
+    fn inertia(&self) -> Real {
+        match self {
+            ShapeType::Circle(circle) => circle.inertia(),
+            ShapeType::AABB(aabb) => aabb.inertia(),
+            ShapeType::Polygon(polygon) => polygon.inertia(),
+            ShapeType::Triangle(triangle) => triangle.inertia(),
+            ShapeType::Capsule(capsule) => capsule.inertia(),
+            ShapeType::Segment(segment) => segment.inertia(),
+            ShapeType::Chain(chain) => chain.inertia(),
+            ShapeType::Compound(compound) => compound.inertia(),
+            ShapeType::Point(point) => point.inertia(),
+            ShapeType::Custom(custom) => custom.inertia(),
+        }
+    }
+
+    fn area(&self) -> Real {
+        match self {
+            ShapeType::Circle(circle) => circle.area(),
+            ShapeType::AABB(aabb) => aabb.area(),
+            ShapeType::Polygon(polygon) => polygon.area(),
+            ShapeType::Triangle(triangle) => triangle.area(),
+            ShapeType::Capsule(capsule) => capsule.area(),
+            ShapeType::Segment(segment) => segment.area(),
+            ShapeType::Chain(chain) => chain.area(),
+            ShapeType::Compound(compound) => compound.area(),
+            ShapeType::Point(point) => point.area(),
+            ShapeType::Custom(custom) => custom.area(),
+        }
+    }
+
+    fn centroid(&self) -> Vec2 {
+        match self {
+            ShapeType::Circle(circle) => circle.centroid(),
+            ShapeType::AABB(aabb) => aabb.centroid(),
+            ShapeType::Polygon(polygon) => polygon.centroid(),
+            ShapeType::Triangle(triangle) => triangle.centroid(),
+            ShapeType::Capsule(capsule) => capsule.centroid(),
+            ShapeType::Segment(segment) => segment.centroid(),
+            ShapeType::Chain(chain) => chain.centroid(),
+            ShapeType::Compound(compound) => compound.centroid(),
+            ShapeType::Point(point) => point.centroid(),
+            ShapeType::Custom(custom) => custom.centroid(),
+        }
+    }
+
+    fn perimeter(&self) -> Real {
+        match self {
+            ShapeType::Circle(circle) => circle.perimeter(),
+            ShapeType::AABB(aabb) => aabb.perimeter(),
+            ShapeType::Polygon(polygon) => polygon.perimeter(),
+            ShapeType::Triangle(triangle) => triangle.perimeter(),
+            ShapeType::Capsule(capsule) => capsule.perimeter(),
+            ShapeType::Segment(segment) => segment.perimeter(),
+            ShapeType::Chain(chain) => chain.perimeter(),
+            ShapeType::Compound(compound) => compound.perimeter(),
+            ShapeType::Point(point) => point.perimeter(),
+            ShapeType::Custom(custom) => custom.perimeter(),
+        }
+    }
+
+    fn contains_point(&self, point: Vec2) -> bool {
+        match self {
+            ShapeType::Circle(circle) => circle.contains_point(point),
+            ShapeType::AABB(aabb) => aabb.contains_point(point),
+            ShapeType::Polygon(polygon) => polygon.contains_point(point),
+            ShapeType::Triangle(triangle) => triangle.contains_point(point),
+            ShapeType::Capsule(capsule) => capsule.contains_point(point),
+            ShapeType::Segment(segment) => segment.contains_point(point),
+            ShapeType::Chain(chain) => chain.contains_point(point),
+            ShapeType::Compound(compound) => compound.contains_point(point),
+            ShapeType::Point(_) => false,
+            ShapeType::Custom(custom) => custom.contains_point(point),
+        }
+    }
+}
+
+impl ShapeType {
+    pub fn kind(&self) -> ShapeKind {
+        match self {
+            ShapeType::Circle(_) => ShapeKind::Circle,
+            ShapeType::AABB(_) => ShapeKind::AABB,
+            ShapeType::Polygon(_) => ShapeKind::Polygon,
+            ShapeType::Triangle(_) => ShapeKind::Triangle,
+            ShapeType::Capsule(_) => ShapeKind::Capsule,
+            ShapeType::Segment(_) => ShapeKind::Segment,
+            ShapeType::Chain(_) => ShapeKind::Chain,
+            ShapeType::Compound(_) => ShapeKind::Compound,
+            ShapeType::Point(_) => ShapeKind::Point,
+            ShapeType::Custom(custom) => ShapeKind::Custom(custom.kind()),
+        }
+    }
+
+    /// 形状的种类和尺寸，供编辑器等外部工具显示，全部是只读的。
+    /// 顶点都在物体的局部坐标系中，复合形状只给出子形状的数量
+    pub fn properties(&self) -> Vec<Property> {
+        let mut properties = vec![Property::new("kind", PropertyValue::Kind(self.kind()), false)];
+        let mut push = |name, value| properties.push(Property::new(name, value, false));
+        match self {
+            ShapeType::Circle(circle) => push("radius", PropertyValue::Real(circle.radius())),
+            ShapeType::AABB(aabb) => {
+                push("min", PropertyValue::Vec2(aabb.min()));
+                push("max", PropertyValue::Vec2(aabb.max()));
+            }
+            ShapeType::Polygon(polygon) => {
+                push("vertices", PropertyValue::Points(polygon.vertices().to_vec()))
+            }
+            ShapeType::Triangle(triangle) => {
+                push("vertices", PropertyValue::Points(triangle.vertices().to_vec()))
+            }
+            ShapeType::Capsule(capsule) => {
+                push("a", PropertyValue::Vec2(capsule.a()));
+                push("b", PropertyValue::Vec2(capsule.b()));
+                push("radius", PropertyValue::Real(capsule.radius()));
+            }
+            ShapeType::Segment(segment) => {
+                push("a", PropertyValue::Vec2(segment.a()));
+                push("b", PropertyValue::Vec2(segment.b()));
+            }
+            ShapeType::Chain(chain) => push("points", PropertyValue::Points(chain.points().to_vec())),
+            ShapeType::Compound(compound) => push(
+                "fixture_count",
+                PropertyValue::Real(compound.fixtures().len() as Real),
+            ),
+            ShapeType::Point(_) | ShapeType::Custom(_) => {}
+        }
+        properties
+    }
+
+    /// 设置形状的密度，线段和折线没有质量，点和自定义形状没有密度，都不受影响
+    pub fn set_density(&mut self, density: Real) {
+        match self {
+            ShapeType::Circle(circle) => circle.set_density(density),
+            ShapeType::AABB(aabb) => aabb.set_density(density),
+            ShapeType::Polygon(polygon) => polygon.set_density(density),
+            ShapeType::Triangle(triangle) => triangle.set_density(density),
+            ShapeType::Capsule(capsule) => capsule.set_density(density),
+            ShapeType::Segment(_) | ShapeType::Chain(_) => {}
+            ShapeType::Point(_) | ShapeType::Custom(_) => {}
+            ShapeType::Compound(compound) => compound.set_density(density),
+        }
+    }
+
+    /// 以物体的位置为中心把形状缩放 `factor` 倍，点和自定义形状不受影响
+    ///
+    /// # Panics
+    ///
+    /// `factor` 不是正的有限数时 panic
+    pub fn scale(&mut self, factor: Real) {
+        assert!(valid_scale_factor(factor), "scale factor must be positive and finite");
+        match self {
+            // 半径为 0 的圆缩放之后半径仍然为 0
+            ShapeType::Circle(circle) => circle.radius *= factor,
+            ShapeType::AABB(aabb) => aabb.scale(factor),
+            ShapeType::Polygon(polygon) => polygon.scale(factor),
+            ShapeType::Triangle(triangle) => triangle.scale(factor),
+            ShapeType::Capsule(capsule) => capsule.scale(factor),
+            ShapeType::Segment(segment) => segment.scale(factor),
+            ShapeType::Chain(chain) => chain.scale(factor),
+            ShapeType::Compound(compound) => compound.scale(factor),
+            ShapeType::Point(_) | ShapeType::Custom(_) => {}
+        }
+    }
+
+    /// 形状在各个方向上最小的尺寸
+    pub fn min_extent(&self) -> Real {
+        match self {
+            ShapeType::Circle(circle) => circle.radius() * 2.,
+            ShapeType::AABB(aabb) => (aabb.max() - aabb.min()).min_element(),
+            ShapeType::Polygon(polygon) => convex_min_extent(polygon.vertices(), polygon.normals()),
+            ShapeType::Triangle(triangle) => {
+                convex_min_extent(triangle.vertices(), triangle.normals())
+            }
+            ShapeType::Capsule(capsule) => capsule.radius() * 2.,
+            ShapeType::Segment(_) | ShapeType::Chain(_) | ShapeType::Point(_) => 0.,
+            ShapeType::Compound(compound) => compound
+                .fixtures()
+                .iter()
+                .map(|f| f.shape().min_extent())
+                .fold(Real::INFINITY, Real::min),
+            ShapeType::Custom(custom) => custom.min_extent(),
+        }
+    }
+
+    /// 按 `transform` 放置的形状的包围盒，返回 `(min, max)`。
+    /// AABB 始终与坐标轴对齐，只使用 `transform` 的平移
+    pub fn bounds(&self, transform: &Transform2D) -> (Vec2, Vec2) {
+        // 一组局部坐标系中的点变换之后的包围盒
+        let points_bounds = |points: &mut dyn Iterator<Item = Vec2>| {
+            points.fold(
+                (Vec2::splat(Real::INFINITY), Vec2::splat(Real::NEG_INFINITY)),
+                |(min, max), p| {
+                    let p = transform.transform_point(p);
+                    (min.min(p), max.max(p))
+                },
+            )
+        };
+        match self {
+            ShapeType::Circle(circle) => {
+                let r = Vec2::splat(circle.radius());
+                (transform.translation - r, transform.translation + r)
+            }
+            ShapeType::AABB(aabb) => {
+                (transform.translation + aabb.min(), transform.translation + aabb.max())
+            }
+            ShapeType::Polygon(polygon) => points_bounds(&mut polygon.vertices().iter().copied()),
+            ShapeType::Triangle(triangle) => points_bounds(&mut triangle.vertices().iter().copied()),
+            ShapeType::Capsule(capsule) => {
+                let (min, max) = points_bounds(&mut [capsule.a(), capsule.b()].into_iter());
+                let r = Vec2::splat(capsule.radius());
+                (min - r, max + r)
+            }
+            ShapeType::Segment(segment) => points_bounds(&mut [segment.a(), segment.b()].into_iter()),
+            ShapeType::Chain(chain) => points_bounds(&mut chain.points().iter().copied()),
+            ShapeType::Compound(compound) => compound.fixtures().iter().fold(
+                (Vec2::splat(Real::INFINITY), Vec2::splat(Real::NEG_INFINITY)),
+                |(min, max), f| {
+                    let (lo, hi) = f.shape().bounds(&(*transform * f.transform()));
+                    (min.min(lo), max.max(hi))
+                },
+            ),
+            ShapeType::Point(_) => (transform.translation, transform.translation),
+            ShapeType::Custom(custom) => custom.bounds(transform),
+        }
+    }
+
+    /// 点 `point` 是否在按 `transform` 放置的形状内部。
+    /// AABB 始终与坐标轴对齐，只使用 `transform` 的平移
+    pub fn contains_point(&self, transform: &Transform2D, point: Vec2) -> bool {
+        if let ShapeType::Compound(compound) = self {
+            return compound
+                .fixtures()
+                .iter()
+                .any(|f| f.shape().contains_point(&(*transform * f.transform()), point));
+        }
+        let local = match self {
+            ShapeType::AABB(_) => point - transform.translation,
+            _ => transform.inverse_transform_point(point),
+        };
+        Shape::contains_point(self, local)
+    }
+
+    /// 射线检测，形状按 `transform` 放置，AABB 只使用 `transform` 的平移。
+    /// 返回射线第一次进入形状时的 `t`，起点在形状内部时返回 0
+    pub fn raycast(&self, transform: &Transform2D, ray: Ray) -> Option<Real> {
+        let Ray { origin, dir, max_t } = ray;
+        let t = match self {
+            ShapeType::Circle(circle) => {
+                ray_cast_circle(transform.translation, circle.radius(), origin, dir)?
+            }
+            ShapeType::AABB(aabb) => {
+                // slab 方法，分别求射线在 x、y 两个方向进入和离开的参数
+                let min = transform.translation + aabb.min();
+                let max = transform.translation + aabb.max();
+                let mut t_min: Real = 0.;
+                let mut t_max = max_t;
+                for (o, d, lo, hi) in [(origin.x, dir.x, min.x, max.x), (origin.y, dir.y, min.y, max.y)] {
+                    if d.abs() < 0.00001 {
+                        if o < lo || o > hi {
+                            return None;
+                        }
+                    } else {
+                        let t1 = (lo - o) / d;
+                        let t2 = (hi - o) / d;
+                        t_min = t_min.max(t1.min(t2));
+                        t_max = t_max.min(t1.max(t2));
+                        if t_min > t_max {
+                            return None;
+                        }
+                    }
+                }
+                t_min
+            }
+            ShapeType::Polygon(_) | ShapeType::Triangle(_) => {
+                let (vertices, normals) = match self {
+                    ShapeType::Polygon(polygon) => (polygon.vertices(), polygon.normals()),
+                    ShapeType::Triangle(triangle) => (triangle.vertices(), triangle.normals()),
+                    _ => unreachable!(),
+                };
+                // 在局部坐标系中依次用每条边所在的半平面裁剪射线
+                let origin = transform.inverse_transform_point(origin);
+                let dir = transform.inverse_transform_vector(dir);
+                let planes = normals.iter().copied().zip(vertices.iter().copied());
+                ray_cast_planes(planes, origin, dir, max_t)?
+            }
+            ShapeType::Capsule(capsule) => {
+                // 两端的圆和中间的矩形分别检测，取最近的一个
+                let origin = transform.inverse_transform_point(origin);
+                let dir = transform.inverse_transform_vector(dir);
+                let (a, b, r) = (capsule.a(), capsule.b(), capsule.radius());
+                let mut t = [a, b]
+                    .into_iter()
+                    .filter_map(|center| ray_cast_circle(center, r, origin, dir))
+                    .fold(Real::INFINITY, Real::min);
+                if let Some(axis) = (b - a).try_normalize() {
+                    let side = axis.perp();
+                    let planes = [(side, a + side * r), (-side, a - side * r), (axis, b), (-axis, a)];
+                    if let Some(t_box) = ray_cast_planes(planes.into_iter(), origin, dir, max_t) {
+                        t = t.min(t_box);
+                    }
+                }
+                t
+            }
+            ShapeType::Segment(segment) => {
+                let origin = transform.inverse_transform_point(origin);
+                let dir = transform.inverse_transform_vector(dir);
+                ray_cast_segment(segment.a(), segment.b(), segment.normal(), origin, dir)?
+            }
+            ShapeType::Chain(_) | ShapeType::Compound(_) => {
+                return self.raycast_sub_shape(transform, ray).map(|(t, _)| t);
+            }
+            // 点没有大小，不会被射线击中
+            ShapeType::Point(_) => return None,
+            ShapeType::Custom(custom) => custom.raycast(transform, ray)?,
+        };
+        if (0. ..=max_t).contains(&t) {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// 与 `raycast` 相同，同时返回被击中的子形状：复合形状为子形状的下标，
+    /// 折线为边的下标，其他形状为 0
+    pub fn raycast_sub_shape(&self, transform: &Transform2D, ray: Ray) -> Option<(Real, usize)> {
+        match self {
+            ShapeType::Chain(chain) => {
+                let origin = transform.inverse_transform_point(ray.origin);
+                let dir = transform.inverse_transform_vector(ray.dir);
+                chain
+                    .edges()
+                    .enumerate()
+                    .filter_map(|(i, (a, b))| Some((ray_cast_segment(a, b, None, origin, dir)?, i)))
+                    .filter(|(t, _)| (0. ..=ray.max_t).contains(t))
+                    .min_by(|x, y| x.0.total_cmp(&y.0))
+            }
+            ShapeType::Compound(compound) => compound
+                .fixtures()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, f)| Some((f.shape().raycast(&(*transform * f.transform()), ray)?, i)))
+                .min_by(|x, y| x.0.total_cmp(&y.0)),
+            _ => Some((self.raycast(transform, ray)?, 0)),
+        }
+    }
+}
+
+// 缩放倍数是否是正的有限数，0 和负数会让形状退化或者翻转
+pub(crate) fn valid_scale_factor(factor: Real) -> bool {
+    factor > 0. && factor.is_finite()
+}
+
+// 凸多边形沿每条边的法线方向的宽度，取最小值
+fn convex_min_extent(vertices: &[Vec2], normals: &[Vec2]) -> Real {
+    normals
+        .iter()
+        .zip(vertices)
+        .map(|(n, p)| vertices.iter().map(|q| n.dot(*p - *q)).fold(0., Real::max))
+        .fold(Real::INFINITY, Real::min)
+}
+
+// 首尾相连的折线的总长度
+fn closed_length(points: &[Vec2]) -> Real {
+    (0..points.len())
+        .map(|i| points[i].distance(points[(i + 1) % points.len()]))
+        .sum()
+}
+
+// 局部坐标系中的点 `point` 是否位于凸多边形所有边的内侧
+fn convex_contains_point(vertices: &[Vec2], normals: &[Vec2], point: Vec2) -> bool {
+    normals.iter().zip(vertices).all(|(n, v)| n.dot(point - *v) <= 0.)
+}
+
+// 把 `p` 加入从 `start` 开始的凸包链，先弹出所有不构成左转的点
+fn push_hull_point(hull: &mut Vec<Vec2>, start: usize, p: Vec2) {
+    while hull.len() >= start + 2 {
+        let a = hull[hull.len() - 2];
+        let b = hull[hull.len() - 1];
+        if (b - a).perp_dot(p - b) > 0. {
+            break;
+        }
+        hull.pop();
+    }
+    hull.push(p);
+}
+
+// 射线与圆的检测，返回射线进入圆时的 `t`，起点在圆内时返回 0
+fn ray_cast_circle(center: Vec2, radius: Real, origin: Vec2, dir: Vec2) -> Option<Real> {
+    let m = origin - center;
+    let c = m.length_squared() - radius * radius;
+    if c <= 0. {
+        return Some(0.);
+    }
+    let a = dir.length_squared();
+    let b = m.dot(dir);
+    let disc = b * b - a * c;
+    if a == 0. || b > 0. || disc < 0. {
+        return None;
+    }
+    Some((-b - disc.sqrt()) / a)
+}
+
+// 射线与线段的检测，`normal` 为单面线段的法线，从背面射入时没有交点
+fn ray_cast_segment(
+    a: Vec2,
+    b: Vec2,
+    normal: Option<Vec2>,
+    origin: Vec2,
+    dir: Vec2,
+) -> Option<Real> {
+    let perp = (b - a).perp();
+    let denominator = perp.dot(dir);
+    // 射线与线段平行，或者从单面线段的背面射入
+    if denominator == 0. || normal.is_some_and(|n| n.dot(dir) >= 0.) {
+        return None;
+    }
+    let t = perp.dot(a - origin) / denominator;
+    let u = (origin + dir * t - a).dot(b - a) / (b - a).length_squared();
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+    Some(t)
+}
+
+// 射线与若干半平面 `n·(p - v) <= 0` 的交集的检测，`planes` 为 `(n, v)`，
+// 返回射线进入交集时的 `t`，起点在交集内时返回 0
+fn ray_cast_planes(
+    planes: impl Iterator<Item = (Vec2, Vec2)>,
+    origin: Vec2,
+    dir: Vec2,
+    max_t: Real,
+) -> Option<Real> {
+    let mut t_min: Real = 0.;
+    let mut t_max = max_t;
+    for (n, v) in planes {
+        let numerator = n.dot(v - origin);
+        let denominator = n.dot(dir);
+        if denominator == 0. {
+            if numerator < 0. {
+                return None;
+            }
+        } else if denominator < 0. {
+            t_min = t_min.max(numerator / denominator);
+        } else {
+            t_max = t_max.min(numerator / denominator);
+        }
+        if t_min > t_max {
+            return None;
+        }
+    }
+    Some(t_min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::Body;
+
+    /// Test cases for:
+    /// * Polygon::new(&[Vec2])
+    #[test]
+    fn polygon_should_accept_convex_vertices() {
+        // 顺时针的顶点被调整为逆时针，共线的顶点是允许的
+        let polygon = Polygon::new(&[
+            Vec2::new(0., 0.),
+            Vec2::new(0., 2.),
+            Vec2::new(2., 2.),
+            Vec2::new(2., 1.),
+            Vec2::new(2., 0.),
+        ]);
+        assert_eq!(polygon.vertices().len(), 5);
+        for normal in polygon.normals() {
+            assert!((normal.length() - 1.).abs() < 0.0001);
+        }
+    }
+
+    /// Test cases for:
+    /// * Polygon::new(&[Vec2])
+    #[test]
+    #[should_panic(expected = "polygon must be convex")]
+    fn polygon_should_reject_concave_vertices() {
+        Polygon::new(&[
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(1., 0.5),
+            Vec2::new(2., 2.),
+            Vec2::new(0., 2.),
+        ]);
+    }
+
+    /// Test cases for:
+    /// * Polygon::new(&[Vec2])
+    #[test]
+    #[should_panic(expected = "polygon must not have duplicate adjacent vertices")]
+    fn polygon_should_reject_duplicate_vertices() {
+        Polygon::new(&[
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(0., 2.),
+        ]);
+    }
+
+    /// Test cases for:
+    /// * Polygon::try_new(&[Vec2])
+    #[test]
+    fn polygon_try_new_should_return_errors() {
+        let square = [
+            Vec2::new(0., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(0., 1.),
+        ];
+        assert!(Polygon::try_new(&square).is_ok());
+        assert_eq!(Polygon::try_new(&square[..2]).err(), Some(ShapeError::VertexCount));
+        assert_eq!(Polygon::try_new(&[Vec2::ZERO; 9]).err(), Some(ShapeError::VertexCount));
+        let line = [Vec2::new(0., 0.), Vec2::new(1., 1.), Vec2::new(2., 2.)];
+        assert_eq!(Polygon::try_new(&line).err(), Some(ShapeError::ZeroArea));
+        let mut nan = square;
+        nan[2].x = Real::NAN;
+        assert_eq!(Polygon::try_new(&nan).err(), Some(ShapeError::NonFinite));
+        let duplicate = [square[0], square[1], square[1], square[3]];
+        assert_eq!(Polygon::try_new(&duplicate).err(), Some(ShapeError::DuplicateVertices));
+        let concave = [square[0], square[1], Vec2::new(0.5, 0.25), square[2], square[3]];
+        assert_eq!(Polygon::try_new(&concave).err(), Some(ShapeError::NotConvex));
+    }
+
+    /// Test cases for:
+    /// * Capsule::try_new(Vec2, Vec2, Real)
+    #[test]
+    fn capsule_try_new_should_return_errors() {
+        let (a, b) = (Vec2::new(0., -1.), Vec2::new(0., 1.));
+        assert!(Capsule::try_new(a, b, 0.5).is_ok());
+        // 两个端点重合的胶囊体就是圆
+        assert!(Capsule::try_new(a, a, 0.5).is_ok());
+        for radius in [0., -1., Real::NAN, Real::INFINITY] {
+            assert_eq!(Capsule::try_new(a, b, radius).err(), Some(ShapeError::NonPositiveRadius));
+        }
+        let nan = Vec2::new(Real::NAN, 0.);
+        assert_eq!(Capsule::try_new(a, nan, 0.5).err(), Some(ShapeError::NonFinite));
+    }
+
+    /// Test cases for:
+    /// * Segment::try_new(Vec2, Vec2)
+    #[test]
+    fn segment_try_new_should_return_errors() {
+        let (a, b) = (Vec2::new(-1., 0.), Vec2::new(1., 0.));
+        assert!(Segment::try_new(a, b).is_ok());
+        assert_eq!(Segment::try_new(a, a).err(), Some(ShapeError::ZeroLength));
+        let infinite = Vec2::new(0., Real::INFINITY);
+        assert_eq!(Segment::try_new(infinite, b).err(), Some(ShapeError::NonFinite));
+    }
+
+    /// Test cases for:
+    /// * Chain::try_new(&[Vec2])
+    #[test]
+    fn chain_try_new_should_return_errors() {
+        let points = [Vec2::new(-1., 0.), Vec2::new(0., 1.), Vec2::new(1., 0.)];
+        assert_eq!(Chain::try_new(&points).unwrap().points().len(), 3);
+        assert_eq!(Chain::try_new(&points[..1]).err(), Some(ShapeError::VertexCount));
+        let nan = [points[0], Vec2::new(Real::NAN, 1.)];
+        assert_eq!(Chain::try_new(&nan).err(), Some(ShapeError::NonFinite));
+    }
+
+    /// Test cases for:
+    /// * Triangle::try_new(Vec2, Vec2, Vec2)
+    #[test]
+    fn triangle_try_new_should_return_errors() {
+        let (a, b) = (Vec2::new(0., 0.), Vec2::new(1., 0.));
+        assert!(Triangle::try_new(a, b, Vec2::new(0., 1.)).is_ok());
+        assert_eq!(Triangle::try_new(a, b, Vec2::new(2., 0.)).err(), Some(ShapeError::ZeroArea));
+        let nan = Vec2::new(0., Real::NAN);
+        assert_eq!(Triangle::try_new(a, b, nan).err(), Some(ShapeError::NonFinite));
+    }
+
+    /// Test cases for:
+    /// * ShapeType::scale(Real)
+    #[test]
+    #[should_panic(expected = "scale factor must be positive and finite")]
+    fn scale_should_reject_non_positive_factor() {
+        ShapeType::Polygon(Polygon::new_box(Vec2::new(1., 1.))).scale(-1.);
+    }
+
+    /// Test cases for:
+    /// * Circle::set_radius(Real)
+    #[test]
+    #[should_panic(expected = "radius must be positive and finite")]
+    fn set_radius_should_reject_non_positive_radius() {
+        Circle::new(1.).set_radius(-1.);
+    }
+
+    /// Test cases for:
+    /// * ShapeType::scale(Real)
+    #[test]
+    fn scale_should_keep_zero_radius_circle() {
+        let mut shape = ShapeType::Circle(Circle::new(0.));
+        shape.scale(2.);
+        assert!(matches!(shape, ShapeType::Circle(circle) if circle.radius() == 0.));
+    }
+
+    /// Test cases for:
+    /// * ShapeType: Serialize + Deserialize
+    #[cfg(feature = "serde")]
+    #[test]
+    fn shape_type_should_round_trip_through_serde() {
+        let mut polygon = Polygon::new_box(Vec2::new(1., 2.));
+        polygon.set_density(3.);
+        let chain = Chain::new_loop(&[Vec2::new(0., 0.), Vec2::new(1., 0.), Vec2::new(0., 1.)]);
+        for shape in [
+            ShapeType::Circle(Circle::new(1.5)),
+            ShapeType::Polygon(polygon),
+            ShapeType::Chain(chain),
+            ShapeType::Compound(l_shape()),
+        ] {
+            let json = serde_json::to_string(&shape).unwrap();
+            let restored: ShapeType = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.kind(), shape.kind());
+            assert_eq!(restored.properties(), shape.properties());
+            assert_eq!(restored.mass(), shape.mass());
+        }
+    }
+
+    fn l_shape() -> Compound {
+        Compound::new(&[
+            Fixture::new(
+                ShapeType::Polygon(Polygon::new_box(Vec2::new(1., 0.5))),
+                Vec2::ZERO,
+                0.,
+            ),
+            Fixture::new(
+                ShapeType::Polygon(Polygon::new_box(Vec2::new(0.5, 1.))),
+                Vec2::new(1.5, -0.5),
+                0.,
+            ),
+        ])
+    }
+
+    /// Test cases for:
+    /// * Compound::new(&[Fixture])
+    /// * Compound::mass()
+    /// * Compound::inertia()
+    /// * Compound::centroid()
+    #[test]
+    fn compound_should_combine_fixture_mass() {
+        let compound = l_shape();
+        assert!((compound.mass() - 4.).abs() < 0.0001);
+        assert!(compound.centroid().length() < 0.0001);
+        // 子形状被平移到质心位于原点
+        let offsets: Vec<Vec2> = compound
+            .fixtures()
+            .iter()
+            .map(|f| f.transform().translation)
+            .collect();
+        assert!(offsets[0].distance(Vec2::new(-0.75, 0.25)) < 0.0001);
+        assert!(offsets[1].distance(Vec2::new(0.75, -0.25)) < 0.0001);
+        // 平行轴定理：每个子形状 2 * 5 / 12 + 2 * 0.625
+        assert!((compound.inertia() - 2. * (10. / 12. + 1.25)).abs() < 0.0001);
+        assert!(compound.contains_point(Vec2::new(-1.5, 0.5)));
+        assert!(!compound.contains_point(Vec2::new(-1.5, -0.5)));
+    }
+
+    /// Test cases for:
+    /// * Shape::inertia_for_mass(Real)
+    #[test]
+    fn inertia_for_mass_should_scale_with_mass() {
+        let circle = Circle::new(2.);
+        let mass = circle.mass();
+        assert!((circle.inertia_for_mass(mass) - circle.inertia()).abs() < 0.0001);
+        assert!((circle.inertia_for_mass(3. * mass) - 3. * circle.inertia()).abs() < 0.001);
+        let polygon = Polygon::new_box(Vec2::new(1., 2.));
+        // 质量为 m 的 2 * 4 矩形：m * (4 + 16) / 12
+        assert!((polygon.inertia_for_mass(6.) - 10.).abs() < 0.0001);
+        assert_eq!(Point::new(0.).inertia_for_mass(5.), 0.);
+
+        let mut body = Body::new_circle(circle, Vec2::new(0., 0.), 0.);
+        body.set_mass(2. * mass);
+        assert!((body.inverse_inertia() * 2. * circle.inertia() - 1.).abs() < 0.0001);
+    }
+
+    /// Test cases for:
+    /// * Circle::with_density(Real, Real)
+    #[test]
+    fn circle_mass_should_scale_with_density() {
+        let light = Circle::new(2.);
+        let heavy = Circle::with_density(2., 3.);
+        assert!((light.mass() - 4. * crate::math::consts::PI).abs() < 0.0001);
+        assert!((heavy.mass() - 3. * light.mass()).abs() < 0.0001);
+        assert!((heavy.inertia() - 3. * light.inertia()).abs() < 0.001);
+        assert_eq!(heavy.area(), light.area());
+    }
+
+    /// Test cases for:
+    /// * Polygon::convex_hull(&[Vec2])
+    #[test]
+    fn convex_hull_should_wrap_point_cloud() {
+        // 正方形的顶点、内部的点、重复的点和边上共线的点
+        let points = [
+            Vec2::new(2., 2.),
+            Vec2::new(1., 1.),
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(0., 2.),
+            Vec2::new(2., 2.),
+            Vec2::new(0.5, 1.5),
+        ];
+        let hull = Polygon::convex_hull(&points).unwrap();
+        assert_eq!(hull.vertices().len(), 4);
+        assert!((hull.area() - 4.).abs() < 0.0001);
+
+        assert!(Polygon::convex_hull(&[Vec2::ZERO, Vec2::new(1., 0.)]).is_none());
+        let line = [Vec2::ZERO, Vec2::new(1., 1.), Vec2::new(2., 2.), Vec2::new(1., 1.)];
+        assert!(Polygon::convex_hull(&line).is_none());
+
+        // 顶点太多时去掉对面积影响最小的顶点
+        let circle: Vec<Vec2> = (0..32)
+            .map(|i| Rot::from_angle(i as Real * crate::math::consts::PI / 16.) * Vec2::new(1., 0.))
+            .collect();
+        let hull = Polygon::convex_hull(&circle).unwrap();
+        assert_eq!(hull.vertices().len(), MAX_POLYGON_VERTICES);
+        assert!(hull.area() > 2.7 && hull.area() < crate::math::consts::PI);
+    }
+
+    /// Test cases for:
+    /// * Triangle::new(Vec2, Vec2, Vec2)
+    #[test]
+    fn triangle_should_be_centered_and_counter_clockwise() {
+        // 顺时针的顶点
+        let triangle = Triangle::new(Vec2::new(0., 3.), Vec2::new(3., 0.), Vec2::new(0., 0.));
+        assert!((triangle.area() - 4.5).abs() < 0.0001);
+        assert!((triangle.mass() - 4.5).abs() < 0.0001);
+        let sum = triangle.vertices().iter().fold(Vec2::ZERO, |sum, v| sum + *v);
+        assert!(sum.length() < 0.0001);
+        let v = triangle.vertices();
+        assert!((v[1] - v[0]).perp_dot(v[2] - v[0]) > 0.);
+        for (i, normal) in triangle.normals().iter().enumerate() {
+            assert!((normal.length() - 1.).abs() < 0.0001);
+            assert!(normal.dot(v[(i + 2) % 3] - v[i]) < 0.);
+        }
+        assert!(triangle.contains_point(Vec2::ZERO));
+        assert!(!triangle.contains_point(Vec2::new(1.5, 1.5)));
+    }
+
+    /// Test cases for:
+    /// * Shape::area()
+    /// * Shape::centroid()
+    /// * Shape::perimeter()
+    /// * Shape::contains_point(Vec2)
+    #[test]
+    fn shapes_should_report_geometry() {
+        let pi = crate::math::consts::PI;
+        let close = |a: Real, b: Real| (a - b).abs() < 0.0001;
+
+        let aabb = AABB::new(Vec2::new(1., 0.), Vec2::new(3., 1.));
+        assert!(close(aabb.area(), 2.) && close(aabb.perimeter(), 6.));
+        assert_eq!(aabb.centroid(), Vec2::new(2., 0.5));
+        assert!(aabb.contains_point(Vec2::new(2.5, 0.5)));
+        assert!(!aabb.contains_point(Vec2::ZERO));
+
+        // 直角梯形的形心在 (14 / 9, 8 / 9)，顶点被平移到形心位于原点
+        let polygon = Polygon::new(&[
+            Vec2::new(0., 0.),
+            Vec2::new(4., 0.),
+            Vec2::new(2., 2.),
+            Vec2::new(0., 2.),
+        ]);
+        let offset = Vec2::new(14. / 9., 8. / 9.);
+        assert!(close(polygon.area(), 6.));
+        assert!(polygon.centroid().length() < 0.0001);
+        assert!(polygon.vertices()[0].distance(-offset) < 0.0001);
+        assert!(close(polygon.perimeter(), 8. + (8. as Real).sqrt()));
+        assert!(polygon.contains_point(Vec2::new(1., 1.) - offset));
+        assert!(!polygon.contains_point(Vec2::new(3.5, 1.5) - offset));
+
+        let capsule = Capsule::new(Vec2::new(-1., 0.), Vec2::new(1., 0.), 0.5);
+        assert!(close(capsule.area(), pi * 0.25 + 2.));
+        assert!(close(capsule.perimeter(), pi + 4.));
+        assert!(capsule.contains_point(Vec2::new(1.4, 0.)));
+        assert!(!capsule.contains_point(Vec2::new(1., 0.6)));
+
+        let segment = Segment::new(Vec2::new(0., 0.), Vec2::new(3., 4.));
+        assert!(close(segment.area(), 0.) && close(segment.perimeter(), 5.));
+        assert_eq!(segment.centroid(), Vec2::new(1.5, 2.));
+        assert!(!segment.contains_point(Vec2::new(1.5, 2.)));
+
+        let chain = Chain::new(&[Vec2::new(0., 0.), Vec2::new(2., 0.), Vec2::new(2., 1.)]);
+        assert!(close(chain.perimeter(), 3.));
+        assert!(chain.centroid().distance(Vec2::new(4. / 3., 1. / 6.)) < 0.0001);
+
+        // 物体坐标系中的查询考虑物体的位置和旋转
+        let shape = ShapeType::Polygon(Polygon::new_box(Vec2::new(2., 0.5)));
+        let transform = Transform2D::new(Vec2::new(10., 0.), Rot::from_angle(pi / 2.));
+        assert!(shape.contains_point(&transform, Vec2::new(10., 1.5)));
+        assert!(!shape.contains_point(&transform, Vec2::new(11.5, 0.)));
+    }
+
+    /// Test cases for:
+    /// * AABB::from_center_half_extents(Vec2, Vec2)
+    /// * AABB::contains(Vec2)
+    /// * AABB::intersects(&AABB)
+    /// * AABB::union(&AABB)
+    /// * AABB::expand(Real)
+    #[test]
+    fn aabb_should_support_bounds_operations() {
+        let a = AABB::from_center_half_extents(Vec2::new(1., 1.), Vec2::new(1., 1.));
+        assert_eq!((a.min(), a.max()), (Vec2::ZERO, Vec2::new(2., 2.)));
+        assert!(a.contains(Vec2::new(2., 0.)));
+        assert!(!a.contains(Vec2::new(2.1, 1.)));
+
+        // 只有边界接触也算重叠
+        let b = AABB::new(Vec2::new(2., 2.), Vec2::new(3., 5.));
+        let c = AABB::new(Vec2::new(2.1, 0.), Vec2::new(3., 1.));
+        assert!(a.intersects(&b) && b.intersects(&a));
+        assert!(!a.intersects(&c) && !c.intersects(&a));
+
+        let union = a.union(&c);
+        assert_eq!((union.min(), union.max()), (Vec2::ZERO, Vec2::new(3., 2.)));
+        let expanded = a.expand(0.5);
+        assert_eq!(expanded.min(), Vec2::new(-0.5, -0.5));
+        assert_eq!(expanded.max(), Vec2::new(2.5, 2.5));
+        assert!(expanded.intersects(&c));
+        assert_eq!(a.expand(-0.5).half_extents(), Vec2::new(0.5, 0.5));
+    }
 }
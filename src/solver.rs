@@ -0,0 +1,89 @@
+use crate::{math::Real, vec2::Vec2};
+
+/// 求解器的配置
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SolverConfig {
+    /// 求解接触时允许的最大质量比
+    /// 两个非静态物体的质量比超过该值时，求解时会把较重物体的质量按比例调小，
+    /// 牺牲一点真实性换取重物压在轻物上时的稳定，`None` 表示不限制
+    pub max_mass_ratio: Option<Real>,
+    /// 岛（互相接触的一组非静态物体）中最重与最轻物体的质量比超过该值时，
+    /// 对该岛做额外的迭代
+    pub high_mass_ratio: Real,
+    /// 高质量比的岛额外的迭代次数，0 表示不做额外迭代
+    pub extra_iterations: u32,
+    /// 扭转摩擦，接触点的摩擦力考虑物体的转动，使旋转的圆可以把转动转化为平动。
+    /// 值为滚动阻力系数（长度单位），接触时阻碍两个物体相对转动的角冲量不超过
+    /// 法向冲量乘以该系数，使滚动的圆最终停下来。`None` 表示摩擦力不考虑转动
+    pub torsional_friction: Option<Real>,
+    /// 圆与圆之间接触的合并角度（弧度）。密集堆积的圆会产生大量接触，
+    /// 同一个非静态物体上法线夹角小于该值的圆-圆接触只保留穿透最深的一个，
+    /// 使每个物体参与求解的接触数量有上限。`None` 表示不合并
+    pub circle_contact_reduction: Option<Real>,
+    /// 是否在更新位置之后做位置修正，把互相侵入的物体沿法线推开一部分侵入量，
+    /// 推开的速度受 `World::set_max_depenetration_speed` 限制。默认关闭，只用冲量分离物体
+    pub positional_correction: bool,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            max_mass_ratio: None,
+            high_mass_ratio: 10.,
+            extra_iterations: 0,
+            torsional_friction: None,
+            circle_contact_reduction: None,
+            positional_correction: false,
+        }
+    }
+}
+
+/// 速度和位置的积分方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum IntegrationScheme {
+    /// 求解接触之前用力更新半个 dt 的速度，更新位置之后再更新另外半个 dt，
+    /// 即 `v += a * dt / 2; x += v * dt; v += a * dt / 2`。
+    /// 加速度不变时与速度 Verlet 相同，抛体运动的能量没有漂移
+    #[default]
+    HalfStep,
+    /// 半隐式欧拉法，求解接触之前用力更新完整的 dt，即 `v += a * dt; x += v * dt`。
+    /// 每次 step 结束时的速度就是求解得到的速度，但恒力作用下能量会随时间漂移
+    SemiImplicitEuler,
+}
+
+impl IntegrationScheme {
+    /// 更新位置之前和之后分别使用的 dt 比例
+    pub(crate) fn kick_fractions(self) -> (Real, Real) {
+        match self {
+            IntegrationScheme::HalfStep => (0.5, 0.5),
+            IntegrationScheme::SemiImplicitEuler => (1., 0.),
+        }
+    }
+}
+
+/// 一个接触在迭代求解过程中的状态
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ContactImpulse {
+    /// 物体 A 在 world 中的下标
+    pub a: usize,
+    /// 物体 B 在 world 中的下标
+    pub b: usize,
+    /// 碰撞法线，从 A 指向 B
+    pub normal: Vec2,
+    /// 本次 step 到目前为止累积的法向冲量
+    pub normal_impulse: Real,
+    /// 本次 step 到目前为止累积的切线冲量
+    pub tangent_impulse: Vec2,
+}
+
+/// 一次冲量迭代结束时求解器的状态，用于演示或调试序列冲量法的收敛过程
+#[derive(Clone, PartialEq, Debug)]
+pub struct SolverIteration {
+    /// 迭代的序号，从 0 开始，高质量比的岛的额外迭代接在普通迭代之后
+    pub iteration: usize,
+    /// 所有没有烘焙的物体在 world 中的下标和当前的速度，按下标从小到大排列。
+    /// 烘焙过的静态物体不会移动，不包含在内
+    pub velocities: Vec<(usize, Vec2)>,
+    /// 所有参与求解的接触
+    pub contacts: Vec<ContactImpulse>,
+}
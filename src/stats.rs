@@ -0,0 +1,79 @@
+use crate::{math::Real, solver::IntegrationScheme};
+
+/// 一个岛（通过接触或约束互相关联的一组非静态物体）在 step 结束时的残差
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct IslandStats {
+    /// 岛中物体在 world 中的下标，从小到大排列
+    pub bodies: Vec<usize>,
+    /// 迭代结束后，接触点沿法线方向剩余的最大接近速度
+    pub max_normal_velocity: Real,
+    /// 迭代结束后，关节和约束剩余的最大位置误差
+    pub max_joint_violation: Real,
+}
+
+/// 上一次 step 的统计信息
+/// 残差较大时说明需要更多的迭代次数，或者场景中的质量比过于悬殊
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct StepStats {
+    /// 参与求解的接触数量
+    pub contact_count: usize,
+    /// 所有岛的统计信息，只包含有接触或约束的岛
+    pub islands: Vec<IslandStats>,
+}
+
+impl StepStats {
+    /// 所有岛中最大的法向速度残差
+    pub fn max_normal_velocity(&self) -> Real {
+        self.islands
+            .iter()
+            .fold(0., |max, island| island.max_normal_velocity.max(max))
+    }
+
+    /// 所有岛中最大的约束位置误差
+    pub fn max_joint_violation(&self) -> Real {
+        self.islands
+            .iter()
+            .fold(0., |max, island| island.max_joint_violation.max(max))
+    }
+}
+
+/// `World::validate_energy_drift` 的结果
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EnergyDrift {
+    /// 使用的积分方式
+    pub scheme: IntegrationScheme,
+    /// 标准场景开始时的机械能（动能加重力势能）
+    pub initial_energy: Real,
+    /// 最后一次 step 之后的机械能
+    pub final_energy: Real,
+    /// 模拟过程中机械能偏离初始值的最大比例
+    pub max_relative_drift: Real,
+}
+
+// 并查集，用于把物体划分成岛
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    pub(crate) fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+        }
+    }
+
+    pub(crate) fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub(crate) fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{math::Real, vec2::Vec2};
+
+/// 接触面相对于重力方向的分类
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SurfaceKind {
+    /// 可以站立的地面
+    Ground,
+    /// 墙壁，坡度超过阈值的斜面也算作墙壁
+    Wall,
+    /// 天花板
+    Ceiling,
+}
+
+/// 上一次 step 中某个物体与各类接触面的接触情况
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SurfaceState {
+    pub on_ground: bool,
+    pub on_wall: bool,
+    pub on_ceiling: bool,
+}
+
+impl SurfaceState {
+    pub(crate) fn add(&mut self, kind: SurfaceKind) {
+        match kind {
+            SurfaceKind::Ground => self.on_ground = true,
+            SurfaceKind::Wall => self.on_wall = true,
+            SurfaceKind::Ceiling => self.on_ceiling = true,
+        }
+    }
+}
+
+/// 对接触面的法线分类
+/// * `normal`: 接触面指向物体的法线，单位向量
+/// * `up`: 与重力方向相反的单位向量
+/// * `max_slope`: 可以站立的最大坡度，单位为弧度
+pub fn classify_normal(normal: Vec2, up: Vec2, max_slope: Real) -> SurfaceKind {
+    let cos_slope = max_slope.cos();
+    let d = normal.dot(up);
+    if d >= cos_slope {
+        SurfaceKind::Ground
+    } else if d <= -cos_slope {
+        SurfaceKind::Ceiling
+    } else {
+        SurfaceKind::Wall
+    }
+}
@@ -0,0 +1,151 @@
+use crate::vec2::Vec2;
+
+/// 一个物体的采样记录，只保留最近的 `capacity` 个采样
+#[derive(Clone, PartialEq, Debug)]
+pub struct BodyTrace {
+    body: usize,
+    capacity: usize,
+    // 长度超过 2 * capacity 时才丢弃旧的采样，保证有效的采样总是一段连续的切片
+    positions: Vec<Vec2>,
+    velocities: Vec<Vec2>,
+}
+
+impl BodyTrace {
+    fn new(body: usize, capacity: usize) -> BodyTrace {
+        BodyTrace {
+            body,
+            capacity,
+            positions: Vec::with_capacity(capacity * 2),
+            velocities: Vec::with_capacity(capacity * 2),
+        }
+    }
+
+    fn push(&mut self, position: Vec2, velocity: Vec2) {
+        if self.positions.len() == self.capacity * 2 {
+            self.positions.drain(..self.capacity);
+            self.velocities.drain(..self.capacity);
+        }
+        self.positions.push(position);
+        self.velocities.push(velocity);
+    }
+
+    /// 物体在 world 中的下标
+    pub fn body(&self) -> usize {
+        self.body
+    }
+
+    /// 按时间顺序排列的位置采样，最后一个是最新的
+    pub fn positions(&self) -> &[Vec2] {
+        let start = self.positions.len().saturating_sub(self.capacity);
+        &self.positions[start..]
+    }
+
+    /// 按时间顺序排列的速度采样，最后一个是最新的
+    pub fn velocities(&self) -> &[Vec2] {
+        let start = self.velocities.len().saturating_sub(self.capacity);
+        &self.velocities[start..]
+    }
+
+    /// 清空所有采样
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.velocities.clear();
+    }
+}
+
+/// 在每次 step 结束时记录选定物体的位置和速度，用来绘制曲线调整恢复系数、阻尼等参数
+#[derive(Clone, PartialEq, Debug)]
+pub struct Telemetry {
+    capacity: usize,
+    traces: Vec<BodyTrace>,
+}
+
+impl Telemetry {
+    /// 每个物体最多保留 `capacity` 个采样
+    pub fn new(capacity: usize) -> Telemetry {
+        Telemetry {
+            capacity: capacity.max(1),
+            traces: vec![],
+        }
+    }
+
+    /// 开始记录下标为 `body` 的物体，已经在记录的物体不会重复添加
+    pub fn track(&mut self, body: usize) {
+        if self.trace(body).is_none() {
+            self.traces.push(BodyTrace::new(body, self.capacity));
+        }
+    }
+
+    /// 停止记录下标为 `body` 的物体，并丢弃它的采样
+    pub fn untrack(&mut self, body: usize) {
+        self.traces.retain(|trace| trace.body != body);
+    }
+
+    /// 下标为 `body` 的物体的采样记录
+    pub fn trace(&self, body: usize) -> Option<&BodyTrace> {
+        self.traces.iter().find(|trace| trace.body == body)
+    }
+
+    /// 所有物体的采样记录
+    pub fn traces(&self) -> &[BodyTrace] {
+        &self.traces
+    }
+
+    /// 清空所有物体的采样，但继续记录
+    pub fn clear(&mut self) {
+        self.traces.iter_mut().for_each(BodyTrace::clear);
+    }
+
+    // 物体在 world 中的下标变化之后更新记录的下标，`remap` 返回 None 的物体已经被移除，丢弃它的采样
+    pub(crate) fn remap<F>(&mut self, remap: F)
+    where
+        F: Fn(usize) -> Option<usize>,
+    {
+        self.traces.retain_mut(|trace| match remap(trace.body) {
+            Some(body) => {
+                trace.body = body;
+                true
+            }
+            None => false,
+        });
+    }
+
+    // 追加一次采样，`sample` 返回物体的位置和速度，物体不存在时返回 `None`
+    pub(crate) fn record<F>(&mut self, mut sample: F)
+    where
+        F: FnMut(usize) -> Option<(Vec2, Vec2)>,
+    {
+        for trace in &mut self.traces {
+            if let Some((position, velocity)) = sample(trace.body) {
+                trace.push(position, velocity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telemetry_keeps_latest_samples() {
+        let mut telemetry = Telemetry::new(3);
+        telemetry.track(0);
+        telemetry.track(0);
+        assert_eq!(telemetry.traces().len(), 1);
+
+        for i in 0..10 {
+            let v = Vec2::splat(i as crate::math::Real);
+            telemetry.record(|_| Some((v, -v)));
+        }
+        let trace = telemetry.trace(0).unwrap();
+        let expected: Vec<Vec2> = (7..10)
+            .map(|i| Vec2::splat(i as crate::math::Real))
+            .collect();
+        assert_eq!(trace.positions(), &expected[..]);
+        assert_eq!(trace.velocities()[2], Vec2::splat(-9.));
+
+        telemetry.untrack(0);
+        assert!(telemetry.trace(0).is_none());
+    }
+}
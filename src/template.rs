@@ -0,0 +1,24 @@
+use crate::{body::Body, vec2::Vec2};
+
+/// 物体模板，保存一个设置好形状、材质和各种标记的原型物体，
+/// 用于反复创建属性完全一致的子弹、箱子、敌人等
+pub struct BodyTemplate {
+    prototype: Body,
+}
+
+impl BodyTemplate {
+    /// 以 `prototype` 为原型创建模板，原型的位置会被忽略，
+    /// 速度、旋转和角速度会复制到每个实例上。速度回调无法复制，实例没有速度回调
+    pub fn new(prototype: Body) -> BodyTemplate {
+        BodyTemplate { prototype }
+    }
+
+    pub fn prototype(&self) -> &Body {
+        &self.prototype
+    }
+
+    /// 在 `position` 创建一个新的物体
+    pub fn instantiate(&self, position: Vec2) -> Body {
+        self.prototype.fork_at(position)
+    }
+}
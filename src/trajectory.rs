@@ -0,0 +1,30 @@
+use crate::{math::Real, vec2::Vec2};
+
+/// 轨迹预测的初始状态，参见 `World::predict_trajectory`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TrajectoryParams {
+    /// 初始位置
+    pub position: Vec2,
+    /// 初始速度
+    pub velocity: Vec2,
+    /// 线性阻力系数（每秒），每次积分时加速度减去 `velocity * drag`，
+    /// 与物体设置 `Body::set_acceleration(move |v, _| -v * drag)` 的效果相同，0 表示没有阻力
+    pub drag: Real,
+    /// 是否检测碰撞，为 true 时轨迹在第一次碰到其他物体的位置结束
+    pub collide: bool,
+    /// 检测碰撞时忽略的物体下标，通常是发射者自身
+    pub ignore: Option<usize>,
+}
+
+impl TrajectoryParams {
+    /// 从给定的位置和速度开始预测，没有阻力，不检测碰撞
+    pub fn new(position: Vec2, velocity: Vec2) -> TrajectoryParams {
+        TrajectoryParams {
+            position,
+            velocity,
+            drag: 0.,
+            collide: false,
+            ignore: None,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops;
+
+use crate::{rot::Rot, vec2::Vec2};
+
+/// Rigid 2d transform, a rotation followed by a translation.
+/// Maps points from the local space of a body to world space.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform2D {
+    pub translation: Vec2,
+    pub rotation: Rot,
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        translation: Vec2::ZERO,
+        rotation: Rot::IDENTITY,
+    };
+
+    /// creates a new `Transform2D`
+    #[inline]
+    pub fn new(translation: Vec2, rotation: Rot) -> Transform2D {
+        Transform2D {
+            translation,
+            rotation,
+        }
+    }
+
+    /// Creates a transform that only translates by `translation`.
+    #[inline]
+    pub fn from_translation(translation: Vec2) -> Transform2D {
+        Transform2D::new(translation, Rot::IDENTITY)
+    }
+
+    /// Transforms a point from local space to world space.
+    #[inline]
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.rotation * point + self.translation
+    }
+
+    /// Transforms a point from world space to local space.
+    #[inline]
+    pub fn inverse_transform_point(&self, point: Vec2) -> Vec2 {
+        self.rotation.inv_mul_vec2(point - self.translation)
+    }
+
+    /// Transforms a direction from local space to world space, ignoring the translation.
+    #[inline]
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        self.rotation * vector
+    }
+
+    /// Transforms a direction from world space to local space, ignoring the translation.
+    #[inline]
+    pub fn inverse_transform_vector(&self, vector: Vec2) -> Vec2 {
+        self.rotation.inv_mul_vec2(vector)
+    }
+
+    /// Returns the inverse transform, mapping world space back to local space.
+    #[must_use]
+    #[inline]
+    pub fn inverse(&self) -> Transform2D {
+        let rotation = self.rotation.inverse();
+        Transform2D::new(rotation * -self.translation, rotation)
+    }
+}
+
+/// Composition, `a * b` applies `b` first and then `a`.
+impl ops::Mul<Transform2D> for Transform2D {
+    type Output = Transform2D;
+
+    #[inline]
+    fn mul(self, rhs: Transform2D) -> Self::Output {
+        Transform2D::new(
+            self.transform_point(rhs.translation),
+            self.rotation * rhs.rotation,
+        )
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::consts::FRAC_PI_2;
+
+    fn assert_vec2_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    /// Test cases for:
+    /// * Transform2D.transform_point(Vec2)
+    /// * Transform2D.inverse_transform_point(Vec2)
+    /// * Transform2D.inverse()
+    #[test]
+    fn transform2d_point_should_work() {
+        let transform = Transform2D::new(Vec2::new(10., 5.), Rot::from_angle(FRAC_PI_2));
+        let point = transform.transform_point(Vec2::new(1., 0.));
+        assert_vec2_near(point, Vec2::new(10., 6.));
+        assert_vec2_near(transform.inverse_transform_point(point), Vec2::new(1., 0.));
+        assert_vec2_near(
+            transform.inverse().transform_point(point),
+            Vec2::new(1., 0.),
+        );
+        assert_vec2_near(transform.transform_vector(Vec2::X), Vec2::Y);
+    }
+
+    /// Test cases for:
+    /// * Transform2D * Transform2D
+    #[test]
+    fn transform2d_composition_should_work() {
+        let parent = Transform2D::new(Vec2::new(3., -1.), Rot::from_angle(0.4));
+        let child = Transform2D::new(Vec2::new(-2., 7.), Rot::from_angle(1.1));
+        let point = Vec2::new(0.5, 2.);
+        assert_vec2_near(
+            (parent * child).transform_point(point),
+            parent.transform_point(child.transform_point(point)),
+        );
+        let identity = parent * parent.inverse();
+        assert_vec2_near(identity.translation, Vec2::ZERO);
+        assert_vec2_near(identity.rotation * Vec2::X, Vec2::X);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+use crate::math::Real;
+
+/// `World::validate` 发现的场景问题
+/// 每一项都带有刚体在 world 中的下标
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ValidationIssue {
+    /// AABB 某个方向上的尺寸为 0
+    ZeroSizeAABB { body: usize },
+    /// AABB 的 min 大于 max
+    InvertedAABB { body: usize },
+    /// 圆的半径小于等于 0
+    NonPositiveRadius { body: usize, radius: Real },
+    /// 形状的密度小于等于 0
+    NonPositiveDensity { body: usize, density: Real },
+    /// 两个静态物体互相重叠
+    OverlappingStatics { a: usize, b: usize },
+    /// 位置中含有 NaN 或无穷大
+    NonFinitePosition { body: usize },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ValidationIssue::ZeroSizeAABB { body } => {
+                write!(f, "body {}: aabb has zero size", body)
+            }
+            ValidationIssue::InvertedAABB { body } => {
+                write!(f, "body {}: aabb min is greater than max", body)
+            }
+            ValidationIssue::NonPositiveRadius { body, radius } => {
+                write!(f, "body {}: circle radius {} is not positive", body, radius)
+            }
+            ValidationIssue::NonPositiveDensity { body, density } => {
+                write!(f, "body {}: density {} is not positive", body, density)
+            }
+            ValidationIssue::OverlappingStatics { a, b } => {
+                write!(f, "static bodies {} and {} overlap", a, b)
+            }
+            ValidationIssue::NonFinitePosition { body } => {
+                write!(f, "body {}: position is not finite", body)
+            }
+        }
+    }
+}
@@ -1,23 +1,32 @@
+use std::iter::Sum;
+
 use std::ops::{self, Mul, SubAssign};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::math::Real;
+
+pub mod batch;
+
 /// 2d vector
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec2 {
-    pub x: f32,
-    pub y: f32,
+    pub x: Real,
+    pub y: Real,
 }
 
 impl Vec2 {
     pub const ZERO: Self = Self::splat(0.0);
-    
+    pub const X: Self = Self { x: 1.0, y: 0.0 };
+    pub const Y: Self = Self { x: 0.0, y: 1.0 };
 
     /// creates a `Vec2` with all elements set to `v`
     #[inline(always)]
-    pub const fn splat(v: f32) -> Vec2 {
+    pub const fn splat(v: Real) -> Vec2 {
         Vec2 { x: v, y: v }
     }
 }
@@ -26,10 +35,22 @@ impl Vec2 {
 impl Vec2 {
     /// creates a new `Vec2`
     #[inline(always)]
-    pub fn new(x: f32, y: f32) -> Vec2 {
+    pub fn new(x: Real, y: Real) -> Vec2 {
         Vec2 { x, y }
     }
 
+    /// Converts `self` from meters to a unit where one meter is `units_per_meter` long.
+    #[inline]
+    pub fn meters_to_units(self, units_per_meter: Real) -> Vec2 {
+        self * units_per_meter
+    }
+
+    /// Converts `self` from a unit where one meter is `units_per_meter` long to meters.
+    #[inline]
+    pub fn units_to_meters(self, units_per_meter: Real) -> Vec2 {
+        self / units_per_meter
+    }
+
     /// Returns `self` normalized to length 1.0
     ///
     /// # Panics
@@ -57,28 +78,293 @@ impl Vec2 {
 
     /// computes the dot product
     #[inline]
-    pub fn dot(self, rhs: Self) -> f32 {
+    pub fn dot(self, rhs: Self) -> Real {
         (self.x * rhs.x) + (self.y * rhs.y)
     }
 
+    /// Returns a vector that is equal to `self` rotated by 90 degrees.
+    #[inline]
+    pub fn perp(self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// The perpendicular dot product of `self` and `rhs`.
+    /// Also known as the wedge product, 2D cross product, and determinant.
+    #[inline]
+    pub fn perp_dot(self, rhs: Self) -> Real {
+        (self.x * rhs.y) - (self.y * rhs.x)
+    }
+
+    /// computes the z-component of the 3d cross product, same as [`Vec2::perp_dot`]
+    #[inline]
+    pub fn cross(self, rhs: Self) -> Real {
+        self.perp_dot(rhs)
+    }
+
+    /// Creates a 2D vector containing `[angle.cos(), angle.sin()]`. This can be used in
+    /// conjunction with the [`rotate()`][Self::rotate()] method, e.g.
+    /// `Vec2::from_angle(PI).rotate(Vec2::Y)` will create the vector `[-1, 0]`
+    /// and rotate [`Vec2::Y`] around it returning `-Vec2::Y`.
+    #[inline]
+    pub fn from_angle(angle: Real) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self { x: cos, y: sin }
+    }
+
+    /// Returns the angle (in radians) of this vector in the range `[-π, +π]`.
+    ///
+    /// The input does not need to be a unit vector however it must be non-zero.
+    #[inline]
+    pub fn to_angle(self) -> Real {
+        self.y.atan2(self.x)
+    }
+
+    /// Returns the signed angle (in radians) between `self` and `rhs` in the range `[-π, +π]`.
+    ///
+    /// The inputs do not need to be unit vectors however they must be non-zero.
+    #[inline]
+    pub fn angle_between(self, rhs: Self) -> Real {
+        self.perp_dot(rhs).atan2(self.dot(rhs))
+    }
+
+    /// Returns `rhs` rotated by the angle of `self`. If `self` is normalized,
+    /// then this is just a rotation. This is what you usually want. Otherwise,
+    /// it will be like a rotation with a multiplication by `self`'s length.
+    #[must_use]
+    #[inline]
+    pub fn rotate(self, rhs: Self) -> Self {
+        Self {
+            x: self.x * rhs.x - self.y * rhs.y,
+            y: self.y * rhs.x + self.x * rhs.y,
+        }
+    }
+
     /// computes the length of `self`
     #[inline]
-    pub fn length(self) -> f32 {
+    pub fn length(self) -> Real {
         (self.dot(self)).sqrt()
     }
 
     /// computes the 1.0 / length
     #[inline]
-    pub fn length_recip(self) -> f32 {
+    pub fn length_recip(self) -> Real {
         self.length().recip()
     }
 
     /// computes the squared length of `self`
     #[inline]
-    pub fn length_squared(self) -> f32 {
+    pub fn length_squared(self) -> Real {
         self.dot(self)
     }
 
+    /// Computes the Euclidean distance between two points in space.
+    #[inline]
+    pub fn distance(self, rhs: Self) -> Real {
+        (self - rhs).length()
+    }
+
+    /// Compute the squared euclidean distance between two points in space.
+    #[inline]
+    pub fn distance_squared(self, rhs: Self) -> Real {
+        (self - rhs).length_squared()
+    }
+
+    /// Returns the vector projection of `self` onto `rhs`.
+    ///
+    /// `rhs` must be of non-zero length.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `rhs` is zero length.
+    #[must_use]
+    #[inline]
+    pub fn project_onto(self, rhs: Self) -> Self {
+        let other_len_sq_rcp = rhs.length_squared().recip();
+        assert!(other_len_sq_rcp.is_finite());
+        rhs * self.dot(rhs) * other_len_sq_rcp
+    }
+
+    /// Returns the vector rejection of `self` from `rhs`.
+    ///
+    /// The vector rejection is the vector perpendicular to the projection of `self` onto
+    /// `rhs`, in other words the result of `self - self.project_onto(rhs)`.
+    ///
+    /// `rhs` must be of non-zero length.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `rhs` has a length of zero.
+    #[must_use]
+    #[inline]
+    pub fn reject_from(self, rhs: Self) -> Self {
+        self - self.project_onto(rhs)
+    }
+
+    /// Returns the reflection vector for a given incident vector `self` and surface normal
+    /// `normal`.
+    ///
+    /// `normal` must be normalized.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `normal` is not normalized.
+    #[must_use]
+    #[inline]
+    pub fn reflect(self, normal: Self) -> Self {
+        assert!((normal.length_squared() - 1.).abs() <= 1e-4, "expected normalized normal");
+        self - normal * (2. * self.dot(normal))
+    }
+
+    /// Performs a linear interpolation between `self` and `rhs` based on the value `t`.
+    ///
+    /// When `t` is `0.0`, the result will be equal to `self`.  When `t` is `1.0`, the result
+    /// will be equal to `rhs`. When `t` is outside of range `[0, 1]`, the result is linearly
+    /// extrapolated.
+    #[inline]
+    pub fn lerp(self, rhs: Self, t: Real) -> Self {
+        self + (rhs - self) * t
+    }
+
+    /// Performs a normalized linear interpolation between `self` and `rhs` based on the value `t`.
+    ///
+    /// The result is the linear interpolation normalized to length 1.0, which is a cheap
+    /// approximation of spherical interpolation between two directions.
+    /// Returns `None` when the interpolated vector is length zero or very close to zero.
+    #[must_use]
+    #[inline]
+    pub fn nlerp(self, rhs: Self, t: Real) -> Option<Self> {
+        self.lerp(rhs, t).try_normalize()
+    }
+
+    /// Moves towards `rhs` based on the value `max_delta`.
+    ///
+    /// When `max_delta` is `0.0`, the result will be equal to `self`.
+    /// When `max_delta` is equal to the distance between `self` and `rhs`, the result will be
+    /// equal to `rhs`.
+    /// Will not go past `rhs`.
+    #[inline]
+    pub fn move_towards(self, rhs: Self, max_delta: Real) -> Self {
+        let a = rhs - self;
+        let len = a.length();
+        if len <= max_delta || len <= 1e-4 {
+            return rhs;
+        }
+        self + a / len * max_delta
+    }
+
+    /// Returns `true` if, and only if, all elements are finite.  If any element is either
+    /// `NaN`, positive or negative infinity, this will return `false`.
+    #[inline]
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    /// Returns `true` if any elements are `NaN`.
+    #[inline]
+    pub fn is_nan(self) -> bool {
+        self.x.is_nan() || self.y.is_nan()
+    }
+
+    /// Returns a vector with every `NaN` element replaced by `0.0`.
+    #[must_use]
+    #[inline]
+    pub fn nan_to_zero(self) -> Self {
+        Self {
+            x: if self.x.is_nan() { 0.0 } else { self.x },
+            y: if self.y.is_nan() { 0.0 } else { self.y },
+        }
+    }
+
+    /// Returns a vector with every non-finite element (`NaN`, positive or negative infinity)
+    /// replaced by `0.0`.
+    #[must_use]
+    #[inline]
+    pub fn sanitize(self) -> Self {
+        Self {
+            x: if self.x.is_finite() { self.x } else { 0.0 },
+            y: if self.y.is_finite() { self.y } else { 0.0 },
+        }
+    }
+
+    /// Returns a vector containing the absolute value of each element of `self`.
+    #[inline]
+    pub fn abs(self) -> Self {
+        Self {
+            x: self.x.abs(),
+            y: self.y.abs(),
+        }
+    }
+
+    /// Returns a vector with elements representing the sign of `self`.
+    ///
+    /// - `1.0` if the number is positive, `+0.0` or `INFINITY`
+    /// - `-1.0` if the number is negative, `-0.0` or `NEG_INFINITY`
+    /// - `NAN` if the number is `NAN`
+    #[inline]
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    /// Returns a vector containing the largest integer less than or equal to a number for each
+    /// element of `self`.
+    #[inline]
+    pub fn floor(self) -> Self {
+        Self {
+            x: self.x.floor(),
+            y: self.y.floor(),
+        }
+    }
+
+    /// Returns a vector containing the smallest integer greater than or equal to a number for
+    /// each element of `self`.
+    #[inline]
+    pub fn ceil(self) -> Self {
+        Self {
+            x: self.x.ceil(),
+            y: self.y.ceil(),
+        }
+    }
+
+    /// Returns a vector containing the nearest integer to a number for each element of `self`.
+    /// Round half-way cases away from 0.0.
+    #[inline]
+    pub fn round(self) -> Self {
+        Self {
+            x: self.x.round(),
+            y: self.y.round(),
+        }
+    }
+
+    /// Returns the horizontal minimum of `self`.
+    ///
+    /// In other words this computes `min(x, y)`.
+    #[inline]
+    pub fn min_element(self) -> Real {
+        self.x.min(self.y)
+    }
+
+    /// Returns the horizontal maximum of `self`.
+    ///
+    /// In other words this computes `max(x, y)`.
+    #[inline]
+    pub fn max_element(self) -> Real {
+        self.x.max(self.y)
+    }
+
+    /// Returns the sum of all elements of `self`.
+    ///
+    /// In other words, this computes `self.x + self.y`.
+    #[inline]
+    pub fn element_sum(self) -> Real {
+        self.x + self.y
+    }
+
     /// Component-wise clamping of values, similar to [`f32::clamp`].
     ///
     /// Each element in `min` must be less-or-equal to the corresponding element in `max`.
@@ -138,11 +424,11 @@ impl ops::Add<Vec2> for Vec2 {
     }
 }
 
-impl ops::Add<f32> for Vec2 {
+impl ops::Add<Real> for Vec2 {
     type Output = Vec2;
 
     #[inline]
-    fn add(self, rhs: f32) -> Self::Output {
+    fn add(self, rhs: Real) -> Self::Output {
         Vec2 {
             x: self.x + rhs,
             y: self.y + rhs,
@@ -150,7 +436,7 @@ impl ops::Add<f32> for Vec2 {
     }
 }
 
-impl ops::Add<Vec2> for f32 {
+impl ops::Add<Vec2> for Real {
     type Output = Vec2;
 
     #[inline]
@@ -166,8 +452,8 @@ impl ops::AddAssign<Vec2> for Vec2 {
     }
 }
 
-impl ops::AddAssign<f32> for Vec2 {
-    fn add_assign(&mut self, rhs: f32) {
+impl ops::AddAssign<Real> for Vec2 {
+    fn add_assign(&mut self, rhs: Real) {
         self.x += rhs;
         self.y += rhs;
     }
@@ -185,11 +471,11 @@ impl ops::Sub<Vec2> for Vec2 {
     }
 }
 
-impl ops::Sub<f32> for Vec2 {
+impl ops::Sub<Real> for Vec2 {
     type Output = Vec2;
 
     #[inline]
-    fn sub(self, rhs: f32) -> Self::Output {
+    fn sub(self, rhs: Real) -> Self::Output {
         Vec2 {
             x: self.x - rhs,
             y: self.y - rhs,
@@ -197,7 +483,7 @@ impl ops::Sub<f32> for Vec2 {
     }
 }
 
-impl ops::Sub<Vec2> for f32 {
+impl ops::Sub<Vec2> for Real {
     type Output = Vec2;
 
     #[inline]
@@ -213,8 +499,8 @@ impl SubAssign<Vec2> for Vec2 {
     }
 }
 
-impl SubAssign<f32> for Vec2 {
-    fn sub_assign(&mut self, rhs: f32) {
+impl SubAssign<Real> for Vec2 {
+    fn sub_assign(&mut self, rhs: Real) {
         self.x -= rhs;
         self.y -= rhs;
     }
@@ -232,11 +518,11 @@ impl ops::Mul<Vec2> for Vec2 {
     }
 }
 
-impl ops::Mul<f32> for Vec2 {
+impl ops::Mul<Real> for Vec2 {
     type Output = Vec2;
 
     #[inline]
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: Real) -> Self::Output {
         Vec2 {
             x: self.x * rhs,
             y: self.y * rhs,
@@ -244,7 +530,7 @@ impl ops::Mul<f32> for Vec2 {
     }
 }
 
-impl ops::Mul<Vec2> for f32 {
+impl ops::Mul<Vec2> for Real {
     type Output = Vec2;
 
     #[inline]
@@ -256,11 +542,25 @@ impl ops::Mul<Vec2> for f32 {
     }
 }
 
-impl ops::Div<f32> for Vec2 {
+impl ops::MulAssign<Vec2> for Vec2 {
+    fn mul_assign(&mut self, rhs: Vec2) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+    }
+}
+
+impl ops::MulAssign<Real> for Vec2 {
+    fn mul_assign(&mut self, rhs: Real) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl ops::Div<Real> for Vec2 {
     type Output = Vec2;
 
     #[inline]
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: Real) -> Self::Output {
         Vec2 {
             x: self.x / rhs,
             y: self.y / rhs,
@@ -280,28 +580,80 @@ impl ops::Div<Vec2> for Vec2 {
     }
 }
 
-impl From<[f32; 2]> for Vec2 {
+impl ops::DivAssign<Vec2> for Vec2 {
+    fn div_assign(&mut self, rhs: Vec2) {
+        self.x /= rhs.x;
+        self.y /= rhs.y;
+    }
+}
+
+impl ops::DivAssign<Real> for Vec2 {
+    fn div_assign(&mut self, rhs: Real) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl ops::Index<usize> for Vec2 {
+    type Output = Real;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("index out of bounds"),
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vec2 {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("index out of bounds"),
+        }
+    }
+}
+
+impl Sum for Vec2 {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + v)
+    }
+}
+
+impl<'a> Sum<&'a Vec2> for Vec2 {
     #[inline]
-    fn from(a: [f32; 2]) -> Self {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + *v)
+    }
+}
+
+impl From<[Real; 2]> for Vec2 {
+    #[inline]
+    fn from(a: [Real; 2]) -> Self {
         Self::new(a[0], a[1])
     }
 }
 
-impl From<Vec2> for [f32; 2] {
+impl From<Vec2> for [Real; 2] {
     #[inline]
     fn from(v: Vec2) -> Self {
         [v.x, v.y]
     }
 }
 
-impl From<(f32, f32)> for Vec2 {
+impl From<(Real, Real)> for Vec2 {
     #[inline]
-    fn from(t: (f32, f32)) -> Self {
+    fn from(t: (Real, Real)) -> Self {
         Self::new(t.0, t.1)
     }
 }
 
-impl From<Vec2> for (f32, f32) {
+impl From<Vec2> for (Real, Real) {
     #[inline]
     fn from(v: Vec2) -> Self {
         (v.x, v.y)
@@ -335,7 +687,7 @@ mod tests {
     #[test]
     fn vec2_add_should_work() {
         let vec2 = Vec2::new(1., 2.);
-        let number: f32 = 2.;
+        let number: Real = 2.;
         let rhs_vec2 = Vec2::new(2., 3.);
 
         let ret = vec2 + number;
@@ -357,7 +709,7 @@ mod tests {
     #[test]
     fn vec2_sub_should_work() {
         let vec2 = Vec2::new(1., 2.);
-        let number: f32 = 3.;
+        let number: Real = 3.;
         let rhs_vec2 = Vec2::new(3., 4.);
 
         let ret = vec2 - number;
@@ -380,7 +732,7 @@ mod tests {
     #[test]
     fn vec2_mul_should_work() {
         let vec2 = Vec2::new(5., 6.);
-        let number: f32 = 4.;
+        let number: Real = 4.;
         let rhs_vec2 = Vec2::new(1., 2.);
 
         let ret = vec2 * number;
@@ -401,7 +753,7 @@ mod tests {
     #[test]
     fn vec2_div_should_work() {
         let vec2 = Vec2::new(5., 8.);
-        let number: f32 = 4.;
+        let number: Real = 4.;
         let rhs_vec2 = Vec2::new(2., 3.);
 
         let ret = vec2 / number;
@@ -413,6 +765,57 @@ mod tests {
         assert_eq!(ret, expected);
     }
 
+    /// Test cases for:
+    /// * Vec2 *= number
+    /// * Vec2 *= Vec2
+    /// * Vec2 /= number
+    /// * Vec2 /= Vec2
+    #[test]
+    fn vec2_mul_div_assign_should_work() {
+        let mut vec2 = Vec2::new(2., 3.);
+        vec2 *= 2.;
+        assert_eq!(vec2, Vec2::new(4., 6.));
+        vec2 *= Vec2::new(0.5, 2.);
+        assert_eq!(vec2, Vec2::new(2., 12.));
+        vec2 /= 2.;
+        assert_eq!(vec2, Vec2::new(1., 6.));
+        vec2 /= Vec2::new(1., 3.);
+        assert_eq!(vec2, Vec2::new(1., 2.));
+    }
+
+    /// Test cases for:
+    /// * Vec2[usize]
+    /// * Vec2[usize] = f32
+    #[test]
+    fn vec2_index_should_work() {
+        let mut vec2 = Vec2::new(1., 2.);
+        assert_eq!(vec2[0], 1.);
+        assert_eq!(vec2[1], 2.);
+        vec2[1] = 5.;
+        assert_eq!(vec2, Vec2::new(1., 5.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn vec2_index_out_of_bounds_should_panic() {
+        let vec2 = Vec2::new(1., 2.);
+        let _ = vec2[2];
+    }
+
+    /// Test cases for:
+    /// * Iterator<Item = Vec2>.sum()
+    /// * Iterator<Item = &Vec2>.sum()
+    #[test]
+    fn vec2_sum_should_work() {
+        let points = [Vec2::new(1., 2.), Vec2::new(3., 4.), Vec2::new(5., 6.)];
+        let ret: Vec2 = points.iter().copied().sum();
+        assert_eq!(ret, Vec2::new(9., 12.));
+        let ret: Vec2 = points.iter().sum();
+        assert_eq!(ret / points.len() as Real, Vec2::new(3., 4.));
+        let ret: Vec2 = [].iter().sum();
+        assert_eq!(ret, Vec2::ZERO);
+    }
+
     /// Test cases for:
     /// * Vec2.normalize()
     /// * Vec2.try_normalize()
@@ -439,12 +842,12 @@ mod tests {
     fn vec2_length_should_work() {
         let vec2 = Vec2::new(1., 2.);
         let ret = vec2.length();
-        let expected = ((1. * 1. + 2. * 2.) as f32).sqrt();
+        let expected = ((1. * 1. + 2. * 2.) as Real).sqrt();
         assert_eq!(ret, expected);
 
         let vec2 = Vec2::splat(2.);
         let ret = vec2.length_squared();
-        let expected: f32 = 2. * 2. + 2. * 2.;
+        let expected: Real = 2. * 2. + 2. * 2.;
         assert_eq!(ret, expected);
 
         let vec2 = Vec2::splat(0.);
@@ -463,13 +866,186 @@ mod tests {
         let vec2 = Vec2::new(1., 2.);
         let vec2_rhs = Vec2::new(3., 4.);
         let ret = vec2.dot(vec2_rhs);
-        let expected: f32 = 1. * 3. + 2. * 4.;
+        let expected: Real = 1. * 3. + 2. * 4.;
         assert_eq!(ret, expected);
 
         let ret = vec2_rhs.dot(vec2);
         assert_eq!(ret, expected);
     }
 
+    /// Test cases for:
+    /// * Vec2.distance(Vec2)
+    /// * Vec2.distance_squared(Vec2)
+    #[test]
+    fn vec2_distance_should_work() {
+        let vec2 = Vec2::new(1., 2.);
+        let vec2_rhs = Vec2::new(4., 6.);
+        assert_eq!(vec2.distance(vec2_rhs), 5.);
+        assert_eq!(vec2_rhs.distance(vec2), 5.);
+        assert_eq!(vec2.distance_squared(vec2_rhs), 25.);
+        assert_eq!(vec2.distance(vec2), 0.);
+    }
+
+    /// Test cases for:
+    /// * Vec2.perp()
+    /// * Vec2.perp_dot(Vec2)
+    /// * Vec2.cross(Vec2)
+    #[test]
+    fn vec2_perp_should_work() {
+        let vec2 = Vec2::new(1., 2.);
+        let ret = vec2.perp();
+        let expected = Vec2::new(-2., 1.);
+        assert_eq!(ret, expected);
+        assert_eq!(ret.dot(vec2), 0.);
+
+        let vec2_rhs = Vec2::new(3., 4.);
+        let ret = vec2.perp_dot(vec2_rhs);
+        let expected: Real = 1. * 4. - 2. * 3.;
+        assert_eq!(ret, expected);
+
+        let ret = vec2_rhs.cross(vec2);
+        assert_eq!(ret, -expected);
+
+        let ret = Vec2::new(1., 0.).cross(Vec2::new(0., 1.));
+        assert_eq!(ret, 1.);
+    }
+
+    /// Test cases for:
+    /// * Vec2::from_angle(f32)
+    /// * Vec2.to_angle()
+    /// * Vec2.angle_between(Vec2)
+    /// * Vec2.rotate(Vec2)
+    #[test]
+    fn vec2_rotation_should_work() {
+        use crate::math::consts::{FRAC_PI_2, PI};
+        const EPSILON: Real = 1e-6;
+
+        let ret = Vec2::from_angle(0.);
+        assert_eq!(ret, Vec2::X);
+        let ret = Vec2::from_angle(FRAC_PI_2);
+        assert!((ret - Vec2::Y).length() < EPSILON);
+
+        assert_eq!(Vec2::X.to_angle(), 0.);
+        assert_eq!(Vec2::Y.to_angle(), FRAC_PI_2);
+        assert_eq!(Vec2::new(-2., 0.).to_angle(), PI);
+
+        assert_eq!(Vec2::X.angle_between(Vec2::Y), FRAC_PI_2);
+        assert_eq!(Vec2::Y.angle_between(Vec2::X), -FRAC_PI_2);
+        assert_eq!(Vec2::new(3., 3.).angle_between(Vec2::new(1., 1.)), 0.);
+
+        let ret = Vec2::from_angle(PI).rotate(Vec2::Y);
+        assert!((ret + Vec2::Y).length() < EPSILON);
+        let ret = Vec2::Y.rotate(Vec2::new(1., 2.));
+        assert_eq!(ret, Vec2::new(-2., 1.));
+    }
+
+    /// Test cases for:
+    /// * Vec2.lerp(Vec2, f32)
+    /// * Vec2.nlerp(Vec2, f32)
+    /// * Vec2.move_towards(Vec2, f32)
+    #[test]
+    fn vec2_interpolation_should_work() {
+        let from = Vec2::new(1., 2.);
+        let to = Vec2::new(5., -2.);
+        assert_eq!(from.lerp(to, 0.), from);
+        assert_eq!(from.lerp(to, 1.), to);
+        assert_eq!(from.lerp(to, 0.5), Vec2::new(3., 0.));
+        assert_eq!(from.lerp(to, 2.), Vec2::new(9., -6.));
+
+        let ret = Vec2::X.nlerp(Vec2::Y, 0.5).unwrap();
+        let expected = Vec2::new(1., 1.).normalize();
+        assert!((ret - expected).length() < 1e-6);
+        assert_eq!(Vec2::X.nlerp(-Vec2::X, 0.5), None);
+
+        let from = Vec2::ZERO;
+        let to = Vec2::new(3., 4.);
+        assert_eq!(from.move_towards(to, 0.), from);
+        assert_eq!(from.move_towards(to, 2.5), Vec2::new(1.5, 2.));
+        assert_eq!(from.move_towards(to, 5.), to);
+        assert_eq!(from.move_towards(to, 10.), to);
+    }
+
+    /// Test cases for:
+    /// * Vec2.project_onto(Vec2)
+    /// * Vec2.reject_from(Vec2)
+    /// * Vec2.reflect(Vec2)
+    #[test]
+    fn vec2_project_reject_reflect_should_work() {
+        let vec2 = Vec2::new(3., 4.);
+        assert_eq!(vec2.project_onto(Vec2::new(2., 0.)), Vec2::new(3., 0.));
+        assert_eq!(vec2.reject_from(Vec2::new(2., 0.)), Vec2::new(0., 4.));
+        assert_eq!(vec2.project_onto(Vec2::new(0., -1.)), Vec2::new(0., 4.));
+        assert_eq!(vec2.reject_from(Vec2::new(0., -1.)), Vec2::new(3., 0.));
+
+        let ret = Vec2::new(1., 1.).project_onto(Vec2::new(1., -1.));
+        assert_eq!(ret, Vec2::ZERO);
+
+        assert_eq!(Vec2::new(1., -1.).reflect(Vec2::Y), Vec2::new(1., 1.));
+        assert_eq!(Vec2::new(2., 0.).reflect(-Vec2::X), Vec2::new(-2., 0.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn vec2_project_onto_zero_should_panic() {
+        let _ = Vec2::new(1., 2.).project_onto(Vec2::ZERO);
+    }
+
+    /// Test cases for:
+    /// * Vec2.abs()
+    /// * Vec2.signum()
+    /// * Vec2.floor()
+    /// * Vec2.ceil()
+    /// * Vec2.round()
+    #[test]
+    fn vec2_component_wise_should_work() {
+        let vec2 = Vec2::new(-1.5, 2.4);
+        assert_eq!(vec2.abs(), Vec2::new(1.5, 2.4));
+        assert_eq!(vec2.signum(), Vec2::new(-1., 1.));
+        assert_eq!(Vec2::new(0., -0.).signum(), Vec2::new(1., -1.));
+        assert_eq!(vec2.floor(), Vec2::new(-2., 2.));
+        assert_eq!(vec2.ceil(), Vec2::new(-1., 3.));
+        assert_eq!(vec2.round(), Vec2::new(-2., 2.));
+        assert_eq!(Vec2::new(0.5, -0.5).round(), Vec2::new(1., -1.));
+    }
+
+    /// Test cases for:
+    /// * Vec2.is_finite()
+    /// * Vec2.is_nan()
+    /// * Vec2.nan_to_zero()
+    /// * Vec2.sanitize()
+    #[test]
+    fn vec2_finite_should_work() {
+        let vec2 = Vec2::new(1., 2.);
+        assert!(vec2.is_finite());
+        assert!(!vec2.is_nan());
+        assert_eq!(vec2.nan_to_zero(), vec2);
+        assert_eq!(vec2.sanitize(), vec2);
+
+        let vec2 = Vec2::new(Real::NAN, Real::INFINITY);
+        assert!(!vec2.is_finite());
+        assert!(vec2.is_nan());
+        assert_eq!(vec2.nan_to_zero(), Vec2::new(0., Real::INFINITY));
+        assert_eq!(vec2.sanitize(), Vec2::ZERO);
+
+        let vec2 = Vec2::new(3., Real::NEG_INFINITY);
+        assert!(!vec2.is_finite());
+        assert!(!vec2.is_nan());
+        assert_eq!(vec2.sanitize(), Vec2::new(3., 0.));
+    }
+
+    /// Test cases for:
+    /// * Vec2.min_element()
+    /// * Vec2.max_element()
+    /// * Vec2.element_sum()
+    #[test]
+    fn vec2_element_reduction_should_work() {
+        let vec2 = Vec2::new(3., -2.);
+        assert_eq!(vec2.min_element(), -2.);
+        assert_eq!(vec2.max_element(), 3.);
+        assert_eq!(vec2.element_sum(), 1.);
+        assert_eq!(Vec2::splat(4.).min_element(), 4.);
+    }
+
     /// Test cases for:
     /// * Vec2.clamp(Vec2, Vec2)
     #[test]
@@ -494,4 +1070,26 @@ mod tests {
         let expected = Vec2::new(3., 5.);
         assert_eq!(ret, expected);
     }
+
+    /// Test cases for:
+    /// * Vec2.meters_to_units(Real)
+    /// * Vec2.units_to_meters(Real)
+    #[test]
+    fn vec2_unit_conversion_should_work() {
+        let meters = Vec2::new(1.5, -2.);
+        let pixels = meters.meters_to_units(50.);
+        assert_eq!(pixels, Vec2::new(75., -100.));
+        assert_eq!(pixels.units_to_meters(50.), meters);
+    }
+
+    /// Test cases for:
+    /// * Vec2: Serialize + Deserialize
+    #[cfg(feature = "serde")]
+    #[test]
+    fn vec2_should_round_trip_through_serde() {
+        let v = Vec2::new(1.5, -2.);
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"x":1.5,"y":-2.0}"#);
+        assert_eq!(serde_json::from_str::<Vec2>(&json).unwrap(), v);
+    }
 }
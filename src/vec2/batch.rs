@@ -0,0 +1,124 @@
+//! Batched operations over slices of [`Vec2`].
+//!
+//! With the `simd` feature enabled the loops use SSE on x86_64 and NEON on aarch64,
+//! processing two vectors per instruction. Other targets, and builds with the `f64`
+//! feature, fall back to the scalar loop.
+
+use crate::{math::Real, vec2::Vec2};
+
+/// Advances every position by its velocity over `dt`: `positions[i] += velocities[i] * dt`.
+///
+/// # Panics
+///
+/// will panic if `positions` and `velocities` have different lengths
+pub fn integrate(positions: &mut [Vec2], velocities: &[Vec2], dt: Real) {
+    assert_eq!(positions.len(), velocities.len());
+    simd::integrate(positions, velocities, dt);
+}
+
+#[cfg(any(
+    not(feature = "simd"),
+    feature = "f64",
+    not(any(target_arch = "x86_64", target_arch = "aarch64"))
+))]
+mod simd {
+    use super::*;
+
+    #[inline]
+    pub(super) fn integrate(positions: &mut [Vec2], velocities: &[Vec2], dt: Real) {
+        scalar_integrate(positions, velocities, dt);
+    }
+}
+
+#[cfg(all(feature = "simd", not(feature = "f64"), target_arch = "x86_64"))]
+mod simd {
+    use super::*;
+    use std::arch::x86_64::{_mm_add_ps, _mm_loadu_ps, _mm_mul_ps, _mm_set1_ps, _mm_storeu_ps};
+
+    pub(super) fn integrate(positions: &mut [Vec2], velocities: &[Vec2], dt: Real) {
+        let pairs = positions.len() / 2;
+        // SAFETY: SSE is always available on x86_64, `Vec2` is `repr(C)` with two `f32`
+        // fields so two consecutive vectors are four packed floats, and every access
+        // stays within the first `pairs * 2` elements of both slices.
+        unsafe {
+            let dt = _mm_set1_ps(dt);
+            let p = positions.as_mut_ptr() as *mut f32;
+            let v = velocities.as_ptr() as *const f32;
+            for k in 0..pairs {
+                let offset = k * 4;
+                let step = _mm_mul_ps(_mm_loadu_ps(v.add(offset)), dt);
+                let result = _mm_add_ps(_mm_loadu_ps(p.add(offset)), step);
+                _mm_storeu_ps(p.add(offset), result);
+            }
+        }
+        scalar_integrate(&mut positions[pairs * 2..], &velocities[pairs * 2..], dt);
+    }
+}
+
+#[cfg(all(feature = "simd", not(feature = "f64"), target_arch = "aarch64"))]
+mod simd {
+    use super::*;
+    use std::arch::aarch64::{vaddq_f32, vdupq_n_f32, vld1q_f32, vmulq_f32, vst1q_f32};
+
+    pub(super) fn integrate(positions: &mut [Vec2], velocities: &[Vec2], dt: Real) {
+        let pairs = positions.len() / 2;
+        // SAFETY: NEON is always available on aarch64, `Vec2` is `repr(C)` with two `f32`
+        // fields so two consecutive vectors are four packed floats, and every access
+        // stays within the first `pairs * 2` elements of both slices.
+        unsafe {
+            let dt = vdupq_n_f32(dt);
+            let p = positions.as_mut_ptr() as *mut f32;
+            let v = velocities.as_ptr() as *const f32;
+            for k in 0..pairs {
+                let offset = k * 4;
+                let step = vmulq_f32(vld1q_f32(v.add(offset)), dt);
+                let result = vaddq_f32(vld1q_f32(p.add(offset)), step);
+                vst1q_f32(p.add(offset), result);
+            }
+        }
+        scalar_integrate(&mut positions[pairs * 2..], &velocities[pairs * 2..], dt);
+    }
+}
+
+#[inline]
+fn scalar_integrate(positions: &mut [Vec2], velocities: &[Vec2], dt: Real) {
+    for (p, v) in positions.iter_mut().zip(velocities) {
+        *p += *v * dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_integrate_should_match_scalar() {
+        let velocities: Vec<Vec2> = (0..7)
+            .map(|i| Vec2::new(i as Real, -(i as Real) * 0.5))
+            .collect();
+        let mut positions: Vec<Vec2> = (0..7).map(|i| Vec2::splat(i as Real)).collect();
+        let mut expected = positions.clone();
+        scalar_integrate(&mut expected, &velocities, 0.25);
+
+        integrate(&mut positions, &velocities, 0.25);
+        assert_eq!(positions, expected);
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_integrate_should_match_scalar_for_every_length() {
+        for len in 0..11 {
+            let velocities: Vec<Vec2> = (0..len)
+                .map(|i| Vec2::new(0.1 * i as Real - 0.3, 1.7 - 0.37 * i as Real))
+                .collect();
+            let mut positions: Vec<Vec2> = (0..len)
+                .map(|i| Vec2::new(1.3 * i as Real, -0.9 * i as Real + 0.01))
+                .collect();
+            let mut expected = positions.clone();
+            scalar_integrate(&mut expected, &velocities, 1. / 60.);
+
+            simd::integrate(&mut positions, &velocities, 1. / 60.);
+            assert_eq!(positions, expected);
+        }
+    }
+}
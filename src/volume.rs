@@ -0,0 +1,63 @@
+use crate::{body::Body, math::Real, vec2::Vec2};
+
+/// 影响区域对重叠物体产生的作用
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VolumeEffect {
+    /// 施加恒定的力，例如风扇
+    Force(Vec2),
+    /// 把物体的速度以 `rate`（每秒）的比例拉向 `velocity`，例如传送带、水流和弹射板。
+    /// `rate * dt` 不小于 1 时速度直接被设为 `velocity`
+    Velocity { velocity: Vec2, rate: Real },
+}
+
+/// 影响区域，每次 step 对包围盒与区域重叠的所有非静态物体施加 `effect`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ForceVolume {
+    min: Vec2,
+    max: Vec2,
+    effect: VolumeEffect,
+}
+
+impl ForceVolume {
+    /// 创建影响区域
+    /// * `min`, `max`: 区域的范围，世界坐标
+    /// * `effect`: 对重叠物体的作用
+    pub fn new(min: Vec2, max: Vec2, effect: VolumeEffect) -> ForceVolume {
+        ForceVolume { min, max, effect }
+    }
+
+    #[inline(always)]
+    pub fn min(&self) -> Vec2 {
+        self.min
+    }
+
+    #[inline(always)]
+    pub fn max(&self) -> Vec2 {
+        self.max
+    }
+
+    #[inline(always)]
+    pub fn effect(&self) -> VolumeEffect {
+        self.effect
+    }
+
+    pub fn set_effect(&mut self, effect: VolumeEffect) {
+        self.effect = effect;
+    }
+
+    /// 移动区域，保持大小不变
+    pub fn set_position(&mut self, min: Vec2) {
+        self.max += min - self.min;
+        self.min = min;
+    }
+
+    pub(crate) fn apply(&self, body: &mut Body, dt: Real) {
+        let velocity = match self.effect {
+            VolumeEffect::Force(force) => body.velocity() + force * (body.inverse_mass() * dt),
+            VolumeEffect::Velocity { velocity, rate } => {
+                body.velocity() + (velocity - body.velocity()) * (rate * dt).min(1.)
+            }
+        };
+        body.set_velocity(velocity);
+    }
+}
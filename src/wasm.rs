@@ -1,11 +1,13 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::shape::{ShapeType, AABB};
+use crate::shape::{Capsule, Chain, Point, Polygon, Segment, ShapeError, ShapeType, Triangle, AABB};
 use crate::vec2::Vec2;
-use crate::{body::Body, shape::Circle, world::World};
+use crate::{body::Body, math::Real, shape::Circle, world::World};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 pub struct P2DWorld {
@@ -14,7 +16,7 @@ pub struct P2DWorld {
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 impl P2DWorld {
-    pub fn new(dt: f32, iterations: i32, gravity_scale: f32) -> P2DWorld {
+    pub fn new(dt: Real, iterations: i32, gravity_scale: Real) -> P2DWorld {
         P2DWorld {
             world: World::new(dt, iterations, gravity_scale),
         }
@@ -31,6 +33,14 @@ impl P2DWorld {
             let shape_type = match body.borrow().shape() {
                 ShapeType::AABB(_) => P2DShapeType::AABB,
                 ShapeType::Circle(_) => P2DShapeType::Circle,
+                ShapeType::Polygon(_) => P2DShapeType::Polygon,
+                ShapeType::Triangle(_) => P2DShapeType::Triangle,
+                ShapeType::Capsule(_) => P2DShapeType::Capsule,
+                ShapeType::Segment(_) => P2DShapeType::Segment,
+                ShapeType::Chain(_) => P2DShapeType::Chain,
+                ShapeType::Compound(_) => P2DShapeType::Compound,
+                ShapeType::Custom(_) => P2DShapeType::Custom,
+                ShapeType::Point(_) => P2DShapeType::Point,
             };
             result.push(P2DBody { body: body.clone(), shape_type })
         }
@@ -41,6 +51,105 @@ impl P2DWorld {
     pub fn step(&mut self) {
         self.world.step();
     }
+
+    /// 上一次 step 的所有接触点，每个接触点依次为：
+    /// 物体 A 的下标、物体 B 的下标、接触点的 x、y，
+    /// 从 A 指向 B 的法线的 x、y，侵入量，B 相对 A 的速度的 x、y，共 9 个数
+    pub fn get_contacts(&self) -> Vec<Real> {
+        self.world
+            .contacts()
+            .iter()
+            .flat_map(|c| {
+                [
+                    c.a as Real,
+                    c.b as Real,
+                    c.point.x,
+                    c.point.y,
+                    c.normal.x,
+                    c.normal.y,
+                    c.penetration,
+                    c.relative_velocity.x,
+                    c.relative_velocity.y,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// 管理多个互相独立的物理世界，每个世界用创建时返回的 id 访问，
+/// 同一个页面中的多个画布可以共用一个 wasm 实例。使用不存在的 id 的操作会被忽略
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Default)]
+pub struct P2DEngine {
+    worlds: HashMap<u32, P2DWorld>,
+    next_id: u32,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl P2DEngine {
+    pub fn new() -> P2DEngine {
+        P2DEngine::default()
+    }
+
+    /// 创建一个新的世界，返回它的 id，参数与 `P2DWorld::new` 相同。
+    /// id 依次递增，用完所有 id 之后从 0 开始重新分配，跳过仍然存在的世界
+    pub fn create_world(&mut self, dt: Real, iterations: i32, gravity_scale: Real) -> u32 {
+        let mut id = self.next_id;
+        while self.worlds.contains_key(&id) {
+            id = id.wrapping_add(1);
+        }
+        self.next_id = id.wrapping_add(1);
+        self.worlds.insert(id, P2DWorld::new(dt, iterations, gravity_scale));
+        id
+    }
+
+    /// 销毁世界，世界存在时返回 true
+    pub fn destroy_world(&mut self, id: u32) -> bool {
+        self.worlds.remove(&id).is_some()
+    }
+
+    pub fn has_world(&self, id: u32) -> bool {
+        self.worlds.contains_key(&id)
+    }
+
+    pub fn world_count(&self) -> usize {
+        self.worlds.len()
+    }
+
+    /// 所有世界的 id，从小到大排列
+    pub fn world_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.worlds.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn add_body(&mut self, id: u32, p2d_body: P2DBody) {
+        if let Some(world) = self.worlds.get_mut(&id) {
+            world.add_body(p2d_body);
+        }
+    }
+
+    pub fn get_bodies(&self, id: u32) -> Vec<P2DBody> {
+        self.worlds.get(&id).map_or_else(Vec::new, P2DWorld::get_bodies)
+    }
+
+    /// 参见 `P2DWorld::get_contacts`
+    pub fn get_contacts(&self, id: u32) -> Vec<Real> {
+        self.worlds.get(&id).map_or_else(Vec::new, P2DWorld::get_contacts)
+    }
+
+    pub fn step(&mut self, id: u32) {
+        if let Some(world) = self.worlds.get_mut(&id) {
+            world.step();
+        }
+    }
+
+    /// 每个世界各执行一次 step
+    pub fn step_all(&mut self) {
+        for world in self.worlds.values_mut() {
+            world.step();
+        }
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -48,12 +157,20 @@ impl P2DWorld {
 pub enum P2DShapeType {
     Circle,
     AABB,
+    Polygon,
+    Triangle,
+    Capsule,
+    Segment,
+    Chain,
+    Compound,
+    Custom,
+    Point,
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Copy, Clone)]
 pub struct P2DCircle {
-    pub radius: f32
+    pub radius: Real
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -63,6 +180,21 @@ pub struct P2DAABB {
     pub max: Vec2,
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy)]
+pub struct P2DCapsule {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub radius: Real,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy)]
+pub struct P2DSegment {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 pub struct P2DBody {
     pub(crate) body: Rc<RefCell<Body>>,
@@ -71,7 +203,7 @@ pub struct P2DBody {
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 impl P2DBody {
-    pub fn new_circle(radius: f32, position: Vec2, restitution: f32) -> P2DBody {
+    pub fn new_circle(radius: Real, position: Vec2, restitution: Real) -> P2DBody {
         P2DBody {
             body: Rc::new(RefCell::new(Body::new_circle(
                 Circle::new(radius),
@@ -82,7 +214,19 @@ impl P2DBody {
         }
     }
 
-    pub fn new_aabb(min: Vec2, max: Vec2, position: Vec2, restitution: f32) -> P2DBody {
+    pub fn new_point(mass: Real, position: Vec2, restitution: Real) -> P2DBody {
+        P2DBody {
+            body: Rc::new(RefCell::new(Body::new_point(
+                Point::new(mass),
+                position,
+                restitution,
+            ))),
+            shape_type: P2DShapeType::Point,
+        }
+    }
+
+    /// `min`、`max` 是相对于 `position` 的偏移
+    pub fn new_aabb(min: Vec2, max: Vec2, position: Vec2, restitution: Real) -> P2DBody {
         P2DBody {
             body: Rc::new(RefCell::new(Body::new_aabb(
                 AABB::new(min, max),
@@ -93,6 +237,85 @@ impl P2DBody {
         }
     }
 
+    /// `vertices` 依次为各个顶点的 x、y 坐标，顶点不能组成凸多边形时抛出错误，参见 `Polygon::try_new`
+    pub fn new_polygon(
+        vertices: Vec<Real>,
+        position: Vec2,
+        restitution: Real,
+    ) -> Result<P2DBody, JsValue> {
+        let points: Vec<Vec2> = vertices
+            .chunks_exact(2)
+            .map(|p| Vec2::new(p[0], p[1]))
+            .collect();
+        let polygon = Polygon::try_new(&points).map_err(shape_error)?;
+        Ok(P2DBody {
+            body: Rc::new(RefCell::new(Body::new_polygon(polygon, position, restitution))),
+            shape_type: P2DShapeType::Polygon,
+        })
+    }
+
+    /// 三个顶点共线时抛出错误，参见 `Triangle::try_new`
+    pub fn new_triangle(
+        a: Vec2,
+        b: Vec2,
+        c: Vec2,
+        position: Vec2,
+        restitution: Real,
+    ) -> Result<P2DBody, JsValue> {
+        let triangle = Triangle::try_new(a, b, c).map_err(shape_error)?;
+        Ok(P2DBody {
+            body: Rc::new(RefCell::new(Body::new_triangle(triangle, position, restitution))),
+            shape_type: P2DShapeType::Triangle,
+        })
+    }
+
+    /// 半径不是正数时抛出错误，参见 `Capsule::try_new`
+    pub fn new_capsule(
+        a: Vec2,
+        b: Vec2,
+        radius: Real,
+        position: Vec2,
+        restitution: Real,
+    ) -> Result<P2DBody, JsValue> {
+        let capsule = Capsule::try_new(a, b, radius).map_err(shape_error)?;
+        Ok(P2DBody {
+            body: Rc::new(RefCell::new(Body::new_capsule(capsule, position, restitution))),
+            shape_type: P2DShapeType::Capsule,
+        })
+    }
+
+    /// 线段总是静态的，两个端点重合时抛出错误，参见 `Segment::try_new`
+    pub fn new_segment(
+        a: Vec2,
+        b: Vec2,
+        position: Vec2,
+        restitution: Real,
+    ) -> Result<P2DBody, JsValue> {
+        let segment = Segment::try_new(a, b).map_err(shape_error)?;
+        Ok(P2DBody {
+            body: Rc::new(RefCell::new(Body::new_segment(segment, position, restitution))),
+            shape_type: P2DShapeType::Segment,
+        })
+    }
+
+    /// 折线总是静态的，`points` 依次为各个顶点的 x、y 坐标，
+    /// 顶点少于 2 个时抛出错误，参见 `Chain::try_new`
+    pub fn new_chain(
+        points: Vec<Real>,
+        position: Vec2,
+        restitution: Real,
+    ) -> Result<P2DBody, JsValue> {
+        let points: Vec<Vec2> = points
+            .chunks_exact(2)
+            .map(|p| Vec2::new(p[0], p[1]))
+            .collect();
+        let chain = Chain::try_new(&points).map_err(shape_error)?;
+        Ok(P2DBody {
+            body: Rc::new(RefCell::new(Body::new_chain(chain, position, restitution))),
+            shape_type: P2DShapeType::Chain,
+        })
+    }
+
     pub fn make_static(&mut self) {
         self.body.borrow_mut().make_static();
     }
@@ -122,8 +345,221 @@ impl P2DBody {
         }
     }
 
+    /// 多边形在物体局部坐标系中的顶点，依次为各个顶点的 x、y 坐标
+    pub fn get_polygon(&self) -> Vec<Real> {
+        match self.body.borrow().shape() {
+            ShapeType::Polygon(polygon) => {
+                polygon.vertices().iter().flat_map(|v| [v.x, v.y]).collect()
+            }
+            _ => panic!("Invalid call for get polygon"),
+        }
+    }
+
+    /// 三角形在物体局部坐标系中的顶点，依次为各个顶点的 x、y 坐标
+    pub fn get_triangle(&self) -> Vec<Real> {
+        match self.body.borrow().shape() {
+            ShapeType::Triangle(triangle) => {
+                triangle.vertices().iter().flat_map(|v| [v.x, v.y]).collect()
+            }
+            _ => panic!("Invalid call for get triangle"),
+        }
+    }
+
+    pub fn get_capsule(&self) -> P2DCapsule {
+        match self.body.borrow().shape() {
+            ShapeType::Capsule(capsule) => P2DCapsule {
+                a: capsule.a(),
+                b: capsule.b(),
+                radius: capsule.radius(),
+            },
+            _ => panic!("Invalid call for get capsule"),
+        }
+    }
+
+    pub fn get_segment(&self) -> P2DSegment {
+        match self.body.borrow().shape() {
+            ShapeType::Segment(segment) => P2DSegment {
+                a: segment.a(),
+                b: segment.b(),
+            },
+            _ => panic!("Invalid call for get segment"),
+        }
+    }
+
+    /// 折线在物体局部坐标系中的顶点，依次为各个顶点的 x、y 坐标
+    pub fn get_chain(&self) -> Vec<Real> {
+        match self.body.borrow().shape() {
+            ShapeType::Chain(chain) => chain.points().iter().flat_map(|v| [v.x, v.y]).collect(),
+            _ => panic!("Invalid call for get chain"),
+        }
+    }
+
+    pub fn get_rotation(&self) -> Real {
+        self.body.borrow().rotation().angle()
+    }
+
     pub fn is_static(&self) -> bool {
         self.body.borrow().is_static()
     }
 }
 
+// 形状参数不合法时抛给 JS 的错误
+fn shape_error(error: ShapeError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test cases for:
+    /// * P2DWorld::get_contacts()
+    #[test]
+    fn contacts_should_be_flattened_with_relative_velocity() {
+        let mut world = P2DWorld::new(1. / 60., 10, 1.);
+        let mut ground = P2DBody::new_aabb(
+            Vec2::new(-100., -5.),
+            Vec2::new(100., 5.),
+            Vec2::new(0., 50.),
+            0.5,
+        );
+        ground.make_static();
+        world.add_body(ground);
+        let ball = P2DBody::new_circle(1., Vec2::new(0., 44.05), 0.);
+        ball.body.borrow_mut().set_velocity(Vec2::new(3., 5.));
+        world.add_body(ball);
+        assert!(world.get_contacts().is_empty());
+
+        world.step();
+        let contacts = world.get_contacts();
+        assert_eq!(contacts.len(), 9);
+        assert_eq!(&contacts[..2], &[0., 1.]);
+        assert!((contacts[2] - 0.).abs() < 1e-4 && (contacts[3] - 45.).abs() < 1e-4);
+        assert_eq!(&contacts[4..6], &[0., -1.]);
+        assert!((contacts[6] - 0.05).abs() < 1e-4);
+        // 接触点在求解之前记录，相对速度就是球的速度
+        assert_eq!(&contacts[7..], &[3., 5.]);
+    }
+
+    /// Test cases for:
+    /// * P2DEngine::create_world(Real, i32, Real)
+    /// * P2DEngine::destroy_world(u32)
+    /// * P2DEngine::step_all()
+    #[test]
+    fn engine_should_keep_worlds_independent() {
+        let mut engine = P2DEngine::new();
+        let first = engine.create_world(1. / 60., 10, 1.);
+        let second = engine.create_world(1. / 60., 10, 0.);
+        assert_ne!(first, second);
+        assert_eq!(engine.world_ids(), vec![first, second]);
+        for id in [first, second] {
+            engine.add_body(id, P2DBody::new_circle(1., Vec2::new(0., 0.), 0.5));
+        }
+        engine.step_all();
+        engine.step(first);
+        assert!(engine.get_bodies(first)[0].get_position().y > 0.);
+        assert_eq!(engine.get_bodies(second)[0].get_position(), Vec2::new(0., 0.));
+
+        assert!(engine.destroy_world(first));
+        assert!(!engine.destroy_world(first));
+        assert!(!engine.has_world(first));
+        assert_eq!(engine.world_count(), 1);
+        // 已销毁的 id 不会被复用，对不存在的世界的操作被忽略
+        assert_eq!(engine.create_world(1. / 60., 10, 1.), second + 1);
+        engine.add_body(first, P2DBody::new_circle(1., Vec2::new(0., 0.), 0.5));
+        engine.step(first);
+        assert!(engine.get_bodies(first).is_empty());
+        assert!(engine.get_contacts(first).is_empty());
+    }
+
+    /// Test cases for:
+    /// * P2DBody::new_polygon(Vec<Real>, Vec2, Real)
+    #[test]
+    fn polygon_should_be_created_from_flat_vertices() {
+        let body = P2DBody::new_polygon(vec![0., 0., 2., 0., 2., 2., 0., 2.], Vec2::ZERO, 0.5)
+            .unwrap();
+        assert_eq!(body.get_polygon(), vec![-1., -1., 1., -1., 1., 1., -1., 1.]);
+    }
+
+    /// Test cases for:
+    /// * P2DBody::new_capsule(Vec2, Vec2, Real, Vec2, Real)
+    #[test]
+    fn capsule_should_be_created_with_positive_radius() {
+        let body = P2DBody::new_capsule(
+            Vec2::new(0., -1.),
+            Vec2::new(0., 1.),
+            0.5,
+            Vec2::new(3., 4.),
+            0.5,
+        )
+        .unwrap();
+        let capsule = body.get_capsule();
+        assert_eq!((capsule.a, capsule.b), (Vec2::new(0., -1.), Vec2::new(0., 1.)));
+        assert_eq!(capsule.radius, 0.5);
+        assert_eq!(body.get_position(), Vec2::new(3., 4.));
+    }
+
+    /// Test cases for:
+    /// * P2DBody::new_segment(Vec2, Vec2, Vec2, Real)
+    #[test]
+    fn segment_should_be_static() {
+        let body =
+            P2DBody::new_segment(Vec2::new(-1., 0.), Vec2::new(1., 0.), Vec2::ZERO, 0.5).unwrap();
+        assert!(body.is_static());
+        assert_eq!(body.get_segment().b, Vec2::new(1., 0.));
+    }
+
+    /// Test cases for:
+    /// * P2DBody::new_chain(Vec<Real>, Vec2, Real)
+    #[test]
+    fn chain_should_be_created_from_flat_points() {
+        let body = P2DBody::new_chain(vec![-1., 0., 0., 1., 1., 0.], Vec2::ZERO, 0.5).unwrap();
+        assert!(body.is_static());
+        assert_eq!(body.get_chain(), vec![-1., 0., 0., 1., 1., 0.]);
+    }
+
+    /// Test cases for:
+    /// * P2DBody::new_triangle(Vec2, Vec2, Vec2, Vec2, Real)
+    #[test]
+    fn triangle_should_be_centered_on_centroid() {
+        let body = P2DBody::new_triangle(
+            Vec2::new(0., 0.),
+            Vec2::new(3., 0.),
+            Vec2::new(0., 3.),
+            Vec2::ZERO,
+            0.5,
+        )
+        .unwrap();
+        assert_eq!(body.get_triangle(), vec![-1., -1., 2., -1., -1., 2.]);
+    }
+
+    /// Test cases for:
+    /// * P2DEngine::create_world(Real, i32, Real)
+    #[test]
+    fn engine_should_skip_live_ids_after_wrapping() {
+        let mut engine = P2DEngine::new();
+        let first = engine.create_world(1. / 60., 10, 1.);
+        engine.add_body(first, P2DBody::new_circle(1., Vec2::new(0., 0.), 0.5));
+        engine.next_id = u32::MAX;
+        let last = engine.create_world(1. / 60., 10, 1.);
+        assert_eq!(last, u32::MAX);
+        // 0 仍然被第一个世界使用
+        let wrapped = engine.create_world(1. / 60., 10, 1.);
+        assert_eq!(wrapped, 1);
+        assert_eq!(engine.world_ids(), vec![first, wrapped, last]);
+        engine.step_all();
+        assert_eq!(engine.get_bodies(first).len(), 1);
+        assert!(engine.get_bodies(wrapped).is_empty());
+
+        for id in [first, wrapped, last] {
+            assert!(engine.destroy_world(id));
+        }
+        assert_eq!(engine.world_count(), 0);
+        // 不存在的 id 不会 panic
+        engine.step(first);
+        engine.add_body(last, P2DBody::new_circle(1., Vec2::new(0., 0.), 0.5));
+        assert!(engine.get_bodies(last).is_empty());
+        assert!(engine.get_contacts(wrapped).is_empty());
+        assert!(!engine.destroy_world(first));
+    }
+}
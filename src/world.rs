@@ -1,13 +1,85 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
 
-use crate::{body::Body, manifold::Manifold, vec2::Vec2};
+use crate::{
+    body::Body,
+    broad_phase::{BroadPhase, BroadPhaseKind, BroadPhaseState},
+    bvh::StaticBvh,
+    contact::Contact,
+    event::{CollisionEvent, ImpactEvent, PairEvent},
+    hash::{split_mix64, unit_float},
+    history::StepHistory,
+    joint::{AngleConstraint, MouseJoint, TrackConstraint},
+    manifold::{point_velocity, CoincidentPolicy, FrictionModel, Manifold, LINEAR_SLOP},
+    narrow_phase::{CollideFn, NarrowPhase},
+    math::{consts, shortest_angle_between, Real},
+    rot::Rot,
+    shape::{valid_scale_factor, Circle, Ray, RayHit, ShapeKind, ShapeType, AABB},
+    solver::{ContactImpulse, IntegrationScheme, SolverConfig, SolverIteration},
+    stats::{EnergyDrift, IslandStats, StepStats, UnionFind},
+    surface::{classify_normal, SurfaceState},
+    telemetry::Telemetry,
+    template::BodyTemplate,
+    trajectory::TrajectoryParams,
+    validation::ValidationIssue,
+    volume::ForceVolume,
+    vec2::{batch, Vec2},
+};
+
+// 通过 `&World` 提交、在下一次 step 开始时执行的命令
+#[derive(Clone, Copy)]
+enum QueuedCommand {
+    Impulse(usize, Vec2),
+    Force(usize, Vec2),
+}
 
 pub struct World {
-    dt: f32,                        // 每次循环的时间间隔
-    iterations: i32,                // 每次循环迭代次数
-    bodies: Vec<Rc<RefCell<Body>>>, // 场景中的所有物体
-    gravity_scale: f32,             // 重力放大倍数
-    gravity: Vec2,                  // 重力大小
+    dt: Real,                                       // 每次循环的时间间隔
+    iterations: i32,                                // 每次循环迭代次数
+    bodies: Vec<Rc<RefCell<Body>>>,                 // 场景中的所有物体
+    gravity_scale: Real,                            // 重力放大倍数
+    gravity: Vec2,                                  // 重力大小
+    ccd_fraction: Option<Real>,                     // 触发连续碰撞检测的位移比例
+    coincident_policy: CoincidentPolicy,            // 物体重合时的分离策略
+    friction_model: FrictionModel,                  // 摩擦力模型
+    max_depenetration_speed: Option<Real>,          // 位置修正推开物体的最大速度
+    contacts: Vec<Contact>,                         // 上一次 step 的接触点
+    impacts: HashMap<usize, (Real, Real)>,          // 上一次 step 发生碰撞的物体受到的冲量之和与最大冲量
+    static_bvh: Option<Arc<StaticBvh>>,             // 烘焙后的静态物体 BVH
+    touching: HashSet<(usize, usize)>,              // 上一次 step 互相接触的物体对
+    events: Vec<CollisionEvent>,                    // 上一次 step 产生的碰撞事件
+    max_slope: Real,                                // 可以站立的最大坡度
+    drag: Option<MouseJoint>,                       // 拖拽物体使用的鼠标关节
+    tracks: Vec<TrackConstraint>,                   // 轨道约束
+    angle_constraints: Vec<AngleConstraint>,        // 角度约束
+    spawn_jitter: Option<(u64, Real)>,              // 出生位置扰动的种子和幅度
+    stats: StepStats,                               // 上一次 step 的统计信息
+    solver: SolverConfig,                           // 求解器的配置
+    change_epsilon: Real,                           // 判断物体位置变化的阈值
+    last_positions: Vec<Vec2>,                      // 上一次 changed_bodies 时物体的位置
+    marked_changed: HashSet<usize>,                 // 被手动标记为变化的物体
+    telemetry: Option<Telemetry>,                   // 选定物体的位置和速度记录
+    length_scale: Real,                             // 每米对应的长度单位数
+    impact_events: Vec<ImpactEvent>,                // 上一次 step 中新接触的碰撞冲击
+    history: Option<StepHistory>,                   // 最近若干次 step 之前的物体状态
+    step_count: u64,                                // 已经执行的 step 次数
+    activation_regions: Vec<(Vec2, Vec2)>,          // 区域内的物体总是以完整频率更新
+    queued: RefCell<Vec<QueuedCommand>>,            // 等待下一次 step 开始时施加的冲量和力
+    active_regions: Vec<(Vec2, Vec2)>,              // 不为空时只模拟与区域重叠的物体
+    gravity_ramp: Option<(Vec2, Vec2, Real, Real)>, // 重力渐变的起点、终点、时长和已经过的时间
+    volumes: Vec<ForceVolume>,                      // 影响区域
+    templates: Vec<Rc<BodyTemplate>>,               // 注册的物体模板
+    integration_scheme: IntegrationScheme,          // 速度和位置的积分方式
+    narrow_phase: Rc<NarrowPhase>,                  // 窄检测的自定义碰撞函数
+    broad_phase: RefCell<BroadPhaseState>,          // 粗测阶段使用的数据结构
+    pair_events: Vec<PairEvent>,                    // 上一次 step 中粗测阶段物体对的变化
+    unbaked: Vec<usize>,                            // 没有烘焙的物体的下标，step 只遍历这些物体
+    batch_positions: Vec<Vec2>,                     // 批量积分使用的位置缓冲区，在 step 之间复用
+    batch_velocities: Vec<Vec2>,                    // 批量积分使用的速度缓冲区，在 step 之间复用
 }
 
 impl World {
@@ -15,14 +87,812 @@ impl World {
     /// * `dt`: 物理世界的更新频率
     /// * `iterations`: 每次 step 的循环次数
     /// * `gravity_scale`: 重力放大倍数
-    pub fn new(dt: f32, iterations: i32, gravity_scale: f32) -> World {
+    pub fn new(dt: Real, iterations: i32, gravity_scale: Real) -> World {
         World {
             dt,
             iterations,
             bodies: vec![],
-            gravity_scale: gravity_scale,
+            gravity_scale,
             gravity: Vec2::new(0., 10.0 * gravity_scale),
+            ccd_fraction: Some(0.5),
+            coincident_policy: CoincidentPolicy::default(),
+            friction_model: FrictionModel::default(),
+            max_depenetration_speed: None,
+            contacts: vec![],
+            impacts: HashMap::new(),
+            static_bvh: None,
+            touching: HashSet::new(),
+            events: vec![],
+            max_slope: crate::math::consts::FRAC_PI_4,
+            drag: None,
+            tracks: vec![],
+            angle_constraints: vec![],
+            spawn_jitter: None,
+            stats: StepStats::default(),
+            solver: SolverConfig::default(),
+            change_epsilon: 0.0001,
+            last_positions: vec![],
+            marked_changed: HashSet::new(),
+            telemetry: None,
+            length_scale: 1.,
+            impact_events: vec![],
+            pair_events: vec![],
+            unbaked: vec![],
+            batch_positions: vec![],
+            batch_velocities: vec![],
+            history: None,
+            step_count: 0,
+            activation_regions: vec![],
+            queued: RefCell::new(vec![]),
+            active_regions: vec![],
+            gravity_ramp: None,
+            volumes: vec![],
+            templates: vec![],
+            integration_scheme: IntegrationScheme::default(),
+            narrow_phase: Rc::new(NarrowPhase::new()),
+            broad_phase: RefCell::new(BroadPhaseState::new(BroadPhaseKind::default().create(), 0.1)),
+        }
+    }
+
+    /// 返回自上一次调用以来位置变化超过阈值、或者被 `mark_changed` 标记过的物体下标，
+    /// 新加入的物体也算作变化。渲染和网络同步可以只处理这些物体
+    pub fn changed_bodies(&mut self) -> Vec<usize> {
+        let epsilon_sqr = self.change_epsilon * self.change_epsilon;
+        let mut changed = vec![];
+        for (i, body) in self.bodies.iter().enumerate() {
+            let position = body.borrow().position();
+            let moved = self
+                .last_positions
+                .get(i)
+                .is_none_or(|last| last.distance_squared(position) > epsilon_sqr);
+            if moved || self.marked_changed.contains(&i) {
+                changed.push(i);
+            }
+        }
+        for &i in &changed {
+            let position = self.bodies[i].borrow().position();
+            match self.last_positions.get_mut(i) {
+                Some(last) => *last = position,
+                None => self.last_positions.push(position),
+            }
+        }
+        self.marked_changed.clear();
+        changed
+    }
+
+    /// 标记物体发生了变化，例如修改了形状或者材质，下一次 `changed_bodies` 会包含该物体
+    pub fn mark_changed(&mut self, body: usize) {
+        self.marked_changed.insert(body);
+    }
+
+    /// 设置 `changed_bodies` 判断位置变化的阈值，默认为 0.0001
+    pub fn set_change_epsilon(&mut self, epsilon: Real) {
+        self.change_epsilon = epsilon;
+    }
+
+    /// 开始记录物体的位置和速度，每次 step 结束时采样一次，会替换之前的记录
+    pub fn set_telemetry(&mut self, telemetry: Telemetry) {
+        self.telemetry = Some(telemetry);
+    }
+
+    /// 停止记录，并返回已有的记录
+    pub fn take_telemetry(&mut self) -> Option<Telemetry> {
+        self.telemetry.take()
+    }
+
+    /// 获取物体位置和速度的记录
+    pub fn telemetry(&self) -> Option<&Telemetry> {
+        self.telemetry.as_ref()
+    }
+
+    /// 获取物体位置和速度的记录，可以用来增加或者移除要记录的物体
+    pub fn telemetry_mut(&mut self) -> Option<&mut Telemetry> {
+        self.telemetry.as_mut()
+    }
+
+    /// 添加一个激活区域，包围盒与区域重叠的物体总是以完整频率更新，
+    /// 不受 `Body::set_update_interval` 的影响。通常放在相机或者玩家附近
+    pub fn add_activation_region(&mut self, min: Vec2, max: Vec2) {
+        self.activation_regions.push((min, max));
+    }
+
+    /// 移除所有激活区域
+    pub fn clear_activation_regions(&mut self) {
+        self.activation_regions.clear();
+    }
+
+    /// 设置模拟区域，`AABB` 的 `min`、`max` 为世界坐标。
+    /// 包围盒不与任何区域重叠的非静态物体被冻结：不积分、不参与碰撞检测，保留原来的速度，
+    /// 直到再次与某个区域重叠。适合只模拟相机附近的超大关卡。传入空的切片模拟所有物体
+    pub fn set_active_regions(&mut self, regions: &[AABB]) {
+        self.active_regions = regions.iter().map(|r| (r.min(), r.max())).collect();
+    }
+
+    /// 移除所有模拟区域，所有物体都参与模拟
+    pub fn clear_active_regions(&mut self) {
+        self.active_regions.clear();
+    }
+
+    /// 开启历史记录，`step` 会自动保存最近 `capacity` 次 step 之前所有物体的位置、速度和朝向，
+    /// 之后可以用 `rewind` 回退。传入 0 关闭历史记录
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history = (capacity > 0).then(|| StepHistory::new(capacity));
+    }
+
+    /// 当前可以回退的 step 次数
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, StepHistory::len)
+    }
+
+    /// 把所有物体恢复到 `steps` 次 step 之前的状态，返回实际回退的次数，
+    /// 历史记录不足时回退到最早的记录。回退之后的历史记录会被丢弃，
+    /// 记录之后才加入的物体不受影响
+    pub fn rewind(&mut self, steps: usize) -> usize {
+        match &mut self.history {
+            Some(history) => history.rewind(steps, &self.bodies),
+            None => 0,
+        }
+    }
+
+    /// 获取求解器的配置
+    pub fn solver_config(&self) -> &SolverConfig {
+        &self.solver
+    }
+
+    /// 设置求解器的配置
+    pub fn set_solver_config(&mut self, config: SolverConfig) {
+        self.solver = config;
+    }
+
+    /// 获取上一次 step 的统计信息
+    pub fn stats(&self) -> &StepStats {
+        &self.stats
+    }
+
+    /// 复制一个 world，用于推演（AI 预判、回滚后重新模拟等）
+    /// 烘焙过的只读静态物体和静态 BVH 在两个 world 之间共享，不会复制；
+    /// 其余物体都会深拷贝，在新 world 中的修改不会影响原来的 world。
+    /// 物体的速度回调无法复制，新 world 中的物体没有速度回调
+    pub fn fork(&self) -> World {
+        let bodies = self
+            .bodies
+            .iter()
+            .map(|body| {
+                if body.borrow().is_read_only() {
+                    body.clone()
+                } else {
+                    Rc::new(RefCell::new(body.borrow().fork()))
+                }
+            })
+            .collect();
+        World {
+            dt: self.dt,
+            iterations: self.iterations,
+            bodies,
+            gravity_scale: self.gravity_scale,
+            gravity: self.gravity,
+            ccd_fraction: self.ccd_fraction,
+            coincident_policy: self.coincident_policy,
+            friction_model: self.friction_model,
+            max_depenetration_speed: self.max_depenetration_speed,
+            contacts: self.contacts.clone(),
+            impacts: self.impacts.clone(),
+            static_bvh: self.static_bvh.clone(),
+            touching: self.touching.clone(),
+            events: self.events.clone(),
+            max_slope: self.max_slope,
+            drag: self.drag,
+            tracks: self.tracks.clone(),
+            angle_constraints: self.angle_constraints.clone(),
+            spawn_jitter: self.spawn_jitter,
+            stats: self.stats.clone(),
+            solver: self.solver,
+            change_epsilon: self.change_epsilon,
+            last_positions: self.last_positions.clone(),
+            marked_changed: self.marked_changed.clone(),
+            telemetry: self.telemetry.clone(),
+            length_scale: self.length_scale,
+            impact_events: self.impact_events.clone(),
+            pair_events: self.pair_events.clone(),
+            unbaked: self.unbaked.clone(),
+            batch_positions: vec![],
+            batch_velocities: vec![],
+            history: self.history.clone(),
+            step_count: self.step_count,
+            activation_regions: self.activation_regions.clone(),
+            queued: self.queued.clone(),
+            active_regions: self.active_regions.clone(),
+            gravity_ramp: self.gravity_ramp,
+            volumes: self.volumes.clone(),
+            templates: self.templates.clone(),
+            integration_scheme: self.integration_scheme,
+            narrow_phase: self.narrow_phase.clone(),
+            broad_phase: self.broad_phase.clone(),
+        }
+    }
+
+    /// 预测一个只受重力和阻力（`TrajectoryParams::drag`）作用的物体接下来 `steps` 次 step 的位置
+    /// 使用与 `step` 相同的积分方式，返回的第一个点是初始位置。
+    /// 开启碰撞检测时，把每一次 step 的位移当作一条射线检测碰撞，
+    /// 轨迹在第一个碰撞点结束
+    pub fn predict_trajectory(&self, params: TrajectoryParams, steps: usize) -> Vec<Vec2> {
+        self.predict(params, steps, |v| -v * params.drag)
+    }
+
+    /// 预测 world 中某个物体接下来 `steps` 次 step 的位置，参见 `World::predict_trajectory`。
+    /// 物体设置的加速度函数（`Body::set_acceleration`）也参与积分
+    pub fn predict_body_trajectory(&self, body: usize, steps: usize, collide: bool) -> Vec<Vec2> {
+        let Some(b) = self.bodies.get(body) else {
+            return vec![];
+        };
+        let b = b.borrow();
+        let params = TrajectoryParams {
+            position: b.position(),
+            velocity: b.velocity(),
+            drag: 0.,
+            collide,
+            ignore: Some(body),
+        };
+        let angular_velocity = b.angular_velocity();
+        let acceleration = b.acceleration_fn();
+        drop(b);
+        self.predict(params, steps, |v| {
+            acceleration.as_ref().map_or(Vec2::ZERO, |f| f(v, angular_velocity))
+        })
+    }
+
+    // 按照 `integration_scheme` 积分重力和额外的加速度 `acceleration`（参数为当前速度）
+    fn predict<F>(&self, params: TrajectoryParams, steps: usize, acceleration: F) -> Vec<Vec2>
+    where
+        F: Fn(Vec2) -> Vec2,
+    {
+        let (before, after) = self.integration_scheme.kick_fractions();
+        let mut path = Vec::with_capacity(steps + 1);
+        let mut position = params.position;
+        let mut velocity = params.velocity;
+        path.push(position);
+        for _ in 0..steps {
+            velocity += (self.gravity + acceleration(velocity)) * (self.dt * before);
+            let next = position + velocity * self.dt;
+            if after != 0. {
+                velocity += (self.gravity + acceleration(velocity)) * (self.dt * after);
+            }
+            if params.collide {
+                let hit = self
+                    .bodies
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, body)| {
+                        let body = body.borrow();
+                        Some(*i) != params.ignore && !body.is_sensor() && !body.is_occluder()
+                    })
+                    .filter_map(|(_, body)| body.borrow().ray_cast(position, next - position, 1.))
+                    .min_by(|a, b| a.total_cmp(b));
+                if let Some(t) = hit {
+                    path.push(position + (next - position) * t);
+                    break;
+                }
+            }
+            position = next;
+            path.push(position);
+        }
+        path
+    }
+
+    /// 添加一个轨道约束，返回约束的下标
+    pub fn add_track_constraint(&mut self, constraint: TrackConstraint) -> usize {
+        self.tracks.push(constraint);
+        self.tracks.len() - 1
+    }
+
+    /// 获取 world 中所有轨道约束
+    pub fn track_constraints(&self) -> &[TrackConstraint] {
+        &self.tracks
+    }
+
+    /// 移除所有轨道约束
+    pub fn clear_track_constraints(&mut self) {
+        self.tracks.clear();
+    }
+
+    /// 添加一个角度约束，返回约束的下标
+    pub fn add_angle_constraint(&mut self, constraint: AngleConstraint) -> usize {
+        self.angle_constraints.push(constraint);
+        self.angle_constraints.len() - 1
+    }
+
+    /// 获取 world 中所有角度约束
+    pub fn angle_constraints(&self) -> &[AngleConstraint] {
+        &self.angle_constraints
+    }
+
+    /// 获取 world 中所有角度约束，用于修改目标角度
+    pub fn angle_constraints_mut(&mut self) -> &mut [AngleConstraint] {
+        &mut self.angle_constraints
+    }
+
+    /// 移除所有角度约束
+    pub fn clear_angle_constraints(&mut self) {
+        self.angle_constraints.clear();
+    }
+
+    /// 添加影响区域，返回区域的下标
+    pub fn add_volume(&mut self, volume: ForceVolume) -> usize {
+        self.volumes.push(volume);
+        self.volumes.len() - 1
+    }
+
+    pub fn volumes(&self) -> &[ForceVolume] {
+        &self.volumes
+    }
+
+    pub fn volumes_mut(&mut self) -> &mut [ForceVolume] {
+        &mut self.volumes
+    }
+
+    /// 移除所有影响区域
+    pub fn clear_volumes(&mut self) {
+        self.volumes.clear();
+    }
+
+    /// 开始拖拽 `point` 下最上层（最后加入 world）的非静态物体
+    /// 返回被拖拽物体的下标，`point` 下没有可以拖拽的物体时返回 `None`
+    pub fn start_drag(&mut self, point: Vec2) -> Option<usize> {
+        let (index, body) = self.bodies.iter().enumerate().rev().find(|(_, body)| {
+            let body = body.borrow();
+            !body.is_static() && body.contains_point(point)
+        })?;
+        let body = body.borrow();
+        // 最大拉力与物体重量成正比，保证重的物体也能被拖动
+        let max_force = 1000. * body.mass() * self.gravity.length().max(1.);
+        self.drag = Some(MouseJoint::new(index, body.transform(), point, max_force));
+        Some(index)
+    }
+
+    /// 更新拖拽的目标点
+    pub fn update_drag(&mut self, point: Vec2) {
+        if let Some(joint) = &mut self.drag {
+            joint.set_target(point);
+        }
+    }
+
+    /// 结束拖拽
+    pub fn end_drag(&mut self) {
+        self.drag = None;
+    }
+
+    /// 当前的拖拽关节，可以用来调整弹簧参数
+    pub fn drag_joint(&mut self) -> Option<&mut MouseJoint> {
+        self.drag.as_mut()
+    }
+
+    /// 与重力方向相反的单位向量，没有重力时返回 `None`
+    pub fn up(&self) -> Option<Vec2> {
+        (-self.gravity).try_normalize()
+    }
+
+    /// 设置可以站立的最大坡度，单位为弧度，默认为 45°
+    pub fn set_max_slope(&mut self, max_slope: Real) {
+        self.max_slope = max_slope;
+    }
+
+    /// 根据上一次 step 的接触点，判断物体是否站在地面上、贴着墙壁或者顶着天花板
+    pub fn surface_state(&self, body: usize) -> SurfaceState {
+        let mut state = SurfaceState::default();
+        let Some(up) = self.up() else {
+            return state;
+        };
+        for contact in &self.contacts {
+            // 碰撞法线从 A 指向 B，对 A 来说接触面的法线方向相反
+            let normal = if contact.b == body {
+                contact.normal
+            } else if contact.a == body {
+                -contact.normal
+            } else {
+                continue;
+            };
+            state.add(classify_normal(normal, up, self.max_slope));
+        }
+        state
+    }
+
+    /// 射线检测，射线为 `origin + dir * t`，`t` 在 `[0, max_t]` 之间。
+    /// 返回第一个被击中的物体的下标和对应的 `t`，传感器不会被击中
+    pub fn ray_cast(&self, origin: Vec2, dir: Vec2, max_t: Real) -> Option<(usize, Real)> {
+        self.ray_cast_hit(origin, dir, max_t).map(|hit| (hit.body, hit.t))
+    }
+
+    /// 与 `ray_cast` 相同，同时返回被击中的子形状
+    pub fn ray_cast_hit(&self, origin: Vec2, dir: Vec2, max_t: Real) -> Option<RayHit> {
+        let mut closest: Option<RayHit> = None;
+        self.ray_cast_with(origin, dir, max_t, |hit| {
+            // `t` 相同时取下标较小的物体，结果与访问顺序无关
+            if closest.is_none_or(|closest| (hit.t, hit.body) < (closest.t, closest.body)) {
+                closest = Some(hit);
+            }
+            true
+        });
+        closest
+    }
+
+    /// 视线查询，`from` 与 `to` 之间没有被任何物体（包括遮挡物，不包括传感器）挡住时返回 true。
+    /// `ignore` 中的物体不会挡住视线，通常是观察者和目标自己
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2, ignore: &[usize]) -> bool {
+        let mut visible = true;
+        self.ray_cast_with(from, to - from, 1., |hit| {
+            visible = ignore.contains(&hit.body);
+            visible
+        });
+        visible
+    }
+
+    /// 与 `ray_cast_hit` 类似，但对每个被击中的物体调用 `callback`，不分配结果列表。
+    /// 物体的访问顺序由粗测结构决定，不按 `t` 排序，`callback` 返回 false 时停止查询
+    pub fn ray_cast_with<F>(&self, origin: Vec2, dir: Vec2, max_t: Real, mut callback: F)
+    where
+        F: FnMut(RayHit) -> bool,
+    {
+        let ray = Ray::new(origin, dir, max_t);
+        self.visit_candidates(
+            |broad_phase, visit| broad_phase.ray_query(origin, dir, max_t, visit),
+            |bvh, visit| bvh.ray_query(origin, dir, max_t, visit),
+            |i| {
+                let body = self.bodies[i].borrow();
+                if body.is_sensor() {
+                    return true;
+                }
+                match body.ray_cast_sub_shape(ray) {
+                    Some((t, sub_shape)) => callback(RayHit { body: i, t, sub_shape }),
+                    None => true,
+                }
+            },
+        );
+    }
+
+    /// 区域查询，返回包围盒与 `[min, max]` 重叠的所有物体的下标，从小到大排列
+    pub fn query_region(&self, min: Vec2, max: Vec2) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.query_region_with(min, max, |i| {
+            result.push(i);
+            true
+        });
+        result.sort_unstable();
+        result
+    }
+
+    /// 与 `query_region` 相同，但对每个物体的下标调用 `callback`，不分配结果列表。
+    /// 物体的访问顺序由粗测结构决定，`callback` 返回 false 时停止查询
+    pub fn query_region_with<F>(&self, min: Vec2, max: Vec2, mut callback: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.visit_candidates(
+            |broad_phase, visit| broad_phase.query(min, max, visit),
+            |bvh, visit| bvh.query(min, max, visit),
+            |i| {
+                let (body_min, body_max) = self.bodies[i].borrow().bounds();
+                let overlaps = body_min.x <= max.x
+                    && body_max.x >= min.x
+                    && body_min.y <= max.y
+                    && body_max.y >= min.y;
+                !overlaps || callback(i)
+            },
+        );
+    }
+
+    // 先把物体的变化同步到粗测结构中，再用 `query` 和 `static_query` 分别查询粗测结构和烘焙的静态 BVH，
+    // 对每个候选物体调用 `visit`，`visit` 返回 false 时停止
+    fn visit_candidates<Q, S, F>(&self, query: Q, static_query: S, mut visit: F)
+    where
+        Q: FnOnce(&dyn BroadPhase, &mut dyn FnMut(usize) -> bool),
+        S: FnOnce(&StaticBvh, &mut dyn FnMut(usize) -> bool),
+        F: FnMut(usize) -> bool,
+    {
+        // 在查询的回调中再次查询时外层已经同步过了
+        if let Ok(mut state) = self.broad_phase.try_borrow_mut() {
+            state.sync(&self.bodies, &self.unbaked);
+        }
+        let mut stopped = false;
+        query(self.broad_phase.borrow().broad_phase(), &mut |i| {
+            stopped = !visit(i);
+            !stopped
+        });
+        if let (false, Some(bvh)) = (stopped, &self.static_bvh) {
+            static_query(bvh, &mut visit);
+        }
+    }
+
+    /// 获取上一次 step 产生的碰撞事件
+    pub fn events(&self) -> &[CollisionEvent] {
+        &self.events
+    }
+
+    /// 获取上一次 step 中粗测阶段物体对的变化，先是所有新增的物体对，再是所有移除的物体对，各自从小到大排列。
+    /// 烘焙过的只读静态物体不产生该事件
+    pub fn pair_events(&self) -> &[PairEvent] {
+        &self.pair_events
+    }
+
+    /// 获取上一次 step 中开始接触的物体受到的冲击，包含两个物体的表面材质和法向冲量
+    pub fn impact_events(&self) -> &[ImpactEvent] {
+        &self.impact_events
+    }
+
+    /// 烘焙场景中的静态物体，适合在关卡加载完成后调用一次
+    /// * 合并首尾相接、尺寸对齐且材质相同的静态 AABB
+    /// * 为所有静态物体构建不可修改的 BVH，加速宽阶段检测
+    /// * 把这些静态物体标记为只读，之后修改它们的位置和速度不会生效
+    ///
+    /// BVH 构建之后不再修改，step 时也不再遍历烘焙过的物体，
+    /// 每次 step 的开销主要取决于没有烘焙的物体数量，适合包含大量静态瓦片的关卡。
+    /// 合并会移除被合并的物体，调用之后物体在 world 中的下标可能发生变化：
+    /// 轨道约束、角度约束、遥测、历史记录、`mark_changed` 的标记和排队的冲量与力改为使用物体新的下标，
+    /// 指向被合并掉的物体的会被丢弃；接触、碰撞冲量和拖拽会被清空。
+    /// 烘焙之后新加入的静态物体不在 BVH 中，可以再次调用本函数重新烘焙
+    pub fn bake_statics(&mut self) {
+        let remap = self.merge_static_aabbs();
+        self.remap_bodies(&remap);
+        let mut items = vec![];
+        self.unbaked.clear();
+        for (i, body) in self.bodies.iter().enumerate() {
+            let mut body = body.borrow_mut();
+            if body.is_static() {
+                // 烘焙过的物体不再参与 step，插值的起点固定为当前位置
+                body.snap();
+                body.read_only = true;
+                let (min, max) = body.bounds();
+                items.push((i, min, max));
+            } else {
+                self.unbaked.push(i);
+            }
+        }
+        self.static_bvh = Some(Arc::new(StaticBvh::build(&items)));
+        // 物体下标可能已经变化，之前记录的接触信息、粗测阶段的物体对和拖拽不再有效
+        self.broad_phase.get_mut().clear();
+        self.contacts.clear();
+        self.touching.clear();
+        self.drag = None;
+        self.last_positions.clear();
+        self.impacts.clear();
+    }
+
+    // 物体的下标变化之后，把记录了物体下标的约束、遥测、历史记录、标记和排队的命令改为新的下标，
+    // `remap[i]` 为原来下标为 i 的物体的新下标，被移除的物体为 None
+    fn remap_bodies(&mut self, remap: &[Option<usize>]) {
+        let index = |i: usize| remap.get(i).copied().flatten();
+        self.tracks.retain_mut(|track| match index(track.body()) {
+            Some(i) => {
+                track.set_body(i);
+                true
+            }
+            None => false,
+        });
+        self.angle_constraints.retain_mut(|constraint| match index(constraint.body()) {
+            Some(i) => {
+                constraint.set_body(i);
+                true
+            }
+            None => false,
+        });
+        if let Some(telemetry) = &mut self.telemetry {
+            telemetry.remap(index);
         }
+        if let Some(history) = &mut self.history {
+            history.remap(remap);
+        }
+        self.marked_changed = self.marked_changed.iter().filter_map(|&i| index(i)).collect();
+        self.queued.get_mut().retain_mut(|command| {
+            let (QueuedCommand::Impulse(i, _) | QueuedCommand::Force(i, _)) = command;
+            match index(*i) {
+                Some(new) => {
+                    *i = new;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// 上一次 step 中该物体受到的最大的单次碰撞冲量
+    /// 可以用来计算坠落伤害或者判断物体是否被撞坏，没有碰撞时为 0
+    pub fn largest_impact(&self, body: usize) -> Real {
+        self.impacts.get(&body).map_or(0., |impact| impact.1)
+    }
+
+    /// 上一次 step 中该物体受到的所有碰撞冲量之和，没有碰撞时为 0
+    pub fn accumulated_impulse(&self, body: usize) -> Real {
+        self.impacts.get(&body).map_or(0., |impact| impact.0)
+    }
+
+    /// 获取上一次 step 检测到的所有接触点
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    pub fn narrow_phase(&self) -> &NarrowPhase {
+        &self.narrow_phase
+    }
+
+    /// 注册形状对 `(a, b)` 的碰撞函数，参见 `NarrowPhase::register`
+    pub fn register_collision(&mut self, a: ShapeKind, b: ShapeKind, collide: CollideFn) {
+        Rc::make_mut(&mut self.narrow_phase).register(a, b, collide);
+    }
+
+    pub fn integration_scheme(&self) -> IntegrationScheme {
+        self.integration_scheme
+    }
+
+    /// 设置速度和位置的积分方式，默认为 `IntegrationScheme::HalfStep`
+    pub fn set_integration_scheme(&mut self, scheme: IntegrationScheme) {
+        self.integration_scheme = scheme;
+    }
+
+    /// 设置粗测阶段使用的数据结构，默认为 `BroadPhaseKind::BruteForce`。
+    /// 粗测结构只影响性能，得到的物体对和查询结果相同
+    pub fn set_broad_phase(&mut self, broad_phase: Box<dyn BroadPhase>) {
+        let margin = self.broad_phase_margin();
+        self.broad_phase = RefCell::new(BroadPhaseState::new(broad_phase, margin));
+    }
+
+    /// 使用内置的粗测方式，参见 `set_broad_phase`
+    pub fn set_broad_phase_kind(&mut self, kind: BroadPhaseKind) {
+        self.set_broad_phase(kind.create());
+    }
+
+    pub fn broad_phase_margin(&self) -> Real {
+        self.broad_phase.borrow().margin()
+    }
+
+    /// 设置交给粗测结构的包围盒向外扩大的距离，默认为 0.1，随 `set_length_scale` 缩放。
+    /// 物体移出扩大后的包围盒时才需要更新粗测结构，较大的值减少更新次数，但会产生更多候选物体对
+    pub fn set_broad_phase_margin(&mut self, margin: Real) {
+        self.broad_phase.get_mut().set_margin(margin);
+    }
+
+    /// 用当前的 dt、重力和积分方式模拟一个标准场景（只受重力、不发生碰撞的抛射体）`steps` 次，
+    /// 返回机械能的漂移，用于比较不同积分方式的稳定性。不会影响当前 world 中的物体
+    pub fn validate_energy_drift(&self, steps: usize) -> EnergyDrift {
+        let mut world = World::new(self.dt, self.iterations, 1.);
+        world.gravity = self.gravity;
+        world.length_scale = self.length_scale;
+        world.integration_scheme = self.integration_scheme;
+        let speed = 10. * self.length_scale;
+        let mut body = Body::new_circle(Circle::new(self.length_scale), Vec2::ZERO, 0.);
+        body.set_velocity(Vec2::new(speed, -speed));
+        world.add_body(body);
+
+        let gravity = self.gravity;
+        let energy = |world: &World| {
+            let body = world.bodies[0].borrow();
+            body.mass() * (body.velocity().length_squared() / 2. - gravity.dot(body.position()))
+        };
+        let initial_energy = energy(&world);
+        let mut max_relative_drift: Real = 0.;
+        for _ in 0..steps {
+            world.step();
+            let drift = (energy(&world) - initial_energy).abs() / initial_energy;
+            max_relative_drift = max_relative_drift.max(drift);
+        }
+        EnergyDrift {
+            scheme: self.integration_scheme,
+            initial_energy,
+            final_energy: energy(&world),
+            max_relative_drift,
+        }
+    }
+
+    /// 当前的重力加速度
+    pub fn gravity(&self) -> Vec2 {
+        self.gravity
+    }
+
+    /// 立即设置重力加速度，会取消正在进行的重力渐变
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.gravity = gravity;
+        self.gravity_ramp = None;
+    }
+
+    /// 在 `duration` 秒内把重力从 `from` 线性过渡到 `to`，每次 step 开始时更新重力，
+    /// 用于重力翻转等需要平滑过渡的玩法。`duration` 不大于 0 时直接设置为 `to`
+    pub fn animate_gravity(&mut self, from: Vec2, to: Vec2, duration: Real) {
+        if duration <= 0. {
+            self.set_gravity(to);
+        } else {
+            self.gravity = from;
+            self.gravity_ramp = Some((from, to, duration, 0.));
+        }
+    }
+
+    /// 是否有正在进行的重力渐变
+    pub fn is_gravity_animating(&self) -> bool {
+        self.gravity_ramp.is_some()
+    }
+
+    fn apply_volumes(&self) {
+        for volume in &self.volumes {
+            self.query_region_with(volume.min(), volume.max(), |i| {
+                let mut body = self.bodies[i].borrow_mut();
+                if body.inverse_mass() != 0. {
+                    if let Some(dt) = self.body_dt(&body) {
+                        volume.apply(&mut body, dt);
+                    }
+                }
+                true
+            });
+        }
+    }
+
+    fn advance_gravity_ramp(&mut self) {
+        if let Some((from, to, duration, elapsed)) = &mut self.gravity_ramp {
+            *elapsed += self.dt;
+            let t = (*elapsed / *duration).min(1.);
+            self.gravity = from.lerp(*to, t);
+            if t >= 1. {
+                self.gravity_ramp = None;
+            }
+        }
+    }
+
+    /// 设置长度单位的比例，即每米对应多少个长度单位（例如使用像素作为单位时的每米像素数），默认为 1。
+    /// 重力和内部的各种长度阈值（位置修正允许的侵入量、位置变化阈值等）会按新旧比例缩放，
+    /// 通常在添加物体之前调用。不是正的有限数的 `units_per_meter` 会被忽略
+    pub fn set_length_scale(&mut self, units_per_meter: Real) {
+        if !valid_scale_factor(units_per_meter) {
+            return;
+        }
+        let ratio = units_per_meter / self.length_scale;
+        self.gravity *= ratio;
+        if let Some((from, to, _, _)) = &mut self.gravity_ramp {
+            *from *= ratio;
+            *to *= ratio;
+        }
+        self.change_epsilon *= ratio;
+        self.set_broad_phase_margin(self.broad_phase_margin() * ratio);
+        self.length_scale = units_per_meter;
+    }
+
+    /// 每米对应的长度单位数
+    pub fn length_scale(&self) -> Real {
+        self.length_scale
+    }
+
+    /// 把以米为单位的向量转换为 world 使用的长度单位
+    pub fn meters_to_units(&self, meters: Vec2) -> Vec2 {
+        meters.meters_to_units(self.length_scale)
+    }
+
+    /// 把 world 使用的长度单位转换为以米为单位的向量
+    pub fn units_to_meters(&self, units: Vec2) -> Vec2 {
+        units.units_to_meters(self.length_scale)
+    }
+
+    /// 设置位置修正推开互相侵入的物体时的最大速度，只在开启 `SolverConfig::positional_correction` 时生效
+    /// 深埋在其他物体中的刚体会以不超过该速度的方式慢慢移出，而不是被一下子弹飞。
+    /// 传入 `None` 表示不限制，默认不限制
+    pub fn set_max_depenetration_speed(&mut self, speed: Option<Real>) {
+        self.max_depenetration_speed = speed;
+    }
+
+    /// 设置碰撞求解使用的摩擦力模型
+    pub fn set_friction_model(&mut self, model: FrictionModel) {
+        self.friction_model = model;
+    }
+
+    /// 设置两个物体位置完全重合时的分离策略
+    pub fn set_coincident_policy(&mut self, policy: CoincidentPolicy) {
+        self.coincident_policy = policy;
+    }
+
+    /// 设置连续碰撞检测（CCD）的触发比例
+    /// 物体单步位移超过自身最小尺寸的 `fraction` 倍时，会对这一步的运动做扫掠检测，
+    /// 避免高速的小物体穿过其他物体。传入 `None` 关闭该功能，默认为 `Some(0.5)`
+    pub fn set_ccd_fraction(&mut self, fraction: Option<Real>) {
+        self.ccd_fraction = fraction;
+    }
+
+    /// 每次 step 的时间间隔
+    pub fn dt(&self) -> Real {
+        self.dt
     }
 
     /// 获取 world 中所有刚体
@@ -32,81 +902,2799 @@ impl World {
 
     /// world 中添加一个刚体
     pub fn add_body(&mut self, body: Body) {
-        self.bodies.push(Rc::new(RefCell::new(body)));
+        self.add_rc_body(Rc::new(RefCell::new(body)));
+    }
+
+    /// 注册物体模板，返回模板的 id
+    pub fn register_template(&mut self, template: BodyTemplate) -> usize {
+        self.templates.push(Rc::new(template));
+        self.templates.len() - 1
+    }
+
+    pub fn template(&self, template_id: usize) -> Option<&BodyTemplate> {
+        self.templates.get(template_id).map(|t| t.as_ref())
+    }
+
+    /// 用模板在 `position` 创建一个物体并加入 world，返回新物体的下标，模板不存在时返回 `None`
+    pub fn spawn(&mut self, template_id: usize, position: Vec2) -> Option<usize> {
+        let body = self.templates.get(template_id)?.instantiate(position);
+        self.add_body(body);
+        Some(self.bodies.len() - 1)
     }
 
     pub(crate) fn add_rc_body(&mut self, body: Rc<RefCell<Body>>) {
+        self.jitter_spawn(&mut body.borrow_mut());
+        body.borrow_mut().update_phase = self.bodies.len() as u32;
+        self.unbaked.push(self.bodies.len());
         self.bodies.push(body);
     }
 
-    /// world 推进一步，并更新每个物体的位置
-    pub fn step(&mut self) {
-        // 碰撞检测
-        // Broad Phase + Narrow Phase
-        let mut contacts = vec![];
-        for (i, a) in self.bodies.iter().enumerate() {
-            for b in self.bodies[i + 1..].iter() {
-                if a.borrow().inverse_mass() == 0. && b.borrow().inverse_mass() == 0. {
-                    // 两个物体的质量都是无穷大，不会发生位置的变化
-                    continue;
+    /// 开启出生位置的微小扰动
+    /// 新加入的非静态物体与已有物体的 x 或 y 坐标完全相同（完美对齐地堆叠）时，
+    /// 在该坐标上加入 `[-epsilon, epsilon]` 之间的偏移，避免完全对称的堆叠。
+    /// 偏移由 `seed` 和物体的下标决定，相同的场景总是得到相同的结果
+    pub fn set_spawn_jitter(&mut self, seed: u64, epsilon: Real) {
+        self.spawn_jitter = Some((seed, epsilon));
+    }
+
+    /// 关闭出生位置的扰动
+    pub fn clear_spawn_jitter(&mut self) {
+        self.spawn_jitter = None;
+    }
+
+    /// 检查场景中的退化输入，返回发现的所有问题
+    /// 这些输入通常会在求解时表现为难以定位的数值爆炸
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+        for (i, body) in self.bodies.iter().enumerate() {
+            let body = body.borrow();
+            if !body.position().is_finite() {
+                issues.push(ValidationIssue::NonFinitePosition { body: i });
+            }
+            let density = match body.shape() {
+                ShapeType::Circle(circle) => {
+                    if circle.radius() <= 0. {
+                        issues.push(ValidationIssue::NonPositiveRadius {
+                            body: i,
+                            radius: circle.radius(),
+                        });
+                    }
+                    circle.density()
                 }
-                let m = Manifold::solve(a.clone(), b.clone());
-                if m.get_contacts().len() > 0 {
-                    contacts.push(m);
+                ShapeType::AABB(aabb) => {
+                    let size = aabb.max() - aabb.min();
+                    if size.x < 0. || size.y < 0. {
+                        issues.push(ValidationIssue::InvertedAABB { body: i });
+                    } else if size.x == 0. || size.y == 0. {
+                        issues.push(ValidationIssue::ZeroSizeAABB { body: i });
+                    }
+                    aabb.density()
                 }
+                ShapeType::Polygon(polygon) => polygon.density(),
+                ShapeType::Triangle(triangle) => triangle.density(),
+                ShapeType::Capsule(capsule) => {
+                    if capsule.radius() <= 0. {
+                        issues.push(ValidationIssue::NonPositiveRadius {
+                            body: i,
+                            radius: capsule.radius(),
+                        });
+                    }
+                    capsule.density()
+                }
+                // 线段和折线没有质量，点只有质量没有密度
+                ShapeType::Segment(_) | ShapeType::Chain(_) | ShapeType::Point(_) => continue,
+                // 复合形状的质量由子形状决定，自定义形状由使用者负责，这里不检查
+                ShapeType::Compound(_) | ShapeType::Custom(_) => continue,
+            };
+            if density <= 0. {
+                issues.push(ValidationIssue::NonPositiveDensity { body: i, density });
             }
         }
 
-        for body in &self.bodies {
-            self.integrate_forces(body.clone());
+        // 静态物体之间的重叠不会被求解，只能在这里报告。
+        // 用粗测结构和烘焙的静态 BVH 找出包围盒重叠的静态物体对，只对这些物体对做窄检测
+        let mut pairs = vec![];
+        for (i, a) in self.bodies.iter().enumerate() {
+            if !a.borrow().is_static() {
+                continue;
+            }
+            let (min, max) = a.borrow().bounds();
+            self.query_region_with(min, max, |j| {
+                if j > i && self.bodies[j].borrow().is_static() {
+                    pairs.push((i, j));
+                }
+                true
+            });
+        }
+        pairs.sort_unstable();
+        for (i, j) in pairs {
+            let (a, b) = (self.bodies[i].clone(), self.bodies[j].clone());
+            if !Manifold::solve(a, b, &self.narrow_phase).get_contacts().is_empty() {
+                issues.push(ValidationIssue::OverlappingStatics { a: i, b: j });
+            }
         }
+        issues
+    }
+
+    /// 在下一次 step 开始时对物体 `body` 施加冲量，只需要 `&World`，
+    /// 可以在事件处理或者只持有 world 共享引用的系统中使用。无效的下标会被忽略
+    pub fn queue_impulse(&self, body: usize, impulse: Vec2) {
+        self.queued.borrow_mut().push(QueuedCommand::Impulse(body, impulse));
+    }
+
+    /// 在下一次 step 开始时对物体 `body` 施加力，效果与 `Body::apply_force` 相同
+    pub fn queue_force(&self, body: usize, force: Vec2) {
+        self.queued.borrow_mut().push(QueuedCommand::Force(body, force));
+    }
 
-        for contact in &mut contacts {
-            contact.initialize();
+    /// 批量设置运动学物体（质量为 0、由外部驱动的物体）的位置和朝向，每一项为
+    /// `(物体的下标, 位置, 朝向的弧度)`，用于动画或过场系统每帧驱动大量运动学物体。
+    /// 物体的速度会被设为从原来的变换移动到新变换所需的速度，使其他物体与它碰撞时
+    /// 得到正确的响应。静态物体不能转动，角速度只用于计算接触点的速度。
+    /// 停止驱动时用当前的变换再调用一次，把速度和角速度设为 0。
+    /// 非静态物体会在 step 中继续积分，因此会被忽略；无效的下标也会被忽略
+    pub fn set_transforms(&mut self, transforms: &[(usize, Vec2, Real)]) {
+        for &(i, position, angle) in transforms {
+            let Some(body) = self.bodies.get(i) else {
+                continue;
+            };
+            let mut body = body.borrow_mut();
+            if !body.is_static() {
+                continue;
+            }
+            let velocity = (position - body.position()) / self.dt;
+            let angular_velocity = shortest_angle_between(body.rotation().angle(), angle) / self.dt;
+            body.set_position(position);
+            body.set_rotation(Rot::from_angle(angle));
+            body.set_velocity(velocity);
+            body.set_kinematic_angular_velocity(angular_velocity);
         }
+    }
 
-        for _ in 0..self.iterations {
-            for contact in &mut contacts {
-                contact.apply_impulse();
+    // 按提交的顺序执行排队的命令
+    fn apply_queued(&mut self) {
+        for command in self.queued.get_mut().drain(..) {
+            let (QueuedCommand::Impulse(i, _) | QueuedCommand::Force(i, _)) = command;
+            let Some(body) = self.bodies.get(i) else {
+                continue;
+            };
+            let mut body = body.borrow_mut();
+            match command {
+                QueuedCommand::Impulse(_, impulse) => body.apply_impulse(impulse),
+                QueuedCommand::Force(_, force) => body.apply_force(force),
             }
         }
+    }
+
+    /// world 推进一步，并更新每个物体的位置
+    pub fn step(&mut self) {
+        self.step_impl(None);
+    }
+
+    /// 与 `step` 相同，但每次冲量迭代结束后调用 `observer`，
+    /// 传入当前所有物体的速度和每个接触累积的冲量
+    pub fn step_observed<F>(&mut self, mut observer: F)
+    where
+        F: FnMut(&SolverIteration),
+    {
+        self.step_impl(Some(&mut observer));
+    }
 
-        for body in &self.bodies {
-            self.integrate_velocity(body.clone());
+    fn step_impl(&mut self, mut observer: Option<&mut dyn FnMut(&SolverIteration)>) {
+        if let Some(history) = &mut self.history {
+            history.push(&self.bodies);
+        }
+        self.apply_queued();
+        self.advance_gravity_ramp();
+        self.apply_volumes();
+        // 记录渲染插值的起点
+        for body in self.unbaked_bodies() {
+            body.borrow_mut().snap();
         }
 
-        for body in &self.bodies {
-            body.borrow_mut().clear_force();
+        // 碰撞检测
+        // Broad Phase + Narrow Phase
+        let mut contacts = vec![];
+        for (i, j) in self.broad_phase() {
+            let a = &self.bodies[i];
+            let b = &self.bodies[j];
+            if a.borrow().inverse_mass() == 0. && b.borrow().inverse_mass() == 0. {
+                // 两个物体的质量都是无穷大，不会发生位置的变化
+                continue;
+            }
+            if self.ignores_pair(&a.borrow(), &b.borrow()) {
+                continue;
+            }
+            if self.is_frozen(&a.borrow()) || self.is_frozen(&b.borrow()) {
+                continue;
+            }
+            let normal = self.coincident_policy.normal(i, j);
+            let m = Manifold::solve_with(a.clone(), b.clone(), normal, &self.narrow_phase);
+            if !m.get_contacts().is_empty() {
+                contacts.push((i, j, m));
+            }
+        }
+        self.record_events(contacts.iter().map(|(i, j, _)| (*i, *j)).collect());
+        // 传感器只检测重叠，不参与碰撞求解
+        contacts.retain(|(i, j, _)| {
+            !self.bodies[*i].borrow().is_sensor() && !self.bodies[*j].borrow().is_sensor()
+        });
+        self.record_contacts(&contacts);
+        if let Some(angle) = self.solver.circle_contact_reduction {
+            self.reduce_circle_contacts(&mut contacts, angle);
         }
-    }
-}
 
-impl World {
-    // 把计算出来的力应用到物体上
-    fn integrate_forces(&self, body: Rc<RefCell<Body>>) {
-        let mut internal_body = body.borrow_mut();
-        if internal_body.inverse_mass() == 0. {
-            return;
+        let (before, _) = self.integration_scheme.kick_fractions();
+        for body in self.unbaked_bodies() {
+            self.integrate_forces(body.clone(), before);
         }
-        // v1 = v0 + F / m * dt / 2
-        // TODO: 这里不使用 dt / 2 是否可以？
-        let new_velocity = internal_body.velocity()
-            + (self.gravity + internal_body.force() * internal_body.inverse_mass())
-                * (self.dt as f32 / 2.);
-        internal_body.set_velocity(new_velocity);
-    }
 
-    // 根据速度计算新的位置
-    fn integrate_velocity(&self, body: Rc<RefCell<Body>>) {
-        {
-            let mut internal_body = body.borrow_mut();
-            if internal_body.inverse_mass() == 0. {
-                return;
+        if let Some(joint) = &self.drag {
+            if let Some(body) = self.bodies.get(joint.body()) {
+                joint.apply_impulse(&mut body.borrow_mut(), self.dt);
             }
-            let new_pos = internal_body.position() + internal_body.velocity() * self.dt as f32;
+        }
+        for track in &self.tracks {
+            if let Some(body) = self.bodies.get(track.body()) {
+                track.apply_impulse(&mut body.borrow_mut(), self.dt);
+            }
+        }
+        for constraint in &self.angle_constraints {
+            if let Some(body) = self.bodies.get(constraint.body()) {
+                constraint.apply_impulse(&mut body.borrow_mut(), self.dt);
+            }
+        }
+
+        for (_, _, contact) in &mut contacts {
+            contact.initialize(&self.solver);
+        }
+
+        let mut iteration = 0;
+        for _ in 0..self.iterations {
+            for (_, _, contact) in &mut contacts {
+                contact.apply_impulse(self.friction_model);
+            }
+            if let Some(observer) = &mut observer {
+                observer(&self.solver_iteration(iteration, &contacts));
+            }
+            iteration += 1;
+        }
+
+        if self.solver.extra_iterations > 0 {
+            let high_ratio = self.high_mass_ratio_contacts(&contacts);
+            for _ in 0..self.solver.extra_iterations {
+                for (k, (_, _, contact)) in contacts.iter_mut().enumerate() {
+                    if high_ratio[k] {
+                        contact.apply_impulse(self.friction_model);
+                    }
+                }
+                if let Some(observer) = &mut observer {
+                    observer(&self.solver_iteration(iteration, &contacts));
+                }
+                iteration += 1;
+            }
+        }
+
+        self.record_stats(&contacts);
+
+        self.record_impact_events(&contacts);
+
+        self.impacts.clear();
+        for (i, j, contact) in &contacts {
+            let impulse = contact.normal_impulse();
+            for k in [*i, *j] {
+                let impact = self.impacts.entry(k).or_insert((0., 0.));
+                impact.0 += impulse;
+                impact.1 = impact.1.max(impulse);
+            }
+        }
+
+        let positions = self.integrated_positions();
+        for (&i, &position) in self.unbaked.iter().zip(&positions) {
+            self.integrate_velocity(i, position);
+        }
+        self.batch_positions = positions;
+
+        for body in self.unbaked_bodies() {
+            body.borrow_mut().run_velocity_callback(self.dt);
+        }
+
+        if self.solver.positional_correction {
+            let max_correction = self.max_depenetration_speed.map(|speed| speed * self.dt);
+            for (_, _, contact) in &mut contacts {
+                contact.positional_correction(max_correction, self.length_scale);
+            }
+        }
+
+        for body in self.unbaked_bodies() {
+            // 本次 step 没有更新的物体保留外力，到下一次更新时再使用
+            let skipped = self.body_dt(&body.borrow()).is_none();
+            if !skipped {
+                body.borrow_mut().clear_force();
+            }
+        }
+        self.step_count += 1;
+
+        if let Some(telemetry) = &mut self.telemetry {
+            let bodies = &self.bodies;
+            telemetry.record(|i| {
+                let body = bodies.get(i)?.borrow();
+                Some((body.position(), body.velocity()))
+            });
+        }
+    }
+}
+
+impl World {
+    // 标记属于高质量比岛的接触
+    fn high_mass_ratio_contacts(&self, contacts: &[(usize, usize, Manifold)]) -> Vec<bool> {
+        let mass = |i: usize| self.bodies[i].borrow().mass();
+        let is_static = |i: usize| self.bodies[i].borrow().is_static();
+        let mut islands = self.islands(contacts);
+        // 每个岛中非静态物体的最小和最大质量
+        let mut range: HashMap<usize, (Real, Real)> = HashMap::new();
+        for (i, j, _) in contacts {
+            for k in [*i, *j].into_iter().filter(|k| !is_static(*k)) {
+                let (island, mass) = (islands.find(self.island_index(k)), mass(k));
+                let r = range.entry(island).or_insert((mass, mass));
+                *r = (r.0.min(mass), r.1.max(mass));
+            }
+        }
+        contacts
+            .iter()
+            .map(|(i, j, _)| {
+                let body = if is_static(*i) { *j } else { *i };
+                !is_static(body)
+                    && range
+                        .get(&islands.find(self.island_index(body)))
+                        .is_some_and(|(min, max)| *max > *min * self.solver.high_mass_ratio)
+            })
+            .collect()
+    }
+
+    // 把接触的非静态物体连成岛，岛的下标为物体在 `unbaked` 中的位置（参见 `island_index`），
+    // 大小只取决于没有烘焙的物体数量。静态物体不会把两个岛连接起来
+    fn islands(&self, contacts: &[(usize, usize, Manifold)]) -> UnionFind {
+        let is_static = |i: usize| self.bodies[i].borrow().is_static();
+        let mut islands = UnionFind::new(self.unbaked.len());
+        for (i, j, _) in contacts {
+            if !is_static(*i) && !is_static(*j) {
+                islands.union(self.island_index(*i), self.island_index(*j));
+            }
+        }
+        islands
+    }
+
+    // 非静态物体在 `unbaked` 中的位置。烘焙的只有静态物体，`unbaked` 从小到大排列
+    fn island_index(&self, body: usize) -> usize {
+        self.unbaked
+            .binary_search(&body)
+            .expect("non-static bodies are never baked")
+    }
+
+    // 合并密集堆积的圆之间的接触：按穿透深度从深到浅处理圆-圆接触，
+    // 如果某个非静态物体上已经保留了一个法线方向相近的接触，就丢弃当前接触
+    fn reduce_circle_contacts(&self, contacts: &mut Vec<(usize, usize, Manifold)>, angle: Real) {
+        let is_circle =
+            |i: usize| matches!(self.bodies[i].borrow().shape(), ShapeType::Circle(_));
+        let is_static = |i: usize| self.bodies[i].borrow().is_static();
+        let min_cos = angle.clamp(0., consts::PI).cos();
+
+        let mut order: Vec<usize> = (0..contacts.len())
+            .filter(|k| is_circle(contacts[*k].0) && is_circle(contacts[*k].1))
+            .collect();
+        order.sort_by(|x, y| contacts[*y].2.penetration().total_cmp(&contacts[*x].2.penetration()));
+
+        // 每个物体已保留的接触法线，方向从该物体指向外
+        let mut kept: HashMap<usize, Vec<Vec2>> = HashMap::new();
+        let mut removed = vec![false; contacts.len()];
+        for k in order {
+            let (i, j, contact) = &contacts[k];
+            let sides = [(*i, contact.normal()), (*j, -contact.normal())];
+            let redundant = sides.iter().any(|(body, normal)| {
+                !is_static(*body)
+                    && kept
+                        .get(body)
+                        .is_some_and(|normals| normals.iter().any(|n| n.dot(*normal) > min_cos))
+            });
+            if redundant {
+                removed[k] = true;
+                continue;
+            }
+            for (body, normal) in sides {
+                kept.entry(body).or_default().push(normal);
+            }
+        }
+        let mut k = 0;
+        contacts.retain(|_| {
+            k += 1;
+            !removed[k - 1]
+        });
+    }
+
+    // 当前迭代结束时求解器的状态
+    fn solver_iteration(
+        &self,
+        iteration: usize,
+        contacts: &[(usize, usize, Manifold)],
+    ) -> SolverIteration {
+        SolverIteration {
+            iteration,
+            velocities: self
+                .unbaked
+                .iter()
+                .map(|&i| (i, self.bodies[i].borrow().velocity()))
+                .collect(),
+            contacts: contacts
+                .iter()
+                .map(|(a, b, contact)| ContactImpulse {
+                    a: *a,
+                    b: *b,
+                    normal: contact.normal(),
+                    normal_impulse: contact.normal_impulse(),
+                    tangent_impulse: contact.tangent_impulse(),
+                })
+                .collect(),
+        }
+    }
+
+    // 在迭代求解之后统计每个岛的残差
+    fn record_stats(&mut self, contacts: &[(usize, usize, Manifold)]) {
+        let is_static = |i: usize| self.bodies[i].borrow().is_static();
+        let mut islands = self.islands(contacts);
+
+        // (物体下标, 法向速度残差, 约束位置误差)
+        let mut residuals = vec![];
+        for (i, j, m) in contacts {
+            let body = if is_static(*i) { *j } else { *i };
+            residuals.push((body, m.normal_velocity_error(), 0.));
+        }
+        if let Some(joint) = &self.drag {
+            if let Some(body) = self.bodies.get(joint.body()) {
+                residuals.push((joint.body(), 0., joint.violation(&body.borrow())));
+            }
+        }
+        for track in &self.tracks {
+            if let Some(body) = self.bodies.get(track.body()) {
+                residuals.push((track.body(), 0., track.violation(&body.borrow())));
+            }
+        }
+        for constraint in &self.angle_constraints {
+            if let Some(body) = self.bodies.get(constraint.body()) {
+                residuals.push((constraint.body(), 0., constraint.violation(&body.borrow())));
+            }
+        }
+
+        let mut stats: HashMap<usize, IslandStats> = HashMap::new();
+        for (body, velocity, violation) in residuals {
+            if is_static(body) {
+                continue;
+            }
+            let island = stats.entry(islands.find(self.island_index(body))).or_default();
+            island.max_normal_velocity = island.max_normal_velocity.max(velocity);
+            island.max_joint_violation = island.max_joint_violation.max(violation);
+        }
+        for (k, &body) in self.unbaked.iter().enumerate() {
+            if is_static(body) {
+                continue;
+            }
+            if let Some(island) = stats.get_mut(&islands.find(k)) {
+                island.bodies.push(body);
+            }
+        }
+        let mut islands: Vec<IslandStats> = stats.into_values().collect();
+        islands.sort_by_key(|island| island.bodies[0]);
+        self.stats = StepStats {
+            contact_count: contacts.len(),
+            islands,
+        };
+    }
+
+    // 对即将加入 world 的物体施加出生位置扰动
+    fn jitter_spawn(&self, body: &mut Body) {
+        let Some((seed, epsilon)) = self.spawn_jitter else {
+            return;
+        };
+        if body.is_static() {
+            return;
+        }
+        let index = self.bodies.len() as u64;
+        let mut position = body.position();
+        let offset = |axis: u64| {
+            let hash = split_mix64(seed ^ (index << 1 | axis));
+            (unit_float(hash) * 2. - 1.) * epsilon
+        };
+        let aligned_x = self.bodies.iter().any(|b| b.borrow().position().x == position.x);
+        let aligned_y = self.bodies.iter().any(|b| b.borrow().position().y == position.y);
+        if aligned_x {
+            position.x += offset(0);
+        }
+        if aligned_y {
+            position.y += offset(1);
+        }
+        body.set_position(position);
+    }
+
+    // 宽阶段检测，返回所有可能发生碰撞的物体对 (i, j)，i < j
+    // 烘焙过的只读静态物体只通过静态 BVH 查询，其余物体由粗测结构组合
+    fn broad_phase(&mut self) -> Vec<(usize, usize)> {
+        let state = self.broad_phase.get_mut();
+        state.sync(&self.bodies, &self.unbaked);
+        state.update_pairs();
+        let added = state.added_pairs().iter().map(|&(a, b)| PairEvent::Added { a, b });
+        let removed = state.removed_pairs().iter().map(|&(a, b)| PairEvent::Removed { a, b });
+        self.pair_events = added.chain(removed).collect();
+        let mut pairs = state.pairs();
+        if let Some(bvh) = &self.static_bvh {
+            for &i in &self.unbaked {
+                if self.bodies[i].borrow().is_static() {
+                    continue;
+                }
+                for &(min, max) in state.shape_bounds(i) {
+                    bvh.query(min, max, |j| {
+                        pairs.push((i.min(j), i.max(j)));
+                        true
+                    });
+                }
+            }
+        }
+        pairs.sort_unstable();
+        // 同一对物体的多个形状互相重叠时只保留一个物体对
+        pairs.dedup();
+        pairs
+    }
+
+    // 合并相接的静态 AABB：先把上下边对齐、左右相接的合并成横条，
+    // 再把左右边对齐、上下相接的横条合并，每个方向排序后只扫描一遍。
+    // 合并后的物体保留原物体的所有非几何属性。
+    // 返回每个物体原来的下标到新下标的映射，被合并到其他物体中的物体为 None
+    fn merge_static_aabbs(&mut self) -> Vec<Option<usize>> {
+        // (min, max, 组中最小的物体下标, 组中物体的数量)
+        let mut rects = vec![];
+        let mut candidates = vec![false; self.bodies.len()];
+        for (i, body) in self.bodies.iter().enumerate() {
+            let body = body.borrow();
+            if body.is_static() && matches!(body.shape(), ShapeType::AABB(_)) {
+                let (min, max) = body.bounds();
+                rects.push((min, max, i, 1));
+                candidates[i] = true;
+            }
+        }
+        let rects = self.merge_aabb_runs(rects, true);
+        let rects = self.merge_aabb_runs(rects, false);
+        let mut groups: HashMap<usize, (Vec2, Vec2, usize)> = rects
+            .into_iter()
+            .map(|(min, max, first, count)| (first, (min, max, count)))
+            .collect();
+        let bodies = std::mem::take(&mut self.bodies);
+        let mut remap = vec![None; bodies.len()];
+        for (i, body) in bodies.into_iter().enumerate() {
+            // 合并到其他物体中的物体被移除，每组合并结果放在组中最小的下标处
+            let body = match (candidates[i], groups.remove(&i)) {
+                (false, _) | (true, Some((_, _, 1))) => body,
+                (true, Some((min, max, _))) => {
+                    Rc::new(RefCell::new(body.borrow().with_aabb(min, max)))
+                }
+                (true, None) => continue,
+            };
+            remap[i] = Some(self.bodies.len());
+            self.bodies.push(body);
+        }
+        remap
+    }
+
+    // 按另一方向的两条边和起点排序，把 `horizontal` 方向（false 时为竖直方向）首尾相接、
+    // 另一方向两条边对齐并且非几何属性完全相同的相邻矩形合并成一个
+    fn merge_aabb_runs(
+        &self,
+        mut rects: Vec<(Vec2, Vec2, usize, usize)>,
+        horizontal: bool,
+    ) -> Vec<(Vec2, Vec2, usize, usize)> {
+        let epsilon = 0.0001 * self.length_scale;
+        let quantize = |v: Real| (v / epsilon).round() as i64;
+        // (合并方向的坐标, 需要对齐的坐标)
+        let split = |v: Vec2| if horizontal { (v.x, v.y) } else { (v.y, v.x) };
+        let key = |min: Vec2, max: Vec2| {
+            (quantize(split(min).1), quantize(split(max).1), quantize(split(min).0))
+        };
+        rects.sort_by_key(|&(min, max, ..)| key(min, max));
+        let mut merged: Vec<(Vec2, Vec2, usize, usize)> = Vec::with_capacity(rects.len());
+        for rect in rects {
+            if let Some(last) = merged.last_mut() {
+                let (last_key, rect_key) = (key(last.0, last.1), key(rect.0, rect.1));
+                let a = self.bodies[last.2].borrow();
+                let b = self.bodies[rect.2].borrow();
+                if last_key.0 == rect_key.0
+                    && last_key.1 == rect_key.1
+                    && quantize(split(last.1).0) == rect_key.2
+                    && a.same_properties(&b)
+                {
+                    drop(a);
+                    drop(b);
+                    last.0 = last.0.min(rect.0);
+                    last.1 = last.1.max(rect.1);
+                    last.2 = last.2.min(rect.2);
+                    last.3 += rect.3;
+                    continue;
+                }
+            }
+            merged.push(rect);
+        }
+        merged
+    }
+
+    // 对比上一次 step 的接触情况，生成开始接触和结束接触的事件
+    fn record_events(&mut self, touching: HashSet<(usize, usize)>) {
+        self.events.clear();
+        let mut started: Vec<_> = touching.difference(&self.touching).copied().collect();
+        let mut ended: Vec<_> = self.touching.difference(&touching).copied().collect();
+        started.sort_unstable();
+        ended.sort_unstable();
+        for (a, b) in started {
+            if let Some(event) = self.collision_event(a, b, true) {
+                self.events.push(event);
+            }
+        }
+        for (a, b) in ended {
+            if let Some(event) = self.collision_event(a, b, false) {
+                self.events.push(event);
+            }
+        }
+        self.touching = touching;
+    }
+
+    // 为本次 step 开始接触的物体对记录冲击，需要在迭代求解之后调用
+    fn record_impact_events(&mut self, contacts: &[(usize, usize, Manifold)]) {
+        self.impact_events.clear();
+        for (a, b, contact) in contacts {
+            let started = CollisionEvent::ContactStarted { a: *a, b: *b };
+            if !self.events.contains(&started) {
+                continue;
+            }
+            let body_a = self.bodies[*a].borrow();
+            let body_b = self.bodies[*b].borrow();
+            self.impact_events.push(ImpactEvent {
+                a: *a,
+                b: *b,
+                surface_a: body_a.surface_id(),
+                surface_b: body_b.surface_id(),
+                impulse: contact.normal_impulse(),
+                sub_shape_a: contact.sub_shapes().0,
+                sub_shape_b: contact.sub_shapes().1,
+            });
+        }
+    }
+
+    // 幽灵物体只与传感器产生重叠，与其他物体之间不做碰撞检测，遮挡物不与任何物体做碰撞检测
+    fn ignores_pair(&self, a: &Body, b: &Body) -> bool {
+        if a.is_occluder() || b.is_occluder() {
+            return true;
+        }
+        (a.is_ghost() || b.is_ghost()) && !a.is_sensor() && !b.is_sensor()
+    }
+
+    // 两个物体都没有开启上报时返回 None
+    fn collision_event(&self, a: usize, b: usize, started: bool) -> Option<CollisionEvent> {
+        let body_a = self.bodies.get(a)?.borrow();
+        let body_b = self.bodies.get(b)?.borrow();
+        if body_a.is_sensor() || body_b.is_sensor() {
+            if !body_a.report_sensor_overlaps() && !body_b.report_sensor_overlaps() {
+                return None;
+            }
+            let (sensor, other) = if body_a.is_sensor() { (a, b) } else { (b, a) };
+            if started {
+                Some(CollisionEvent::SensorEntered { sensor, other })
+            } else {
+                Some(CollisionEvent::SensorExited { sensor, other })
+            }
+        } else if body_a.report_contacts() || body_b.report_contacts() {
+            if started {
+                Some(CollisionEvent::ContactStarted { a, b })
+            } else {
+                Some(CollisionEvent::ContactEnded { a, b })
+            }
+        } else {
+            None
+        }
+    }
+
+    // 记录本次 step 的接触点
+    // 同一对物体的第 k 个接触点在上一次 step 中也存在时，认为是同一个接触点，年龄加一
+    fn record_contacts(&mut self, manifolds: &[(usize, usize, Manifold)]) {
+        let mut ages = HashMap::new();
+        let mut counts = HashMap::new();
+        for c in &self.contacts {
+            let k = counts.entry((c.a, c.b)).or_insert(0);
+            ages.insert((c.a, c.b, *k), c.age);
+            *k += 1;
+        }
+
+        self.contacts.clear();
+        for (a, b, m) in manifolds {
+            let body_a = self.bodies[*a].borrow();
+            let body_b = self.bodies[*b].borrow();
+            for (k, point) in m.get_contacts().iter().enumerate() {
+                let relative_velocity = point_velocity(&body_b, *point - body_b.position())
+                    - point_velocity(&body_a, *point - body_a.position());
+                self.contacts.push(Contact {
+                    a: *a,
+                    b: *b,
+                    point: *point,
+                    normal: m.normal(),
+                    penetration: m.penetration(),
+                    relative_velocity,
+                    sub_shape_a: m.sub_shapes().0,
+                    sub_shape_b: m.sub_shapes().1,
+                    age: ages.get(&(*a, *b, k)).map_or(0, |age| age + 1),
+                });
+            }
+        }
+    }
+
+    // 把计算出来的力应用到物体上，只更新 `fraction` 比例的 dt，参见 `IntegrationScheme`
+    fn integrate_forces(&self, body: Rc<RefCell<Body>>, fraction: Real) {
+        if fraction == 0. {
+            return;
+        }
+        let mut internal_body = body.borrow_mut();
+        if internal_body.inverse_mass() == 0. {
+            return;
+        }
+        let Some(dt) = self.body_dt(&internal_body) else {
+            return;
+        };
+        // v1 = v0 + F / m * dt * fraction
+        let acceleration = self.gravity
+            + internal_body.force() * internal_body.inverse_mass()
+            + internal_body.acceleration();
+        let new_velocity = internal_body.velocity() + acceleration * (dt * fraction);
+        internal_body.set_velocity(new_velocity);
+    }
+
+    // 没有烘焙的物体，烘焙过的物体不会移动，step 时跳过
+    fn unbaked_bodies(&self) -> impl Iterator<Item = &Rc<RefCell<Body>>> {
+        self.unbaked.iter().map(|&i| &self.bodies[i])
+    }
+
+    // 根据速度批量计算所有没有烘焙的物体新的位置，与 `unbaked` 一一对应。
+    // 每个物体只借用一次，位置和速度收集到在 step 之间复用的连续缓冲区中，再整体积分；
+    // 降低了更新频率的物体的速度按自己的时间间隔缩放，本次 step 不更新的物体速度为 0。
+    // 返回的缓冲区用完之后放回 `batch_positions`
+    fn integrated_positions(&mut self) -> Vec<Vec2> {
+        let mut positions = std::mem::take(&mut self.batch_positions);
+        let mut velocities = std::mem::take(&mut self.batch_velocities);
+        positions.clear();
+        velocities.clear();
+        for body in self.unbaked_bodies() {
+            let body = body.borrow();
+            let scale = self.body_dt(&body).map_or(0., |dt| dt / self.dt);
+            positions.push(body.position());
+            velocities.push(body.velocity() * scale);
+        }
+        batch::integrate(&mut positions, &velocities, self.dt);
+        self.batch_velocities = velocities;
+        positions
+    }
+
+    // 物体在本次 step 使用的时间间隔，本次 step 不更新的物体返回 None
+    fn body_dt(&self, body: &Body) -> Option<Real> {
+        if self.is_frozen(body) {
+            return None;
+        }
+        let interval = body.update_interval();
+        if interval <= 1 || self.in_activation_region(body) {
+            return Some(self.dt);
+        }
+        // 用物体的下标错开不同物体的更新时机，避免所有低频物体挤在同一次 step 中更新
+        let phase = body.update_phase as u64;
+        (self.step_count + phase)
+            .is_multiple_of(interval as u64)
+            .then_some(self.dt * interval as Real)
+    }
+
+    fn in_activation_region(&self, body: &Body) -> bool {
+        overlaps_regions(body, &self.activation_regions)
+    }
+
+    // 设置了模拟区域，并且非静态物体不与任何区域重叠
+    fn is_frozen(&self, body: &Body) -> bool {
+        !self.active_regions.is_empty()
+            && !body.is_static()
+            && !overlaps_regions(body, &self.active_regions)
+    }
+
+    // 把物体移动到积分得到的新位置 `new_pos`
+    fn integrate_velocity(&self, index: usize, new_pos: Vec2) {
+        let body = self.bodies[index].clone();
+        {
+            let mut internal_body = body.borrow_mut();
+            if internal_body.inverse_mass() == 0. {
+                return;
+            }
+            let Some(dt) = self.body_dt(&internal_body) else {
+                return;
+            };
+            let old_pos = internal_body.position();
             internal_body.set_position(new_pos);
+            let w = internal_body.angular_velocity();
+            if w != 0. {
+                let rotation = internal_body.rotation() * Rot::from_angle(w * dt);
+                internal_body.set_rotation(rotation.normalize());
+            }
+            let step_len = (new_pos - old_pos).length();
+            if let Some(fraction) = self.ccd_fraction {
+                let max_step = internal_body.shape().min_extent() * fraction;
+                if max_step > 0.
+                    && step_len > max_step
+                    && !internal_body.is_sensor()
+                    && !internal_body.is_ghost()
+                {
+                    drop(internal_body);
+                    let pos = self.sweep(index, old_pos, new_pos, max_step);
+                    body.borrow_mut().set_position(pos);
+                }
+            }
+        }
+        let (_, after) = self.integration_scheme.kick_fractions();
+        self.integrate_forces(body, after);
+    }
+
+    // 扫掠检测：把 from 到 to 的位移切分成长度不超过 max_step 的小段，
+    // 返回第一个与新物体侵入超过允许量的位置，没有则返回 to。
+    // 起点处已经接触的物体（例如正在地面上滑动）和浅接触不会截停扫掠
+    fn sweep(&self, index: usize, from: Vec2, to: Vec2, max_step: Real) -> Vec2 {
+        const MAX_SUB_STEPS: usize = 64;
+        let body = &self.bodies[index];
+        // 只检查包围盒与整段扫掠范围重叠的物体，物体此时位于 to
+        let (min, max) = body.borrow().bounds();
+        let offset = from - to;
+        let mut candidates = vec![];
+        self.query_region_with(min.min(min + offset), max.max(max + offset), |j| {
+            let other = self.bodies[j].borrow();
+            if j != index && !other.is_sensor() && !other.is_ghost() && !other.is_occluder() {
+                candidates.push(j);
+            }
+            true
+        });
+        let slop = LINEAR_SLOP * self.length_scale;
+        let penetration = |j: usize| {
+            let m = Manifold::solve(body.clone(), self.bodies[j].clone(), &self.narrow_phase);
+            (!m.get_contacts().is_empty()).then(|| m.penetration())
+        };
+        body.borrow_mut().set_position(from);
+        candidates.retain(|&j| penetration(j).is_none());
+        let sub_steps = (((to - from).length() / max_step).ceil() as usize).min(MAX_SUB_STEPS);
+        for s in 1..=sub_steps {
+            let pos = from + (to - from) * (s as Real / sub_steps as Real);
+            body.borrow_mut().set_position(pos);
+            if candidates.iter().any(|&j| penetration(j).is_some_and(|depth| depth > slop)) {
+                return pos;
+            }
+        }
+        to
+    }
+}
+
+// 物体的包围盒是否与 `regions` 中的某个区域重叠
+fn overlaps_regions(body: &Body, regions: &[(Vec2, Vec2)]) -> bool {
+    let (min, max) = body.bounds();
+    regions.iter().any(|(region_min, region_max)| {
+        min.x <= region_max.x && max.x >= region_min.x && min.y <= region_max.y && max.y >= region_min.y
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    use crate::{
+        inspect::PropertyValue,
+        narrow_phase::ContactManifold,
+        shape::{Chain, Compound, CustomShape, Fixture, Polygon, Shape},
+        transform::Transform2D,
+        volume::VolumeEffect,
+    };
+
+    fn frictionless(mut body: Body) -> Body {
+        for name in ["static_friction", "dynamic_friction"] {
+            body.set_property(name, PropertyValue::Real(0.)).unwrap();
+        }
+        body
+    }
+
+    fn ground() -> Body {
+        let mut ground = Body::new_aabb(
+            AABB::from_half_extents(Vec2::new(100., 5.)),
+            Vec2::new(0., 50.),
+            0.5,
+        );
+        ground.make_static();
+        frictionless(ground)
+    }
+
+    fn slide(ccd_fraction: Option<Real>) -> (Vec2, Vec2) {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_ccd_fraction(ccd_fraction);
+        world.add_body(ground());
+        let mut ball = frictionless(Body::new_circle(Circle::new(1.), Vec2::new(0., 44.), 0.5));
+        ball.set_velocity(Vec2::new(120., 0.));
+        world.add_body(ball);
+        for _ in 0..10 {
+            world.step();
+        }
+        let ball = world.get_bodies()[1].borrow();
+        (ball.position(), ball.velocity())
+    }
+
+    /// Test cases for:
+    /// * sweep(usize, Vec2, Vec2, Real)
+    #[test]
+    fn sweep_should_ignore_resting_contacts() {
+        let (position, velocity) = slide(Some(0.5));
+        let (expected_position, expected_velocity) = slide(None);
+        assert!(position.x > 19.);
+        assert!((position.x - expected_position.x).abs() < 0.01);
+        assert!((velocity.x - expected_velocity.x).abs() < 0.01);
+    }
+
+    /// Test cases for:
+    /// * sweep(usize, Vec2, Vec2, Real)
+    #[test]
+    fn sweep_should_stop_at_thin_wall() {
+        let run = |ccd_fraction: Option<Real>| {
+            let mut world = World::new(1. / 60., 10, 0.);
+            world.set_ccd_fraction(ccd_fraction);
+            let mut wall = Body::new_aabb(
+                AABB::from_half_extents(Vec2::new(0.1, 10.)),
+                Vec2::new(10., 0.),
+                0.,
+            );
+            wall.make_static();
+            world.add_body(wall);
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.);
+            ball.set_velocity(Vec2::new(1200., 0.));
+            world.add_body(ball);
+            for _ in 0..5 {
+                world.step();
+            }
+            let x = world.get_bodies()[1].borrow().position().x;
+            x
+        };
+        assert!(run(None) > 10.);
+        assert!(run(Some(0.5)) < 10.);
+    }
+
+    fn tile(min: Vec2, max: Vec2) -> Body {
+        let aabb = AABB::from_half_extents((max - min) / 2.);
+        let mut body = Body::new_aabb(aabb, (min + max) / 2., 0.5);
+        body.make_static();
+        body
+    }
+
+    /// Test cases for:
+    /// * merge_static_aabbs()
+    #[test]
+    fn bake_statics_should_merge_tiles() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        for y in 0..100 {
+            for x in 0..100 {
+                let min = Vec2::new(x as Real, y as Real);
+                world.add_body(tile(min, min + Vec2::new(1., 1.)));
+            }
+        }
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(50., -5.), 0.5));
+        world.bake_statics();
+        assert_eq!(world.get_bodies().len(), 2);
+        let (min, max) = world.get_bodies()[0].borrow().bounds();
+        assert!(min.distance(Vec2::ZERO) < 0.001);
+        assert!(max.distance(Vec2::new(100., 100.)) < 0.001);
+        assert!(!world.get_bodies()[1].borrow().is_static());
+    }
+
+    /// Test cases for:
+    /// * World::bake_statics()
+    #[test]
+    fn bake_statics_should_remap_body_indices() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        for x in 0..4 {
+            let min = Vec2::new(x as Real, 0.);
+            world.add_body(tile(min, min + Vec2::new(1., 1.)));
+        }
+        let pin = Vec2::new(10., -10.);
+        world.add_body(Body::new_circle(Circle::new(0.5), pin, 1.));
+        world.add_track_constraint(TrackConstraint::new(4, vec![pin]));
+        world.add_angle_constraint(AngleConstraint::new(4, 0.));
+        // 指向被合并掉的瓦片的约束会被丢弃
+        world.add_track_constraint(TrackConstraint::new(2, vec![pin]));
+        let mut telemetry = Telemetry::new(10);
+        telemetry.track(4);
+        world.set_telemetry(telemetry);
+        world.bake_statics();
+
+        // 四个瓦片合并成一个，小球的下标从 4 变为 1
+        assert_eq!(world.get_bodies().len(), 2);
+        assert_eq!(world.track_constraints().len(), 1);
+        assert_eq!(world.track_constraints()[0].body(), 1);
+        assert_eq!(world.angle_constraints()[0].body(), 1);
+        for _ in 0..120 {
+            world.step();
+        }
+        // 小球仍然被固定在原来的位置，没有在重力作用下掉落
+        assert!(world.get_bodies()[1].borrow().position().distance(pin) < 0.1);
+        assert_eq!(world.telemetry().unwrap().trace(1).unwrap().positions().len(), 10);
+    }
+
+    /// Test cases for:
+    /// * World::bake_statics()
+    /// * World::step()
+    #[test]
+    fn step_should_only_track_unbaked_bodies() {
+        let run = |tiles: usize| {
+            let mut world = World::new(1. / 60., 10, 1.);
+            world.set_broad_phase_kind(BroadPhaseKind::SpatialHash { cell_size: 2. });
+            world.add_body(ground());
+            // 瓦片之间有空隙，不会被合并，全部放入静态 BVH
+            for k in 0..tiles {
+                let min = Vec2::new((k % 100) as Real * 2., 100. + (k / 100) as Real * 2.);
+                world.add_body(tile(min, min + Vec2::new(1., 1.)));
+            }
+            for k in 0..100 {
+                let (x, y) = ((k % 10) as Real, (k / 10) as Real);
+                let position = Vec2::new(x * 2.5 - 12., 42. - y * 2.5);
+                world.add_body(Body::new_circle(Circle::new(1.), position, 0.5));
+            }
+            world.bake_statics();
+            for _ in 0..60 {
+                world.step();
+            }
+            world
+        };
+        let few = run(0);
+        let many = run(10_000);
+        // 每次 step 使用的结构只与没有烘焙的物体有关
+        assert_eq!(many.unbaked.len(), 100);
+        assert!(many.impacts.len() <= 101);
+        // 远处烘焙的瓦片不影响模拟结果
+        let last = |world: &World| world.get_bodies()[world.get_bodies().len() - 100..].to_vec();
+        for (a, b) in last(&few).iter().zip(last(&many)) {
+            assert_eq!(a.borrow().position(), b.borrow().position());
+        }
+        assert!(few.impacts.len() > 1);
+        assert_eq!(few.stats.islands.len(), many.stats.islands.len());
+    }
+
+    /// Test cases for:
+    /// * merge_static_aabbs()
+    #[test]
+    fn bake_statics_should_not_merge_unaligned_tiles() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        // 高度不同
+        world.add_body(tile(Vec2::new(0., 0.), Vec2::new(1., 1.)));
+        world.add_body(tile(Vec2::new(1., 0.), Vec2::new(2., 2.)));
+        // 中间有空隙
+        world.add_body(tile(Vec2::new(0., 10.), Vec2::new(1., 11.)));
+        world.add_body(tile(Vec2::new(1.5, 10.), Vec2::new(2.5, 11.)));
+        // 材质不同
+        world.add_body(tile(Vec2::new(0., 20.), Vec2::new(1., 21.)));
+        let mut bouncy = tile(Vec2::new(1., 20.), Vec2::new(2., 21.));
+        bouncy.set_property("restitution", PropertyValue::Real(0.9)).unwrap();
+        world.add_body(bouncy);
+        world.bake_statics();
+        assert_eq!(world.get_bodies().len(), 6);
+    }
+
+    /// Test cases for:
+    /// * merge_static_aabbs()
+    #[test]
+    fn bake_statics_should_only_merge_tiles_with_same_properties() {
+        let left = || tile(Vec2::new(0., 0.), Vec2::new(1., 1.));
+        let right = || tile(Vec2::new(1., 0.), Vec2::new(2., 1.));
+        let merged_count = |a: Body, b: Body| {
+            let mut world = World::new(1. / 60., 10, 1.);
+            world.add_body(a);
+            world.add_body(b);
+            world.bake_statics();
+            world.get_bodies().len()
+        };
+        let with = |f: &dyn Fn(&mut Body)| {
+            let mut body = right();
+            f(&mut body);
+            body
+        };
+        assert_eq!(merged_count(left(), right()), 1);
+        assert_eq!(merged_count(left(), with(&|b| b.set_sensor(true))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_ghost(true))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_occluder(true))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_report_contacts(true))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_report_sensor_overlaps(true))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_surface_id(3))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_surface_normal(|_, n| n))), 2);
+        assert_eq!(merged_count(left(), with(&|b| b.set_velocity_callback(|v, _| v))), 2);
+
+        // 合并后的物体保留原物体的属性
+        let mut world = World::new(1. / 60., 10, 1.);
+        for mut body in [left(), right()] {
+            body.set_occluder(true);
+            body.set_report_contacts(true);
+            body.set_surface_id(7);
+            world.add_body(body);
+        }
+        world.bake_statics();
+        assert_eq!(world.get_bodies().len(), 1);
+        let body = world.get_bodies()[0].borrow();
+        assert!(body.is_occluder() && body.report_contacts() && body.is_static());
+        assert_eq!(body.surface_id(), 7);
+    }
+
+    /// Test cases for:
+    /// * add_angle_constraint(AngleConstraint)
+    #[test]
+    fn angle_constraint_should_converge_to_target() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        let mut turret = Body::new_circle(Circle::new(1.), Vec2::new(3., 4.), 0.5);
+        turret.set_rotation(Rot::from_angle(3.));
+        turret.set_velocity(Vec2::new(1., 0.));
+        world.add_body(turret);
+        // 沿较短的方向越过 ±π 转到目标角度
+        world.add_angle_constraint(AngleConstraint::new(0, -3.));
+        let mut max_error: Real = 0.;
+        for _ in 0..120 {
+            world.step();
+            let angle = world.get_bodies()[0].borrow().rotation().angle();
+            max_error = max_error.max(shortest_angle_between(-3., angle).abs());
+        }
+        let turret = world.get_bodies()[0].borrow();
+        assert!(shortest_angle_between(-3., turret.rotation().angle()).abs() < 0.001);
+        assert!(turret.angular_velocity().abs() < 0.001);
+        assert!(max_error < 2. * consts::PI - 6. + 0.001);
+        // 角度约束不影响平动
+        assert!((turret.velocity().x - 1.).abs() < 0.0001);
+        drop(turret);
+
+        world.angle_constraints_mut()[0].set_target(0.5);
+        for _ in 0..120 {
+            world.step();
+        }
+        let angle = world.get_bodies()[0].borrow().rotation().angle();
+        assert!((angle - 0.5).abs() < 0.001);
+    }
+
+    /// Test cases for:
+    /// * predict_trajectory(TrajectoryParams, usize)
+    /// * predict_body_trajectory(usize, usize, bool)
+    #[test]
+    fn predicted_trajectory_should_match_simulation() {
+        for scheme in [IntegrationScheme::HalfStep, IntegrationScheme::SemiImplicitEuler] {
+            let mut world = World::new(1. / 60., 10, 1.);
+            world.set_integration_scheme(scheme);
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+            ball.set_velocity(Vec2::new(20., -30.));
+            // 与速度有关的升力
+            ball.set_acceleration(|v, _| v.perp() * 0.5);
+            world.add_body(ball);
+            let predicted = world.predict_body_trajectory(0, 60, false);
+            let params = TrajectoryParams::new(Vec2::ZERO, Vec2::new(20., -30.));
+            let plain = world.predict_trajectory(params, 60);
+            let mut simulated = vec![Vec2::ZERO];
+            for _ in 0..60 {
+                world.step();
+                simulated.push(world.get_bodies()[0].borrow().position());
+            }
+            assert_eq!(predicted.len(), simulated.len());
+            for (p, s) in predicted.iter().zip(&simulated) {
+                assert!(p.distance(*s) < 0.001, "{:?}: {:?} != {:?}", scheme, p, s);
+            }
+            // 不考虑加速度函数时轨迹不同
+            assert!(plain[60].distance(simulated[60]) > 1.);
+        }
+    }
+
+    /// Test cases for:
+    /// * predict_trajectory(TrajectoryParams, usize)
+    #[test]
+    fn predicted_trajectory_should_include_drag() {
+        let drag = 0.8;
+        let mut world = World::new(1. / 60., 10, 1.);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+        ball.set_velocity(Vec2::new(20., -30.));
+        ball.set_acceleration(move |v, _| -v * drag);
+        world.add_body(ball);
+        let params = TrajectoryParams {
+            drag,
+            ..TrajectoryParams::new(Vec2::ZERO, Vec2::new(20., -30.))
+        };
+        let predicted = world.predict_trajectory(params, 90);
+        let params_without_drag = TrajectoryParams::new(params.position, params.velocity);
+        let without_drag = world.predict_trajectory(params_without_drag, 90);
+        for (i, p) in predicted.iter().enumerate().skip(1) {
+            world.step();
+            let position = world.get_bodies()[0].borrow().position();
+            assert!(p.distance(position) < 0.001, "step {}: {:?} != {:?}", i, p, position);
+        }
+        assert!(without_drag[90].distance(predicted[90]) > 1.);
+    }
+
+    fn buried_box(positional_correction: bool, max_speed: Option<Real>) -> Vec<Vec2> {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.set_solver_config(SolverConfig {
+            positional_correction,
+            ..SolverConfig::default()
+        });
+        world.set_max_depenetration_speed(max_speed);
+        world.add_body(ground());
+        let aabb = AABB::from_half_extents(Vec2::new(1., 1.));
+        world.add_body(Body::new_aabb(aabb, Vec2::new(0., 48.), 0.5));
+        let mut positions = vec![world.get_bodies()[1].borrow().position()];
+        for _ in 0..30 {
+            world.step();
+            positions.push(world.get_bodies()[1].borrow().position());
+        }
+        positions
+    }
+
+    /// Test cases for:
+    /// * set_max_depenetration_speed(Option<Real>)
+    #[test]
+    fn depenetration_should_respect_speed_cap() {
+        let dt = 1. / 60.;
+        let capped = buried_box(true, Some(2.));
+        for pair in capped.windows(2) {
+            assert!(pair[0].distance(pair[1]) <= 2. * dt + 0.0001);
+        }
+        assert!(capped[30].y < capped[0].y - 0.5);
+
+        let uncapped = buried_box(true, None);
+        assert!(uncapped[0].distance(uncapped[1]) > 2. * dt * 10.);
+
+        // 默认不做位置修正
+        let disabled = buried_box(false, Some(2.));
+        assert_eq!(disabled[30], disabled[0]);
+    }
+
+    /// Test cases for:
+    /// * set_transforms(&[(usize, Vec2, Real)])
+    #[test]
+    fn set_transforms_should_drive_kinematic_bodies() {
+        let dt = 1. / 60.;
+        let mut world = World::new(dt, 10, 0.);
+        let aabb = AABB::from_half_extents(Vec2::new(2., 0.5));
+        let mut platform = Body::new_aabb(aabb, Vec2::ZERO, 0.5);
+        platform.make_static();
+        world.add_body(platform);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(10., 0.), 0.5));
+        for target in [0.1, 0.2, 0.3] {
+            let transforms = [(0, Vec2::new(target, 0.), 0.), (1, Vec2::new(target, 5.), 0.)];
+            world.set_transforms(&transforms);
+            world.step();
+            let platform = world.get_bodies()[0].borrow();
+            assert!((platform.position().x - target).abs() < 0.0001);
+            assert!((platform.velocity().x - 0.1 / dt).abs() < 0.01);
+        }
+        // 非静态物体不受影响
+        let ball = world.get_bodies()[1].borrow();
+        assert_eq!(ball.position(), Vec2::new(10., 0.));
+        assert_eq!(ball.velocity(), Vec2::ZERO);
+    }
+
+    /// Test cases for:
+    /// * set_transforms(&[(usize, Vec2, Real)])
+    #[test]
+    fn set_transforms_should_spin_kinematic_bodies() {
+        let dt = 1. / 60.;
+        let spin = |angle: Real| {
+            let mut world = World::new(dt, 10, 0.);
+            // 只有考虑转动的摩擦力使用接触点处的速度
+            world.set_solver_config(SolverConfig {
+                torsional_friction: Some(0.),
+                ..SolverConfig::default()
+            });
+            let box_shape = Polygon::new_box(Vec2::new(5., 0.5));
+            let mut platform = Body::new_polygon(box_shape, Vec2::ZERO, 0.5);
+            platform.make_static();
+            world.add_body(platform);
+            let mut ball = Body::new_circle(Circle::new(0.5), Vec2::new(0., -0.99), 0.5);
+            ball.set_velocity(Vec2::new(0., 1.));
+            world.add_body(ball);
+            world.set_transforms(&[(0, Vec2::ZERO, angle)]);
+            let angular_velocity = world.get_bodies()[0].borrow().angular_velocity();
+            world.step();
+            let velocity = world.get_bodies()[1].borrow().velocity();
+            (angular_velocity, velocity)
+        };
+        let (angular_velocity, velocity) = spin(0.1);
+        assert!((angular_velocity - 0.1 / dt).abs() < 0.001);
+        // 转动的平台通过摩擦力带动上面的小球
+        assert!(velocity.x.abs() > 0.01);
+        let (angular_velocity, velocity) = spin(0.);
+        assert_eq!(angular_velocity, 0.);
+        assert!(velocity.x.abs() < 0.0001);
+    }
+
+    /// Test cases for:
+    /// * start_drag(Vec2)
+    /// * update_drag(Vec2)
+    /// * end_drag()
+    #[test]
+    fn drag_should_pull_the_grabbed_point() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.add_body(Body::new_circle(Circle::new(2.), Vec2::new(0., 0.), 0.5));
+        world.add_body(Body::new_circle(Circle::new(2.), Vec2::new(1., 0.), 0.5));
+        assert_eq!(world.start_drag(Vec2::new(10., 0.)), None);
+        // 选中最上层的物体，抓住偏离质心的点
+        let grabbed = Vec2::new(2.5, 0.);
+        assert_eq!(world.start_drag(grabbed), Some(1));
+        let target = Vec2::new(2.5, -10.);
+        world.update_drag(target);
+        for _ in 0..600 {
+            world.step();
+        }
+        let anchor = {
+            let joint = *world.drag_joint().unwrap();
+            joint.anchor(&world.get_bodies()[1].borrow())
+        };
+        assert!(anchor.distance(target) < 0.1);
+        // 拉力作用在抓住的点上，物体会转动
+        let body = world.get_bodies()[1].borrow();
+        assert!(body.rotation().angle().abs() > 0.1);
+        assert!(body.transform().transform_point(Vec2::new(1.5, 0.)).distance(anchor) < 0.001);
+        drop(body);
+
+        world.end_drag();
+        assert!(world.drag_joint().is_none());
+        world.get_bodies()[1].borrow_mut().set_velocity(Vec2::new(1., 0.));
+        world.step();
+        assert_eq!(world.get_bodies()[1].borrow().velocity(), Vec2::new(1., 0.));
+    }
+
+    // 让 `body` 以 10 的速度沿 `chain` 的水平边滑动 `steps` 次，
+    // 检查每次 step 的速度和接触法线都没有突变
+    fn slide_on_chain(chain: Chain, body: Body, steps: usize) {
+        let mut world = World::new(1. / 60., 10, 1.);
+        let mut ground = frictionless(Body::new_chain(chain, Vec2::ZERO, 0.5));
+        ground.make_static();
+        world.add_body(ground);
+        let mut body = frictionless(body);
+        body.set_velocity(Vec2::new(10., 0.));
+        world.add_body(body);
+        let start = world.get_bodies()[1].borrow().position();
+        for step in 0..steps {
+            world.step();
+            let body = world.get_bodies()[1].borrow();
+            assert!((body.velocity().x - 10.).abs() < 0.01, "step {}: {:?}", step, body.velocity());
+            assert!(body.velocity().y.abs() < 0.5, "step {}: {:?}", step, body.velocity());
+            for contact in world.contacts() {
+                assert!(contact.normal.x.abs() < 0.01, "step {}: {:?}", step, contact);
+            }
+        }
+        let end = world.get_bodies()[1].borrow().position();
+        assert!(end.x - start.x > 10. * steps as Real / 60. - 0.1);
+        assert!((end.y - start.y).abs() < 0.1);
+    }
+
+    /// Test cases for:
+    /// * Manifold::chain_2_shape(&Chain)
+    #[test]
+    fn bodies_should_slide_across_chain_vertices() {
+        // 许多共线的短边
+        let points: Vec<Vec2> = (-20..=20).map(|x| Vec2::new(x as Real * 0.5, 0.)).collect();
+        let aabb = AABB::from_half_extents(Vec2::new(0.5, 0.5));
+        let boxed = Body::new_aabb(aabb, Vec2::new(-8., -0.45), 0.5);
+        slide_on_chain(Chain::new(&points), boxed, 90);
+        let polygon = Polygon::new_box(Vec2::new(0.5, 0.5));
+        let polygon = Body::new_polygon(polygon, Vec2::new(-8., -0.45), 0.5);
+        slide_on_chain(Chain::new(&points), polygon, 90);
+        let circle = Body::new_circle(Circle::new(0.5), Vec2::new(-8., -0.45), 0.5);
+        slide_on_chain(Chain::new(&points), circle, 90);
+
+        // 闭合折线的首尾相接处位于底边的中间
+        let mut ring: Vec<Vec2> = (0..20).map(|x| Vec2::new(x as Real * 0.5, 10.)).collect();
+        ring.push(Vec2::new(10., -10.));
+        ring.push(Vec2::new(-10., -10.));
+        ring.extend((-20..0).map(|x| Vec2::new(x as Real * 0.5, 10.)));
+        let aabb = AABB::from_half_extents(Vec2::new(0.5, 0.5));
+        let boxed = Body::new_aabb(aabb, Vec2::new(-6., 9.55), 0.5);
+        slide_on_chain(Chain::new_loop(&ring), boxed, 60);
+    }
+
+    /// Test cases for:
+    /// * Manifold::compound_2_shape(&Compound, &NarrowPhase)
+    #[test]
+    fn compound_should_rest_on_all_touching_fixtures() {
+        let fixture = |half_extents: Vec2, offset: Vec2| {
+            Fixture::new(ShapeType::Polygon(Polygon::new_box(half_extents)), offset, 0.)
+        };
+        // 两个子形状的底边都在地面上
+        let l_shape = Compound::new(&[
+            fixture(Vec2::new(1., 0.5), Vec2::ZERO),
+            fixture(Vec2::new(0.5, 1.), Vec2::new(1.5, -0.5)),
+        ]);
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        world.add_body(Body::new_compound(l_shape, Vec2::new(0., 44.25), 0.5));
+        world.step();
+        let xs: Vec<Real> = world.contacts().iter().map(|c| c.point.x).collect();
+        assert_eq!(xs.len(), 4);
+        assert!(xs.iter().any(|&x| (x + 1.75).abs() < 0.01));
+        assert!(xs.iter().any(|&x| (x - 1.25).abs() < 0.01));
+        for _ in 0..120 {
+            world.step();
+        }
+        let body = world.get_bodies()[1].borrow();
+        assert!((body.position().y - 44.25).abs() < 0.05);
+        assert!(body.rotation().angle().abs() < 0.01);
+        drop(body);
+
+        // 只有竖直的子形状接触地面时，接触属于第二个子形状
+        let hook = Compound::new(&[
+            fixture(Vec2::new(1., 0.5), Vec2::new(0., -1.)),
+            fixture(Vec2::new(0.5, 1.), Vec2::new(1.5, -0.5)),
+        ]);
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        let body = Body::new_compound(hook, Vec2::ZERO, 0.5);
+        let bottom = body.bounds().1.y;
+        world.add_body(body);
+        world.get_bodies()[1].borrow_mut().set_position(Vec2::new(0., 45. - bottom + 0.01));
+        world.step();
+        assert!(!world.contacts().is_empty());
+        for contact in world.contacts() {
+            assert_eq!((contact.a, contact.b), (0, 1));
+            assert_eq!(contact.sub_shape_a, 0);
+            assert_eq!(contact.sub_shape_b, 1);
+        }
+    }
+
+    // 用户自己实现的粗测结构：所有代理保存在列表中两两比较
+    #[derive(Clone, Default)]
+    struct ProxyList {
+        proxies: Vec<(usize, Vec2, Vec2)>,
+    }
+
+    impl BroadPhase for ProxyList {
+        fn insert(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.proxies.push((proxy, min, max));
+        }
+
+        fn remove(&mut self, proxy: usize) {
+            self.proxies.retain(|p| p.0 != proxy);
+        }
+
+        fn update(&mut self, proxy: usize, min: Vec2, max: Vec2) {
+            self.remove(proxy);
+            self.insert(proxy, min, max);
+        }
+
+        fn pairs(&mut self, pairs: &mut Vec<(usize, usize)>) {
+            for (k, a) in self.proxies.iter().enumerate() {
+                for b in &self.proxies[k + 1..] {
+                    if a.2.x >= b.1.x && a.1.x <= b.2.x && a.2.y >= b.1.y && a.1.y <= b.2.y {
+                        pairs.push((a.0, b.0));
+                    }
+                }
+            }
+        }
+
+        fn query(&self, min: Vec2, max: Vec2, callback: &mut dyn FnMut(usize) -> bool) {
+            for p in &self.proxies {
+                let overlaps = p.2.x >= min.x && p.1.x <= max.x && p.2.y >= min.y && p.1.y <= max.y;
+                if overlaps && !callback(p.0) {
+                    return;
+                }
+            }
+        }
+    }
+
+    // 每一步粗测阶段的物体对、区域查询的结果和物体的位置
+    type Frame = (Vec<(usize, usize)>, Vec<usize>, Vec<Vec2>);
+
+    // 一堆物体落到地面上，记录每一步的结果
+    fn pile(broad_phase: Box<dyn BroadPhase>) -> Vec<Frame> {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_broad_phase(broad_phase);
+        world.add_body(ground());
+        for i in 0..24 {
+            let position = Vec2::new((i % 6) as Real * 2.5 - 6., 40. - (i / 6) as Real * 3.);
+            let body = if i % 2 == 0 {
+                Body::new_circle(Circle::new(1.), position, 0.5)
+            } else {
+                Body::new_aabb(AABB::from_half_extents(Vec2::new(1., 1.)), position, 0.5)
+            };
+            world.add_body(body);
+        }
+        let mut pairs = std::collections::BTreeSet::new();
+        let mut frames = vec![];
+        for step in 0..120 {
+            // 中途加入物体、烘焙静态物体，粗测结构需要同步代理的加入和移除
+            if step == 40 {
+                world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 30.), 0.5));
+            }
+            if step == 80 {
+                // 烘焙可能改变物体的下标，之后的物体对重新开始记录
+                world.bake_statics();
+                pairs.clear();
+            }
+            world.step();
+            for event in world.pair_events() {
+                match *event {
+                    PairEvent::Added { a, b } => assert!(pairs.insert((a, b))),
+                    PairEvent::Removed { a, b } => assert!(pairs.remove(&(a, b))),
+                }
+            }
+            let region = world.query_region(Vec2::new(-3., 40.), Vec2::new(3., 46.));
+            let positions = world.get_bodies().iter().map(|b| b.borrow().position()).collect();
+            frames.push((pairs.iter().copied().collect(), region, positions));
+        }
+        frames
+    }
+
+    /// Test cases for:
+    /// * set_broad_phase(Box<dyn BroadPhase>)
+    /// * set_broad_phase_kind(BroadPhaseKind)
+    #[test]
+    fn broad_phases_should_give_same_simulation() {
+        let expected = pile(BroadPhaseKind::BruteForce.create());
+        assert!(expected.iter().any(|(pairs, _, _)| pairs.len() > 10));
+        assert!(expected.iter().any(|(_, region, _)| !region.is_empty()));
+        let broad_phases: Vec<Box<dyn BroadPhase>> = vec![
+            BroadPhaseKind::SpatialHash { cell_size: 2. }.create(),
+            BroadPhaseKind::SweepAndPrune.create(),
+            BroadPhaseKind::DynamicTree { margin: 0.2 }.create(),
+            BroadPhaseKind::Quadtree { max_depth: 8 }.create(),
+            Box::new(ProxyList::default()),
+        ];
+        for broad_phase in broad_phases {
+            assert!(pile(broad_phase) == expected);
+        }
+    }
+
+    /// Test cases for:
+    /// * validate()
+    #[test]
+    fn validate_should_report_invalid_bodies() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5));
+        assert_eq!(world.validate(), []);
+
+        world.add_body(tile(Vec2::new(-1., 44.), Vec2::new(1., 46.)));
+        world.add_body(Body::new_circle(Circle::new(0.), Vec2::ZERO, 0.5));
+        world.add_body(Body::new_circle(Circle::with_density(1., 0.), Vec2::ZERO, 0.5));
+        let zero_size = AABB::new(Vec2::ZERO, Vec2::new(1., 0.));
+        world.add_body(Body::new_aabb(zero_size, Vec2::ZERO, 0.5));
+        let inverted = AABB::new(Vec2::new(1., 1.), Vec2::ZERO);
+        world.add_body(Body::new_aabb(inverted, Vec2::ZERO, 0.5));
+        let nan = Vec2::new(Real::NAN, 0.);
+        world.add_body(Body::new_circle(Circle::new(1.), nan, 0.5));
+
+        let issues = world.validate();
+        assert!(issues.contains(&ValidationIssue::OverlappingStatics { a: 0, b: 2 }));
+        assert!(issues.contains(&ValidationIssue::NonPositiveRadius { body: 3, radius: 0. }));
+        let density = ValidationIssue::NonPositiveDensity { body: 4, density: 0. };
+        assert!(issues.contains(&density));
+        assert!(issues.contains(&ValidationIssue::ZeroSizeAABB { body: 5 }));
+        assert!(issues.contains(&ValidationIssue::InvertedAABB { body: 6 }));
+        assert!(issues.contains(&ValidationIssue::NonFinitePosition { body: 7 }));
+    }
+
+    /// Test cases for:
+    /// * validate()
+    #[test]
+    fn validate_should_report_overlaps_with_baked_statics() {
+        for kind in [BroadPhaseKind::BruteForce, BroadPhaseKind::SpatialHash { cell_size: 2. }] {
+            let mut world = World::new(1. / 60., 10, 1.);
+            world.set_broad_phase_kind(kind);
+            // 瓦片之间有空隙，不会被合并
+            for x in 0..10 {
+                let min = Vec2::new(x as Real * 2., 0.);
+                world.add_body(tile(min, min + Vec2::new(1., 1.)));
+            }
+            world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., -10.), 0.5));
+            world.bake_statics();
+            assert_eq!(world.validate(), []);
+
+            // 烘焙之后加入的静态物体不在 BVH 中，与 BVH 中的瓦片重叠
+            world.add_body(tile(Vec2::new(4.5, 0.5), Vec2::new(5.5, 1.5)));
+            world.add_body(tile(Vec2::new(5.2, 0.8), Vec2::new(5.4, 1.)));
+            let expected = [
+                ValidationIssue::OverlappingStatics { a: 2, b: 11 },
+                ValidationIssue::OverlappingStatics { a: 11, b: 12 },
+            ];
+            assert_eq!(world.validate(), expected);
+        }
+    }
+
+    /// Test cases for:
+    /// * step()
+    #[test]
+    fn step_should_treat_zero_mass_circle_as_static() {
+        let drop_ball = |circle: Circle| {
+            let mut world = World::new(1. / 60., 10, 1.);
+            world.add_body(Body::new_circle(circle, Vec2::new(0., 45.), 0.));
+            world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.));
+            for _ in 0..120 {
+                world.step();
+            }
+            let obstacle = world.get_bodies()[0].borrow();
+            assert!(obstacle.is_static());
+            assert_eq!((obstacle.inverse_mass(), obstacle.inverse_inertia()), (0., 0.));
+            assert_eq!(obstacle.position(), Vec2::new(0., 45.));
+            drop(obstacle);
+            let ball = world.get_bodies()[1].borrow().position();
+            ball
+        };
+        // 密度为 0 的圆是静态的，小球停在它的上方
+        let ball = drop_ball(Circle::with_density(2., 0.));
+        assert!((ball.y - 42.).abs() < 0.1);
+        // 半径为 0 的圆同样是静态的，碰撞之后小球的位置仍然是有限的
+        assert!(drop_ball(Circle::new(0.)).is_finite());
+    }
+
+    fn separate(policy: CoincidentPolicy) -> (Vec2, Vec2) {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.set_solver_config(SolverConfig {
+            positional_correction: true,
+            ..SolverConfig::default()
+        });
+        world.set_coincident_policy(policy);
+        for _ in 0..2 {
+            world.add_body(Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5));
+        }
+        for _ in 0..60 {
+            world.step();
+        }
+        let bodies = world.get_bodies();
+        let (a, b) = (bodies[0].borrow().position(), bodies[1].borrow().position());
+        (a, b)
+    }
+
+    /// Test cases for:
+    /// * set_coincident_policy(CoincidentPolicy)
+    #[test]
+    fn coincident_bodies_should_separate_by_policy() {
+        let (a, b) = separate(CoincidentPolicy::Axis(Vec2::new(0., -1.)));
+        assert!(a.distance(b) >= 1.9);
+        assert!((b - a).normalize().distance(Vec2::new(0., -1.)) < 0.001);
+
+        let (a, b) = separate(CoincidentPolicy::Seeded(7));
+        let expected = CoincidentPolicy::Seeded(7).normal(0, 1);
+        assert!(a.distance(b) >= 1.9);
+        assert!((b - a).normalize().distance(expected) < 0.001);
+        assert_eq!(separate(CoincidentPolicy::Seeded(7)), (a, b));
+    }
+
+
+    // 三个叠放的箱子一起在地面上滑动，返回上面两个箱子的水平速度
+    fn slide_stack(model: FrictionModel) -> (Real, Real) {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_friction_model(model);
+        let mut ground = Body::new_aabb(
+            AABB::from_half_extents(Vec2::new(100., 5.)),
+            Vec2::new(0., 50.),
+            0.5,
+        );
+        ground.make_static();
+        world.add_body(ground);
+        for k in 0..3 {
+            let aabb = AABB::from_half_extents(Vec2::new(1., 1.));
+            let mut body = Body::new_aabb(aabb, Vec2::new(0., 44. - 2. * k as Real), 0.5);
+            body.set_velocity(Vec2::new(5., 0.));
+            world.add_body(body);
+        }
+        for _ in 0..120 {
+            world.step();
+        }
+        let bodies = world.get_bodies();
+        let (a, b) = (bodies[2].borrow().velocity().x, bodies[3].borrow().velocity().x);
+        (a, b)
+    }
+
+    /// Test cases for:
+    /// * set_friction_model(FrictionModel)
+    #[test]
+    fn cone_friction_should_keep_stack_together() {
+        // 累积的切线冲量受累积的法向冲量约束，上面的箱子之间保持相对静止
+        let (middle, top) = slide_stack(FrictionModel::Cone);
+        assert!(middle < 4.);
+        assert!((middle - top).abs() < 0.0001);
+        // 每次迭代单独截断的摩擦力不足以带动最上面的箱子
+        let (middle, top) = slide_stack(FrictionModel::Clamped);
+        assert!((middle - top).abs() > 0.005);
+    }
+
+    /// Test cases for:
+    /// * contacts()
+    #[test]
+    fn contact_age_should_count_steps_in_contact() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        // 不反弹的球静止在地面上，一直保持接触
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 44.), 0.));
+        let mut age = None;
+        for _ in 0..30 {
+            world.step();
+            let contacts = world.contacts();
+            age = contacts.first().map(|contact| {
+                assert_eq!(contacts.len(), 1);
+                assert_eq!(contact.age, age.map_or(0, |age| age + 1));
+                contact.age
+            });
+        }
+        assert!(age.unwrap() > 20);
+
+        // 离开地面之后再次接触，年龄重新从 0 开始
+        world.get_bodies()[1].borrow_mut().set_position(Vec2::new(0., 30.));
+        world.step();
+        assert!(world.contacts().is_empty());
+        world.get_bodies()[1].borrow_mut().set_position(Vec2::new(0., 44.1));
+        world.step();
+        assert_eq!(world.contacts()[0].age, 0);
+    }
+
+    /// Test cases for:
+    /// * largest_impact(usize)
+    /// * accumulated_impulse(usize)
+    #[test]
+    fn impact_queries_should_report_collision_impulse() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 43.8), 0.5);
+        ball.set_velocity(Vec2::new(0., 20.));
+        let mass = ball.mass();
+        world.add_body(ball);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(20., 0.), 0.5));
+
+        // 第一次 step 之后球才与地面重叠
+        world.step();
+        assert_eq!(world.largest_impact(1), 0.);
+        world.step();
+        // 恢复系数为 0.5，冲量使球的速度从向下 20 左右变为向上 10 左右
+        let impact = world.largest_impact(1);
+        assert!((impact - mass * 1.5 * 20.).abs() < mass * 1.5 * 0.5);
+        assert!(world.accumulated_impulse(1) >= impact);
+        assert_eq!(world.largest_impact(0), impact);
+        assert_eq!(world.largest_impact(2), 0.);
+        assert_eq!(world.accumulated_impulse(2), 0.);
+
+        // 弹起之后没有碰撞
+        world.step();
+        assert_eq!(world.largest_impact(1), 0.);
+    }
+
+    /// Test cases for:
+    /// * up()
+    /// * surface_state(usize)
+    /// * set_max_slope(Real)
+    #[test]
+    fn surface_state_should_classify_contacts() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        assert_eq!(world.up(), Some(Vec2::new(0., -1.)));
+        world.add_body(ground());
+        world.add_body(tile(Vec2::new(10., 0.), Vec2::new(12., 45.)));
+        world.add_body(tile(Vec2::new(-30., 0.), Vec2::new(-10., 2.)));
+        // 静止在地面上、水平撞向墙壁、向上撞向天花板的球
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 44.05), 0.));
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(8.9, 20.), 0.);
+        ball.set_velocity(Vec2::new(10., 0.));
+        world.add_body(ball);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(-20., 3.1), 0.);
+        ball.set_velocity(Vec2::new(0., -10.));
+        world.add_body(ball);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(-50., 0.), 0.));
+        world.step();
+        world.step();
+
+        let state = |on_ground, on_wall, on_ceiling| SurfaceState {
+            on_ground,
+            on_wall,
+            on_ceiling,
+        };
+        assert_eq!(world.surface_state(3), state(true, false, false));
+        assert_eq!(world.surface_state(4), state(false, true, false));
+        assert_eq!(world.surface_state(5), state(false, false, true));
+        assert_eq!(world.surface_state(6), state(false, false, false));
+
+        // 重力方向改变之后，原来的地面变成天花板
+        world.set_gravity(Vec2::new(0., -10.));
+        assert_eq!(world.up(), Some(Vec2::new(0., 1.)));
+        assert_eq!(world.surface_state(3), state(false, false, true));
+        // 重力指向墙壁时，墙壁就是地面
+        world.set_gravity(Vec2::new(10., 0.));
+        assert_eq!(world.surface_state(4), state(true, false, false));
+        // 墙壁的坡度为 90°，允许站立的坡度超过 90° 时也算作地面
+        world.set_gravity(Vec2::new(0., 10.));
+        world.set_max_slope(crate::math::consts::PI * 0.6);
+        assert_eq!(world.surface_state(4), state(true, false, false));
+        world.set_gravity(Vec2::ZERO);
+        assert_eq!(world.up(), None);
+        assert_eq!(world.surface_state(3), SurfaceState::default());
+    }
+
+    // 两个球迎面相撞，返回碰撞之后重球和轻球的速度
+    fn collide_heavy_ball(max_mass_ratio: Option<Real>) -> (Real, Real) {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.set_solver_config(SolverConfig {
+            max_mass_ratio,
+            ..SolverConfig::default()
+        });
+        let mut heavy = Body::new_circle(Circle::with_density(1., 100.), Vec2::ZERO, 0.5);
+        heavy.set_velocity(Vec2::new(10., 0.));
+        world.add_body(heavy);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(2.05, 0.), 0.5));
+        for _ in 0..3 {
+            world.step();
+        }
+        let bodies = world.get_bodies();
+        let (heavy, light) = (bodies[0].borrow().velocity().x, bodies[1].borrow().velocity().x);
+        (heavy, light)
+    }
+
+    // 重箱子压在轻箱子上，返回前几次 step 中最大的法向速度残差
+    fn heavy_stack_residual(high_mass_ratio: Real, extra_iterations: u32) -> Real {
+        let mut world = World::new(1. / 60., 1, 1.);
+        world.set_solver_config(SolverConfig {
+            high_mass_ratio,
+            extra_iterations,
+            ..SolverConfig::default()
+        });
+        world.add_body(ground());
+        let mut aabb = AABB::from_half_extents(Vec2::new(1., 1.));
+        world.add_body(Body::new_aabb(aabb, Vec2::new(0., 44.), 0.5));
+        aabb.set_density(100.);
+        world.add_body(Body::new_aabb(aabb, Vec2::new(0., 42.), 0.5));
+        let mut residual: Real = 0.;
+        for _ in 0..30 {
+            world.step();
+            residual = residual.max(world.stats().max_normal_velocity());
+        }
+        residual
+    }
+
+    /// Test cases for:
+    /// * set_solver_config(SolverConfig)
+    #[test]
+    fn solver_config_should_mitigate_mass_ratio() {
+        // 不限制质量比时，重球几乎不减速，轻球以 1.5 倍的速度弹开
+        let (heavy, light) = collide_heavy_ball(None);
+        assert!(heavy > 9.5 && (light - 15.).abs() < 0.5);
+        // 质量比限制为 1 时，按相同质量求解
+        let (heavy, light) = collide_heavy_ball(Some(1.));
+        assert!((heavy - 2.5).abs() < 0.01 && (light - 7.5).abs() < 0.01);
+
+        // 只对质量比超过 `high_mass_ratio` 的岛做额外迭代
+        let residual = heavy_stack_residual(10., 0);
+        assert!(residual > 1.);
+        assert!(heavy_stack_residual(10., 20) < residual / 10.);
+        assert_eq!(heavy_stack_residual(1000., 20), residual);
+    }
+
+    /// Test cases for:
+    /// * changed_bodies()
+    /// * mark_changed(usize)
+    /// * set_change_epsilon(Real)
+    #[test]
+    fn changed_bodies_should_report_moved_and_marked_bodies() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5));
+        // 新加入的物体都算作变化，没有变化时返回空
+        assert_eq!(world.changed_bodies(), [0, 1]);
+        assert!(world.changed_bodies().is_empty());
+
+        world.step();
+        assert_eq!(world.changed_bodies(), [1]);
+        world.mark_changed(0);
+        assert_eq!(world.changed_bodies(), [0]);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(10., 0.), 0.5));
+        assert_eq!(world.changed_bodies(), [2]);
+
+        // 每次 step 移动的距离小于阈值，累积超过阈值之后才报告
+        world.set_gravity(Vec2::ZERO);
+        world.get_bodies()[1].borrow_mut().set_velocity(Vec2::new(1.8, 0.));
+        world.set_change_epsilon(0.1);
+        let mut reported = vec![];
+        for step in 0..12 {
+            world.step();
+            if world.changed_bodies().contains(&1) {
+                reported.push(step);
+            }
+        }
+        assert_eq!(reported, [3, 7, 11]);
+    }
+
+    // 在地面上旋转的球，返回 (每一步的水平速度, 最后的角速度)
+    fn spin_ball(torsional_friction: Option<Real>) -> (Vec<Real>, Real) {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_solver_config(SolverConfig {
+            torsional_friction,
+            ..SolverConfig::default()
+        });
+        world.add_body(ground());
+        world.get_bodies()[0]
+            .borrow_mut()
+            .set_property("static_friction", PropertyValue::Real(0.5))
+            .unwrap();
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 44.), 0.);
+        ball.set_property("static_friction", PropertyValue::Real(0.5)).unwrap();
+        ball.set_angular_velocity(10.);
+        world.add_body(ball);
+        let mut velocities = vec![];
+        for _ in 0..600 {
+            world.step();
+            velocities.push(world.get_bodies()[1].borrow().velocity().x);
+        }
+        let angular_velocity = world.get_bodies()[1].borrow().angular_velocity();
+        (velocities, angular_velocity)
+    }
+
+    /// Test cases for:
+    /// * SolverConfig::torsional_friction
+    #[test]
+    fn torsional_friction_should_roll_spinning_ball() {
+        // 摩擦力不考虑转动时，旋转的球原地打转
+        let (velocities, angular_velocity) = spin_ball(None);
+        assert!(velocities.iter().all(|v| *v == 0.));
+        assert_eq!(angular_velocity, 10.);
+        // 转动通过摩擦力转化为平动，滚动阻力最终使球停下来
+        let (velocities, angular_velocity) = spin_ball(Some(0.05));
+        let max = velocities.iter().fold(0., |max: Real, v| max.max(v.abs()));
+        assert!(max > 1.);
+        assert!(velocities.last().unwrap().abs() < 0.01);
+        assert!(angular_velocity.abs() < 0.01);
+    }
+
+    /// Test cases for:
+    /// * step_observed(FnMut(&SolverIteration))
+    #[test]
+    fn step_observed_should_report_each_iteration() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 44.1), 0.5);
+        ball.set_velocity(Vec2::new(0., 10.));
+        world.add_body(ball);
+        let mut observed = world.fork();
+
+        let mut iterations = vec![];
+        observed.step_observed(|iteration| iterations.push(iteration.clone()));
+        assert_eq!(iterations.len(), 10);
+        let mut last_impulse = 0.;
+        for (k, iteration) in iterations.iter().enumerate() {
+            assert_eq!(iteration.iteration, k);
+            assert_eq!(iteration.velocities.len(), 2);
+            assert_eq!(iteration.contacts.len(), 1);
+            let contact = &iteration.contacts[0];
+            assert_eq!((contact.a, contact.b, contact.normal), (0, 1, Vec2::new(0., -1.)));
+            assert!(contact.normal_impulse > 0. && contact.normal_impulse >= last_impulse);
+            last_impulse = contact.normal_impulse;
+        }
+        // 第一次迭代之后球就开始反弹
+        let (body, velocity) = iterations[0].velocities[1];
+        assert!(body == 1 && velocity.y < 0.);
+
+        // 观察不改变模拟结果
+        world.step();
+        for (a, b) in world.get_bodies().iter().zip(observed.get_bodies()) {
+            assert_eq!(a.borrow().position(), b.borrow().position());
+            assert_eq!(a.borrow().velocity(), b.borrow().velocity());
+        }
+    }
+
+    /// Test cases for:
+    /// * fork()
+    #[test]
+    fn fork_should_not_change_shared_statics() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.5));
+        world.bake_statics();
+        let forked = world.fork();
+        assert!(Rc::ptr_eq(&world.get_bodies()[0], &forked.get_bodies()[0]));
+
+        {
+            let mut ground = forked.get_bodies()[0].borrow_mut();
+            ground.set_mass(5.);
+            ground.set_sensor(true);
+            ground.set_ghost(true);
+            ground.set_occluder(true);
+            ground.set_report_contacts(true);
+            ground.set_report_sensor_overlaps(true);
+            ground.set_surface_id(3);
+            ground.set_surface_normal(|_, _| Vec2::new(1., 0.));
+            ground.set_interpolation(false);
+            ground.set_update_interval(4);
+            ground.set_velocity_callback(|_, _| Vec2::new(1., 0.));
+            ground.set_acceleration(|_, _| Vec2::new(1., 0.));
+            ground.snap();
+        }
+        let ground = world.get_bodies()[0].borrow();
+        assert!(ground.is_static());
+        assert!(ground.interpolates());
+        assert_eq!(ground.update_interval(), 1);
+        // 有速度回调的物体与任何物体的属性都不相同
+        assert!(ground.same_properties(&ground));
+        assert_eq!(ground.acceleration(), Vec2::ZERO);
+        assert!(!ground.is_sensor() && !ground.is_ghost() && !ground.is_occluder());
+        assert!(!ground.report_contacts() && !ground.report_sensor_overlaps());
+        assert_eq!(ground.surface_id(), 0);
+        assert_eq!(ground.surface_normal(Vec2::ZERO, Vec2::new(0., -1.)), Vec2::new(0., -1.));
+
+        // 深拷贝的物体互不影响
+        forked.get_bodies()[1].borrow_mut().set_sensor(true);
+        assert!(!world.get_bodies()[1].borrow().is_sensor());
+    }
+
+    // 以 `scale` 个长度单位为 1 米搭建同一个场景，返回球以米为单位的轨迹
+    fn scaled_drop(scale: Real) -> Vec<Vec2> {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_length_scale(scale);
+        world.set_solver_config(SolverConfig {
+            positional_correction: true,
+            ..SolverConfig::default()
+        });
+        let aabb = AABB::from_half_extents(world.meters_to_units(Vec2::new(100., 5.)));
+        let mut ground = Body::new_aabb(aabb, world.meters_to_units(Vec2::new(0., 50.)), 0.5);
+        ground.make_static();
+        world.add_body(ground);
+        let position = world.meters_to_units(Vec2::new(0., 40.));
+        let ball = Circle::new(world.meters_to_units(Vec2::new(1., 0.)).x);
+        world.add_body(Body::new_circle(ball, position, 0.));
+        let mut trajectory = vec![];
+        for _ in 0..120 {
+            world.step();
+            let position = world.get_bodies()[1].borrow().position();
+            trajectory.push(world.units_to_meters(position));
+        }
+        trajectory
+    }
+
+    /// Test cases for:
+    /// * set_length_scale(Real)
+    /// * meters_to_units(Vec2)
+    /// * units_to_meters(Vec2)
+    #[test]
+    fn length_scale_should_not_change_simulation() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_length_scale(100.);
+        assert_eq!(world.gravity(), Vec2::new(0., 1000.));
+        assert_eq!(world.meters_to_units(Vec2::new(1., 2.)), Vec2::new(100., 200.));
+        assert_eq!(world.units_to_meters(Vec2::new(100., 200.)), Vec2::new(1., 2.));
+        // 不是正的有限数的比例会被忽略
+        for scale in [0., -1., Real::NAN, Real::INFINITY] {
+            world.set_length_scale(scale);
+            assert_eq!(world.length_scale(), 100.);
+            assert_eq!(world.gravity(), Vec2::new(0., 1000.));
+        }
+        assert!((world.broad_phase_margin() - 10.).abs() < 0.0001);
+
+        let meters = scaled_drop(1.);
+        let pixels = scaled_drop(100.);
+        // 球落到地面上并静止
+        assert!((meters.last().unwrap().y - 44.).abs() < 0.1);
+        for (a, b) in meters.iter().zip(&pixels) {
+            assert!(a.distance(*b) < 0.001);
+        }
+    }
+
+    /// Test cases for:
+    /// * impact_events()
+    #[test]
+    fn impact_events_should_report_surfaces_on_first_contact() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        let mut ground = ground();
+        ground.set_surface_id(1);
+        world.add_body(ground);
+        for (x, report) in [(0., true), (10., false)] {
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(x, 43.), 0.);
+            ball.set_surface_id(2);
+            ball.set_report_contacts(report);
+            world.add_body(ball);
+        }
+        let mut impacts = vec![];
+        for _ in 0..60 {
+            world.step();
+            impacts.extend_from_slice(world.impact_events());
+        }
+        // 只有开启上报的球在开始接触时产生一次冲击
+        assert_eq!(impacts.len(), 1);
+        let impact = impacts[0];
+        assert_eq!((impact.a, impact.b), (0, 1));
+        assert_eq!((impact.surface_a, impact.surface_b), (1, 2));
+        assert!(impact.impulse > 0.);
+        assert_eq!(world.contacts().len(), 2);
+    }
+
+    /// Test cases for:
+    /// * set_history_capacity(usize)
+    /// * history_len()
+    /// * rewind(usize)
+    #[test]
+    fn rewind_should_restore_earlier_steps() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::ZERO, 0.5);
+        ball.set_velocity(Vec2::new(3., -5.));
+        ball.set_angular_velocity(2.);
+        world.add_body(ball);
+        assert_eq!(world.rewind(1), 0);
+
+        world.set_history_capacity(5);
+        let state = |world: &World| {
+            let body = world.get_bodies()[0].borrow();
+            (body.position(), body.velocity(), body.rotation().angle())
+        };
+        // states[k] 为第 k 次 step 之后的状态
+        let mut states = vec![state(&world)];
+        for _ in 0..10 {
+            world.step();
+            states.push(state(&world));
+        }
+        assert_eq!(world.history_len(), 5);
+
+        assert_eq!(world.rewind(2), 2);
+        assert_eq!(state(&world), states[8]);
+        assert_eq!(world.history_len(), 3);
+        // 回退之后重新 step 得到相同的结果
+        world.step();
+        assert_eq!(state(&world), states[9]);
+        // 历史记录不足时回退到最早的记录
+        assert_eq!(world.rewind(100), 4);
+        assert_eq!(state(&world), states[5]);
+        assert_eq!(world.history_len(), 0);
+    }
+
+    /// Test cases for:
+    /// * Body::set_update_interval(u32)
+    /// * add_activation_region(Vec2, Vec2)
+    #[test]
+    fn update_interval_should_skip_steps_outside_activation_regions() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        for y in [0., 10.] {
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., y), 0.5);
+            ball.set_velocity(Vec2::new(6., 0.));
+            ball.set_update_interval(4);
+            world.add_body(ball);
+        }
+        // 第二个球在激活区域中，总是以完整频率更新
+        world.add_activation_region(Vec2::new(-100., 5.), Vec2::new(100., 15.));
+        let mut moves = vec![];
+        for _ in 0..8 {
+            let x = world.get_bodies()[0].borrow().position().x;
+            world.step();
+            moves.push(world.get_bodies()[0].borrow().position().x > x);
+            let active = world.get_bodies()[1].borrow().position().x;
+            assert!((active - world.step_count as Real * 0.1).abs() < 0.0001);
+        }
+        // 每 4 次 step 更新一次，每次前进 4 个 dt
+        assert_eq!(moves.iter().filter(|m| **m).count(), 2);
+        assert!((world.get_bodies()[0].borrow().position().x - 0.8).abs() < 0.0001);
+    }
+
+    /// Test cases for:
+    /// * Body::set_ghost(bool)
+    /// * ignores_pair(&Body, &Body)
+    #[test]
+    fn ghost_should_pass_through_bodies_but_stay_visible() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.);
+        ball.set_ghost(true);
+        world.add_body(ball);
+        let mut sensor = tile(Vec2::new(-2., 38.), Vec2::new(2., 42.));
+        sensor.set_sensor(true);
+        sensor.set_report_sensor_overlaps(true);
+        world.add_body(sensor);
+
+        let hit = world.ray_cast(Vec2::new(0., 0.), Vec2::new(0., 1.), 100.);
+        assert_eq!(hit.map(|(body, _)| body), Some(1));
+        assert!(world.query_region(Vec2::new(-1., 39.), Vec2::new(1., 41.)).contains(&1));
+
+        world.step();
+        let entered = CollisionEvent::SensorEntered { sensor: 2, other: 1 };
+        assert!(world.events().contains(&entered));
+        for _ in 0..180 {
+            world.step();
+        }
+        assert!(world.get_bodies()[1].borrow().position().y > 60.);
+    }
+
+    /// Test cases for:
+    /// * queue_impulse(usize, Vec2)
+    /// * queue_force(usize, Vec2)
+    #[test]
+    fn queued_commands_should_apply_on_next_step() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.));
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(10., 0.), 0.));
+        let inverse_mass = world.get_bodies()[0].borrow().inverse_mass();
+
+        let shared = &world;
+        shared.queue_impulse(0, Vec2::new(2., 0.));
+        shared.queue_force(1, Vec2::new(0., 60.));
+        shared.queue_impulse(5, Vec2::new(2., 0.));
+        assert_eq!(world.get_bodies()[0].borrow().velocity(), Vec2::new(0., 0.));
+
+        world.step();
+        let impulsed = world.get_bodies()[0].borrow().velocity();
+        let forced = world.get_bodies()[1].borrow().velocity();
+        assert!((impulsed.x - 2. * inverse_mass).abs() < 1e-4);
+        assert!((forced.y - inverse_mass).abs() < 1e-4);
+
+        world.step();
+        assert_eq!(world.get_bodies()[0].borrow().velocity(), impulsed);
+        assert_eq!(world.get_bodies()[1].borrow().velocity(), forced);
+    }
+
+    /// Test cases for:
+    /// * Body::set_acceleration(F)
+    /// * Body::clear_acceleration()
+    #[test]
+    fn acceleration_should_depend_on_velocity() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.);
+        ball.set_velocity(Vec2::new(10., 0.));
+        ball.set_angular_velocity(2.);
+        ball.set_acceleration(|velocity, angular_velocity| {
+            Vec2::new(-velocity.x, angular_velocity)
+        });
+        world.add_body(ball);
+        for _ in 0..60 {
+            world.step();
+        }
+        let velocity = world.get_bodies()[0].borrow().velocity();
+        assert!((velocity.x - 10. * (-1. as Real).exp()).abs() < 0.1);
+        assert!((velocity.y - 2.).abs() < 0.01);
+
+        world.get_bodies()[0].borrow_mut().clear_acceleration();
+        world.step();
+        assert_eq!(world.get_bodies()[0].borrow().velocity(), velocity);
+    }
+
+    // 中间的圆与上方两个方向相近的圆和下方一个圆重叠，返回第一次迭代时参与求解的物体对
+    fn packed_circles(reduction: Option<Real>) -> Vec<(usize, usize)> {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.set_solver_config(SolverConfig {
+            circle_contact_reduction: reduction,
+            ..SolverConfig::default()
+        });
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.5));
+        let neighbours: [(Real, Real); 3] = [(-0.1, 1.9), (0.1, 1.95), (consts::PI, 1.95)];
+        for (angle, distance) in neighbours {
+            let position = Vec2::new(angle.sin(), -angle.cos()) * distance;
+            let mut circle = Body::new_circle(Circle::new(1.), position, 0.5);
+            circle.make_static();
+            world.add_body(circle);
+        }
+        let mut pairs = vec![];
+        world.step_observed(|iteration| {
+            if iteration.iteration == 0 {
+                pairs = iteration.contacts.iter().map(|c| (c.a, c.b)).collect();
+            }
+        });
+        pairs.sort();
+        pairs
+    }
+
+    /// Test cases for:
+    /// * reduce_circle_contacts(&mut Vec<(usize, usize, Manifold)>, Real)
+    #[test]
+    fn circle_contact_reduction_should_keep_deepest_similar_contact() {
+        let all = packed_circles(None);
+        assert_eq!(all.len(), 3);
+        let reduced = packed_circles(Some(0.5));
+        assert_eq!(reduced.len(), 2);
+        assert!(reduced.iter().all(|pair| *pair != (0, 2) && *pair != (2, 0)));
+        assert_eq!(packed_circles(Some(0.1)).len(), 3);
+    }
+
+    /// Test cases for:
+    /// * set_active_regions(&[AABB])
+    /// * clear_active_regions()
+    #[test]
+    fn active_regions_should_freeze_bodies_outside() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        for x in [0., 100.] {
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(x, 0.), 0.);
+            ball.set_velocity(Vec2::new(6., 0.));
+            world.add_body(ball);
+        }
+        // 第二个球与墙重叠，冻结时不会被墙推开
+        world.add_body(tile(Vec2::new(100.5, -5.), Vec2::new(101.5, 5.)));
+        world.set_active_regions(&[AABB::new(Vec2::new(-5., -5.), Vec2::new(5., 5.))]);
+        for _ in 0..10 {
+            world.step();
+        }
+        let x = |world: &World, i: usize| world.get_bodies()[i].borrow().position().x;
+        assert!((x(&world, 0) - 1.).abs() < 1e-4);
+        assert_eq!(x(&world, 1), 100.);
+        assert_eq!(world.get_bodies()[1].borrow().velocity(), Vec2::new(6., 0.));
+
+        world.clear_active_regions();
+        world.step();
+        assert!(world.get_bodies()[1].borrow().velocity().x < 6.);
+    }
+
+    /// Test cases for:
+    /// * Body::interpolated_transform(Real)
+    /// * Body::set_interpolation(bool)
+    /// * Body::snap()
+    #[test]
+    fn interpolation_should_blend_previous_and_current_transform() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.);
+        ball.set_velocity(Vec2::new(6., 0.));
+        world.add_body(ball);
+        world.step();
+        world.step();
+        let ball = world.get_bodies()[0].clone();
+        let translation = |alpha: Real| ball.borrow().interpolated_transform(alpha).translation;
+        assert!((translation(0.).x - 0.1).abs() < 1e-4);
+        assert!((translation(0.5).x - 0.15).abs() < 1e-4);
+        assert!((translation(1.).x - 0.2).abs() < 1e-4);
+
+        ball.borrow_mut().set_position(Vec2::new(50., 0.));
+        assert!((translation(0.5).x - 25.05).abs() < 1e-3);
+        ball.borrow_mut().snap();
+        assert_eq!(translation(0.5), Vec2::new(50., 0.));
+
+        world.step();
+        ball.borrow_mut().set_interpolation(false);
+        assert_eq!(translation(0.), ball.borrow().position());
+    }
+
+    /// Test cases for:
+    /// * AABB::new(Vec2, Vec2)
+    /// * aabb_2_circle(&AABB, &Circle)
+    #[test]
+    fn aabb_should_be_defined_in_body_local_space() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        let aabb = AABB::new(Vec2::new(-100., 45.), Vec2::new(100., 55.));
+        let mut platform = frictionless(Body::new_aabb(aabb, Vec2::new(0., 0.), 0.5));
+        platform.make_static();
+        world.add_body(platform);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.));
+
+        assert_eq!(world.get_bodies()[0].borrow().bounds().0, Vec2::new(-100., 45.));
+        let hit = world.ray_cast(Vec2::new(5., 0.), Vec2::new(0., 1.), 100.);
+        assert_eq!(hit, Some((0, 45.)));
+        for _ in 0..120 {
+            world.step();
+        }
+        let y = world.get_bodies()[1].borrow().position().y;
+        assert!((y - 44.).abs() < 0.1);
+    }
+
+    /// Test cases for:
+    /// * Body::set_occluder(bool)
+    /// * line_of_sight(Vec2, Vec2, &[usize])
+    #[test]
+    fn occluder_should_block_sight_but_not_bodies() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        let mut window = tile(Vec2::new(9.9, -10.), Vec2::new(10.1, 10.));
+        window.set_occluder(true);
+        world.add_body(window);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.);
+        ball.set_velocity(Vec2::new(60., 0.));
+        world.add_body(ball);
+
+        let (from, to) = (Vec2::new(0., 5.), Vec2::new(20., 5.));
+        assert!(!world.line_of_sight(from, to, &[]));
+        assert!(world.line_of_sight(from, to, &[0]));
+        assert_eq!(world.ray_cast(from, to - from, 1.).map(|(body, _)| body), Some(0));
+
+        for _ in 0..30 {
+            world.step();
+        }
+        let ball = world.get_bodies()[1].borrow();
+        assert!(ball.position().x > 20.);
+        assert_eq!(ball.velocity(), Vec2::new(60., 0.));
+    }
+
+    /// Test cases for:
+    /// * ray_cast_hit(Vec2, Vec2, Real)
+    /// * impact_events()
+    #[test]
+    fn sub_shapes_should_be_reported_by_queries_and_impacts() {
+        let plank = |x: Real| {
+            let shape = ShapeType::Polygon(Polygon::new_box(Vec2::new(2., 1.)));
+            Fixture::new(shape, Vec2::new(x, 0.), 0.)
+        };
+        let mut bridge = Body::new_compound(
+            Compound::new(&[plank(-5.), plank(5.)]),
+            Vec2::new(0., 46.),
+            0.5,
+        );
+        bridge.make_static();
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(bridge);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(5., 43.), 0.);
+        ball.set_report_contacts(true);
+        world.add_body(ball);
+
+        for (x, sub_shape) in [(-5., 0), (5., 1)] {
+            let hit = world.ray_cast_hit(Vec2::new(x, 50.), Vec2::new(0., -1.), 10.).unwrap();
+            assert_eq!((hit.body, hit.sub_shape), (0, sub_shape));
+            assert!((hit.t - 3.).abs() < 1e-4);
+        }
+
+        let mut impacts = vec![];
+        for _ in 0..60 {
+            world.step();
+            impacts.extend_from_slice(world.impact_events());
+        }
+        assert_eq!(impacts.len(), 1);
+        assert_eq!((impacts[0].sub_shape_a, impacts[0].sub_shape_b), (1, 0));
+    }
+
+    /// Test cases for:
+    /// * query_region_with(Vec2, Vec2, F)
+    /// * ray_cast_with(Vec2, Vec2, Real, F)
+    #[test]
+    fn query_callbacks_should_visit_each_body_and_stop_early() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        for x in 0..10 {
+            world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(x as Real * 3., 0.), 0.));
+        }
+        world.add_body(tile(Vec2::new(30., -1.), Vec2::new(32., 1.)));
+        world.bake_statics();
+        world.get_bodies()[5].borrow_mut().set_sensor(true);
+
+        let (min, max) = (Vec2::new(-1., -1.), Vec2::new(40., 1.));
+        let mut visited = vec![];
+        world.query_region_with(min, max, |i| {
+            // 在回调中可以再次查询
+            assert_eq!(world.query_region(min, max).len(), 11);
+            visited.push(i);
+            true
+        });
+        visited.sort_unstable();
+        assert_eq!(visited, (0..11).collect::<Vec<_>>());
+        let mut count = 0;
+        world.query_region_with(min, max, |_| {
+            count += 1;
+            count < 3
+        });
+        assert_eq!(count, 3);
+
+        let mut hits = vec![];
+        world.ray_cast_with(Vec2::new(-5., 0.), Vec2::new(1., 0.), 50., |hit| {
+            hits.push(hit.body);
+            true
+        });
+        hits.sort_unstable();
+        assert_eq!(hits, vec![0, 1, 2, 3, 4, 6, 7, 8, 9, 10]);
+        let mut count = 0;
+        world.ray_cast_with(Vec2::new(-5., 0.), Vec2::new(1., 0.), 50., |_| {
+            count += 1;
+            false
+        });
+        assert_eq!(count, 1);
+    }
+
+    /// Test cases for:
+    /// * animate_gravity(Vec2, Vec2, Real)
+    /// * set_gravity(Vec2)
+    #[test]
+    fn gravity_should_ramp_over_duration() {
+        let mut world = World::new(0.25, 10, 1.);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.));
+        world.animate_gravity(Vec2::new(0., 10.), Vec2::new(0., -10.), 1.);
+        assert_eq!(world.gravity(), Vec2::new(0., 10.));
+        let mut gravities = vec![];
+        while world.is_gravity_animating() {
+            world.step();
+            gravities.push(world.gravity().y);
+        }
+        assert_eq!(gravities, vec![5., 0., -5., -10.]);
+        // 物体在渐变过程中受到变化的重力，最终速度为 (5 + 0 - 5 - 10) * 0.25
+        let velocity = world.get_bodies()[0].borrow().velocity();
+        assert!((velocity.y + 2.5).abs() < 1e-4);
+
+        world.animate_gravity(Vec2::new(0., 10.), Vec2::new(0., -10.), 1.);
+        world.set_gravity(Vec2::new(3., 0.));
+        world.step();
+        assert!(!world.is_gravity_animating());
+        assert_eq!(world.gravity(), Vec2::new(3., 0.));
+        world.animate_gravity(Vec2::ZERO, Vec2::new(0., 1.), 0.);
+        assert_eq!(world.gravity(), Vec2::new(0., 1.));
+    }
+
+    /// Test cases for:
+    /// * add_volume(ForceVolume)
+    /// * apply_volumes()
+    #[test]
+    fn volumes_should_affect_overlapping_dynamic_bodies() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        for x in [0., 20., 40.] {
+            world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(x, 0.), 0.));
+        }
+        let mut fixed = Body::new_circle(Circle::new(1.), Vec2::new(2., 0.), 0.);
+        fixed.make_static();
+        world.add_body(fixed);
+        let conveyor = VolumeEffect::Velocity { velocity: Vec2::new(5., 0.), rate: 1000. };
+        world.add_volume(ForceVolume::new(Vec2::new(-3., -3.), Vec2::new(3., 3.), conveyor));
+        let wind = VolumeEffect::Force(Vec2::new(0., -60.));
+        world.add_volume(ForceVolume::new(Vec2::new(18., -3.), Vec2::new(22., 3.), wind));
+        let inverse_mass = world.get_bodies()[0].borrow().inverse_mass();
+
+        world.step();
+        let velocity = |world: &World, i: usize| world.get_bodies()[i].borrow().velocity();
+        assert_eq!(velocity(&world, 0), Vec2::new(5., 0.));
+        assert!((velocity(&world, 1).y + inverse_mass).abs() < 1e-4);
+        assert_eq!(velocity(&world, 2), Vec2::ZERO);
+        assert_eq!(velocity(&world, 3), Vec2::ZERO);
+
+        world.volumes_mut()[1].set_position(Vec2::new(38., -3.));
+        world.step();
+        assert!((velocity(&world, 1).y + inverse_mass).abs() < 1e-4);
+        assert!((velocity(&world, 2).y + inverse_mass).abs() < 1e-4);
+    }
+
+    /// Test cases for:
+    /// * Body::scale_shape(Real)
+    #[test]
+    fn scaled_shape_should_update_mass_and_contacts() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_solver_config(SolverConfig {
+            positional_correction: true,
+            ..SolverConfig::default()
+        });
+        world.add_body(ground());
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 44.), 0.));
+        let mass = world.get_bodies()[1].borrow().mass();
+        for _ in 0..30 {
+            world.step();
+        }
+
+        world.get_bodies()[1].borrow_mut().scale_shape(2.);
+        world.get_bodies()[0].borrow_mut().scale_shape(0.5);
+        assert!((world.get_bodies()[1].borrow().mass() - 4. * mass).abs() < 1e-3);
+        assert!(world.get_bodies()[0].borrow().is_static());
+        // 地面缩小后顶部在 47.5，放大的球被推到地面上方
+        for _ in 0..120 {
+            world.step();
+        }
+        let y = world.get_bodies()[1].borrow().position().y;
+        assert!((y - 45.5).abs() < 0.1);
+    }
+
+    /// Test cases for:
+    /// * register_template(BodyTemplate)
+    /// * spawn(usize, Vec2)
+    #[test]
+    fn spawn_should_copy_template_prototype() {
+        let mut prototype = Body::new_circle(Circle::new(0.5), Vec2::new(100., 100.), 0.8);
+        prototype.set_velocity(Vec2::new(30., 0.));
+        prototype.set_surface_id(7);
+        prototype.set_report_contacts(true);
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.add_body(ground());
+        let bullet = world.register_template(BodyTemplate::new(prototype));
+        assert_eq!(world.template(bullet).unwrap().prototype().surface_id(), 7);
+        assert!(world.template(bullet + 1).is_none());
+        assert_eq!(world.spawn(bullet + 1, Vec2::ZERO), None);
+
+        assert_eq!(world.spawn(bullet, Vec2::new(0., 0.)), Some(1));
+        assert_eq!(world.spawn(bullet, Vec2::new(0., 10.)), Some(2));
+        for (i, y) in [(1, 0.), (2, 10.)] {
+            let body = world.get_bodies()[i].borrow();
+            assert_eq!(body.position(), Vec2::new(0., y));
+            assert_eq!(body.velocity(), Vec2::new(30., 0.));
+            assert_eq!(body.restitution(), 0.8);
+            assert_eq!(body.surface_id(), 7);
+            assert!(body.report_contacts());
+        }
+        // 实例之间互不影响
+        world.get_bodies()[1].borrow_mut().set_surface_id(3);
+        assert_eq!(world.get_bodies()[2].borrow().surface_id(), 7);
+        assert_eq!(world.template(bullet).unwrap().prototype().surface_id(), 7);
+    }
+
+    // 无限宽的地面，局部坐标系中 y >= 0 的部分
+    struct Floor;
+
+    impl Shape for Floor {
+        fn mass(&self) -> Real {
+            0.
+        }
+
+        fn inertia(&self) -> Real {
+            0.
+        }
+
+        fn area(&self) -> Real {
+            0.
+        }
+
+        fn centroid(&self) -> Vec2 {
+            Vec2::ZERO
+        }
+
+        fn perimeter(&self) -> Real {
+            0.
+        }
+
+        fn contains_point(&self, point: Vec2) -> bool {
+            point.y >= 0.
+        }
+    }
+
+    impl CustomShape for Floor {
+        fn kind(&self) -> u32 {
+            1
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn bounds(&self, transform: &Transform2D) -> (Vec2, Vec2) {
+            let y = transform.translation.y;
+            (Vec2::new(-1e6, y), Vec2::new(1e6, y + 1e6))
+        }
+    }
+
+    fn floor_2_circle(floor: &Body, ball: &Body) -> Option<ContactManifold> {
+        let ShapeType::Circle(circle) = ball.shape() else {
+            return None;
+        };
+        let top = floor.position().y;
+        let penetration = ball.position().y + circle.radius() - top;
+        (penetration > 0.).then(|| ContactManifold {
+            normal: Vec2::new(0., -1.),
+            penetration,
+            points: vec![Vec2::new(ball.position().x, top)],
+        })
+    }
+
+    /// Test cases for:
+    /// * register_collision(ShapeKind, ShapeKind, CollideFn)
+    #[test]
+    fn registered_collision_should_drive_custom_shapes() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_solver_config(SolverConfig {
+            positional_correction: true,
+            ..SolverConfig::default()
+        });
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.));
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(10., 40.), 0.));
+        let mut floor = Body::new_custom(Rc::new(Floor), Vec2::new(0., 45.), 0.5);
+        floor.make_static();
+        world.add_body(floor);
+        // 第二个球下方的 AABB 地面，内置的检测被覆盖为不碰撞
+        world.add_body(tile(Vec2::new(5., 42.), Vec2::new(15., 44.)));
+        let never: CollideFn = |_, _| None;
+        world.register_collision(ShapeKind::Circle, ShapeKind::AABB, never);
+        world.register_collision(ShapeKind::Custom(1), ShapeKind::Circle, floor_2_circle);
+
+        for _ in 0..120 {
+            world.step();
+        }
+        for i in 0..2 {
+            let y = world.get_bodies()[i].borrow().position().y;
+            assert!((y - 44.).abs() < 0.1);
+        }
+        // 球是物体 A，注册的函数交换两个物体调用后法线反向
+        let contact = world.contacts().iter().find(|c| c.a == 0).unwrap();
+        assert_eq!(contact.b, 2);
+        assert_eq!(contact.normal, Vec2::new(0., 1.));
+
+        world.get_bodies()[1].borrow_mut().set_position(Vec2::new(10., 43.));
+        world.step();
+        assert!(world.contacts().iter().all(|c| c.b != 3));
+    }
+
+    // 一个球向另一个静止的球运动，返回第一次报告 `PairEvent::Added` 的 step
+    fn first_pair_step(margin: Real) -> usize {
+        let mut world = World::new(1. / 60., 10, 0.);
+        world.set_broad_phase_margin(margin);
+        world.set_broad_phase_kind(BroadPhaseKind::SweepAndPrune);
+        for (x, vx) in [(0., 6.), (10.05, 0.)] {
+            let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(x, 0.), 0.);
+            ball.set_velocity(Vec2::new(vx, 0.));
+            world.add_body(ball);
+        }
+        (1..200)
+            .find(|_| {
+                world.step();
+                world.pair_events().contains(&PairEvent::Added { a: 0, b: 1 })
+            })
+            .unwrap()
+    }
+
+    /// Test cases for:
+    /// * set_broad_phase_margin(Real)
+    /// * broad_phase_margin()
+    #[test]
+    fn broad_phase_margin_should_fatten_proxies() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        assert_eq!(world.broad_phase_margin(), 0.1);
+        world.set_broad_phase_margin(0.5);
+        world.set_broad_phase_kind(BroadPhaseKind::Quadtree { max_depth: 8 });
+        assert_eq!(world.broad_phase_margin(), 0.5);
+        world.set_length_scale(10.);
+        assert_eq!(world.broad_phase_margin(), 5.);
+
+        // 球之间的间隙为 8.05，每个 step 缩小 0.1，在第 81 次 step 之后重叠，
+        // 粗测在积分之前进行，因此第 82 次 step 才报告。
+        // 静止的球的包围盒扩大 margin，运动的球的包围盒只在移出时更新，朝运动方向多出 0 到 margin
+        assert_eq!(first_pair_step(0.), 82);
+        for margin in [1., 2.] {
+            let step = first_pair_step(margin);
+            let earliest = 81 - (margin * 20.) as usize;
+            assert!(step >= earliest && step <= 82 - (margin * 10.) as usize, "{step}");
+        }
+    }
+
+    /// Test cases for:
+    /// * pair_events()
+    #[test]
+    fn pair_events_should_report_changes_only() {
+        let mut world = World::new(1. / 60., 10, 0.);
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 0.), 0.);
+        ball.set_velocity(Vec2::new(30., 0.));
+        world.add_body(ball);
+        let mut sensor = Body::new_circle(Circle::new(1.), Vec2::new(5., 0.2), 0.);
+        sensor.make_static();
+        sensor.set_sensor(true);
+        sensor.set_report_sensor_overlaps(true);
+        world.add_body(sensor);
+        world.add_body(Body::new_circle(Circle::new(1.), Vec2::new(5., -30.), 0.));
+
+        let mut history = vec![];
+        for step in 0..120 {
+            world.step();
+            for event in world.events() {
+                history.push((step, format!("{event:?}")));
+            }
+            for event in world.pair_events() {
+                history.push((step, format!("{event:?}")));
+            }
+        }
+        // 每个物体对只新增、移除一次，离开传感器不晚于物体对被移除
+        let names: Vec<&str> = history.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Added { a: 0, b: 1 }",
+                "SensorEntered { sensor: 1, other: 0 }",
+                "SensorExited { sensor: 1, other: 0 }",
+                "Removed { a: 0, b: 1 }",
+            ]
+        );
+        assert!(history[2].0 <= history[3].0);
+    }
+
+    /// Test cases for:
+    /// * bake_statics()
+    /// * sweep(usize, Vec2, Vec2, Real)
+    #[test]
+    fn baked_statics_should_be_skipped_but_still_collide() {
+        let mut world = World::new(1. / 60., 10, 1.);
+        world.set_ccd_fraction(Some(0.5));
+        world.add_body(ground());
+        // 用速度回调统计静态物体在 step 中被遍历的次数
+        let visits = Rc::new(std::cell::Cell::new(0));
+        let mut platform = tile(Vec2::new(-30., 0.), Vec2::new(-20., 1.));
+        let counter = visits.clone();
+        platform.set_velocity_callback(move |velocity, _| {
+            counter.set(counter.get() + 1);
+            velocity
+        });
+        world.add_body(platform);
+        world.add_body(tile(Vec2::new(10., 30.), Vec2::new(10.2, 44.)));
+        world.step();
+        assert_eq!(visits.get(), 1);
+
+        world.bake_statics();
+        let mut ball = Body::new_circle(Circle::new(1.), Vec2::new(0., 40.), 0.);
+        ball.set_velocity(Vec2::new(1200., 0.));
+        world.add_body(ball);
+        for _ in 0..120 {
+            world.step();
         }
-        // 为了稳定？
-        self.integrate_forces(body);
+        // 烘焙之后不再遍历静态物体，球被烘焙过的薄墙挡住并落在地面上
+        assert_eq!(visits.get(), 1);
+        let ball = world.get_bodies()[3].borrow().position();
+        assert!(ball.x < 10.);
+        assert!((ball.y - 44.).abs() < 0.2);
     }
 }